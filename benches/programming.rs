@@ -0,0 +1,83 @@
+//! Cost of the frequency conversion and of programming the device over a bus doing nothing
+//!
+//! `cargo bench --target <host triple>`
+
+use core::convert::Infallible;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use embedded_hal::{blocking::i2c::Write, digital::v2::OutputPin};
+use ltc690x::{freq_to_reg, reg_to_freq, Address, OutputMode, LTC6904};
+
+/// One frequency per octave, away from the octave boundaries
+const FREQUENCIES: [u32; 16] = [
+    1_500, 3_000, 6_000, 12_000, 24_000, 48_000, 96_000, 192_000, 384_000, 768_000, 1_536_000,
+    3_072_000, 6_144_000, 12_288_000, 24_576_000, 49_152_000,
+];
+
+struct NoopI2c;
+
+impl Write for NoopI2c {
+    type Error = Infallible;
+
+    fn write(&mut self, _: u8, _: &[u8]) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+struct NoopPin;
+
+impl OutputPin for NoopPin {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+fn conversion(c: &mut Criterion) {
+    c.bench_function("freq_to_reg", |b| {
+        b.iter(|| {
+            for f in FREQUENCIES {
+                black_box(freq_to_reg(black_box(f)).unwrap());
+            }
+        })
+    });
+    c.bench_function("reg_to_freq", |b| {
+        let regs = FREQUENCIES.map(|f| freq_to_reg(f).unwrap());
+        b.iter(|| {
+            for reg in regs {
+                black_box(reg_to_freq(black_box(reg)));
+            }
+        })
+    });
+}
+
+fn programming(c: &mut Criterion) {
+    let mut ltc = LTC6904::new(NoopI2c, Address::AddressLow, NoopPin);
+    ltc.set_output_mode(OutputMode::BothOutputs);
+    c.bench_function("set_frequency + write_out", |b| {
+        b.iter(|| {
+            for f in FREQUENCIES {
+                ltc.set_frequency(black_box(f)).unwrap();
+                ltc.write_out().unwrap();
+            }
+        })
+    });
+
+    ltc.set_calibration_ppm(-1_234).unwrap();
+    c.bench_function("calibrated set_frequency + write_out", |b| {
+        b.iter(|| {
+            for f in FREQUENCIES {
+                ltc.set_frequency(black_box(f)).unwrap();
+                ltc.write_out().unwrap();
+            }
+        })
+    });
+}
+
+criterion_group!(benches, conversion, programming);
+criterion_main!(benches);
@@ -0,0 +1,66 @@
+//! Embassy on the nRF52840 sweeping an LTC6904
+//!
+//! SCL on P0.28, SDA on P0.29, OE on P1.01, ADR strapped low, as in `examples/nrf52840.rs`.
+//!
+//! The driver only implements the blocking embedded-hal traits so far, so the bus transfers
+//! block the executor for the 2 byte write (about 0.3 ms at 100 kHz). Everything else, pauses
+//! and gating included, runs asynchronously in the `oscillator` task.
+
+#![no_std]
+#![no_main]
+
+use defmt_rtt as _;
+use panic_probe as _;
+
+use embassy_executor::Spawner;
+use embassy_nrf::{
+    bind_interrupts,
+    gpio::{Level, Output, OutputDrive},
+    peripherals::TWISPI0,
+    twim::{self, Twim},
+};
+use embassy_time::Timer;
+use ltc690x::{sweep, Address, OutputMode, LTC6904};
+
+bind_interrupts!(struct Irqs {
+    TWISPI0 => twim::InterruptHandler<TWISPI0>;
+});
+
+type Ltc = LTC6904<Twim<'static, TWISPI0>, Output<'static>>;
+
+#[embassy_executor::task]
+async fn oscillator(mut ltc: Ltc) {
+    ltc.set_output_mode(OutputMode::BothOutputs);
+    loop {
+        // 1 MHz to 2 MHz in 50 kHz steps, 100 ms each
+        ltc.enable_output().unwrap();
+        for reg in sweep(1_000_000, 2_000_000, 50_000).unwrap() {
+            if ltc
+                .write_reg(reg | u16::from(OutputMode::BothOutputs))
+                .is_err()
+            {
+                defmt::error!("I2C write failed");
+            }
+            defmt::debug!("{:?}", ltc.dump());
+            Timer::after_millis(100).await;
+        }
+
+        // gate the output for a second before the next sweep
+        ltc.disable_output().unwrap();
+        Timer::after_secs(1).await;
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    let p = embassy_nrf::init(Default::default());
+
+    let mut config = twim::Config::default();
+    config.frequency = twim::Frequency::K100;
+    let i2c = Twim::new(p.TWISPI0, Irqs, p.P0_29, p.P0_28, config);
+    let oe = Output::new(p.P1_01, Level::Low, OutputDrive::Standard);
+
+    let ltc = LTC6904::new(i2c, Address::AddressLow, oe);
+    defmt::info!("LTC6904 initialised");
+    spawner.spawn(oscillator(ltc)).unwrap();
+}
@@ -0,0 +1,36 @@
+//! Programming an LTC6904 from the desktop through an FT232H USB to I2C bridge
+//!
+//! AD0 is SCL, AD1 and AD2 are tied together as SDA, AD4 drives OE. The FTDI D2XX driver needs
+//! to be installed. The repository's cargo config targets the nRF52840, so pass the host target
+//! explicitly:
+//!
+//! ```text
+//! cargo run --target x86_64-unknown-linux-gnu -- 12288000
+//! ```
+//!
+//! Other bridges work the same way as long as their crate implements the embedded-hal 0.2 I2C
+//! and output pin traits, e.g. `mcp2221` for the MCP2221.
+
+use std::{env, error::Error};
+
+use ftdi_embedded_hal as hal;
+use ltc690x::{Address, OutputMode, LTC6904};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let f: u32 = match env::args().nth(1) {
+        Some(arg) => arg.parse()?,
+        None => 1_000_000,
+    };
+
+    let device = libftd2xx::Ft232h::with_description("Single RS232-HS")?;
+    let hal = hal::FtHal::init_freq(device, 100_000)?;
+    let mut ltc = LTC6904::new(hal.i2c()?, Address::AddressLow, hal.ad4()?);
+
+    let programmed = ltc.configure(f, OutputMode::BothOutputs)?;
+    ltc.enable_output()?;
+    println!(
+        "{} Hz requested, {} Hz programmed ({} ppm), register {:#06x}",
+        f, programmed.frequency, programmed.error_ppm, programmed.reg
+    );
+    Ok(())
+}
@@ -1,159 +1,123 @@
+//! nRF52840 with RTIC 2 sweeping an LTC6904
+//!
+//! SCL on P0.28, SDA on P0.29, OE on P1.01, ADR strapped low.
+//!
+//! The driver is split: the low priority `plan` task calculates the register words on a
+//! monotonic schedule, the `write` task at a higher priority owns the bus and only commits the
+//! staged word. The LED toggles with every write.
+
 #![no_main]
 #![no_std]
 
-use core::sync::atomic::AtomicUsize;
-
 use defmt_rtt as _;
-use hal::{
-    gpio::{self, Output, Pin, PushPull},
-    prelude::{OutputPin, StatefulOutputPin},
-    timer::Timer,
-    twim::Pins,
-};
 use panic_probe as _;
 
-extern crate ltc690x;
-use ltc690x::{Address, OutputSettings, LTC6904};
-
 use nrf52840_hal as hal;
+use rtic_monotonics::nrf::timer::prelude::*;
+
+nrf_timer0_monotonic!(Mono, 1_000_000);
+
+#[rtic::app(device = nrf52840_hal::pac, dispatchers = [SWI0_EGU0, SWI1_EGU1])]
+mod app {
+    use super::*;
+
+    use embedded_hal::digital::v2::{OutputPin, StatefulOutputPin};
+    use hal::{
+        gpio::{self, Output, Pin, PushPull},
+        twim::{self, Twim},
+    };
+    use ltc690x::{
+        split::{FrequencyPlanner, RegisterWriter, Staged},
+        Address, Error, OutputMode, LTC6904,
+    };
+
+    type Writer = RegisterWriter<'static, Twim<hal::pac::TWIM0>, Pin<Output<PushPull>>>;
+
+    static STAGED: Staged = Staged::new();
 
-static COUNT: AtomicUsize = AtomicUsize::new(0);
-defmt::timestamp!(
-    "{=usize}",
-    COUNT.fetch_add(1, core::sync::atomic::Ordering::Relaxed)
-);
-
-#[rtic::app(device = nrf52840_hal::pac, peripherals = true)]
-const APP: () = {
-    struct Resources {
-        delay: hal::timer::Timer<hal::pac::TIMER0>,
-        ltc: LTC6904<hal::twim::Twim<hal::pac::TWIM0>, hal::gpio::Pin<Output<PushPull>>>,
+    #[shared]
+    struct Shared {}
+
+    #[local]
+    struct Local {
+        planner: FrequencyPlanner<'static>,
+        writer: Writer,
         led: Pin<Output<PushPull>>,
     }
 
-    #[init()]
-    fn init(ctx: init::Context) -> init::LateResources {
+    #[init]
+    fn init(ctx: init::Context) -> (Shared, Local) {
         defmt::info!("Booted Up!");
         let _clk = hal::Clocks::new(ctx.device.CLOCK).enable_ext_hfosc();
-        defmt::info!("Clocks configured");
+        Mono::start(ctx.device.TIMER0);
+
         let p0 = gpio::p0::Parts::new(ctx.device.P0);
         let p1 = gpio::p1::Parts::new(ctx.device.P1);
-
-        let _led1 = p0.p0_13.into_push_pull_output(gpio::Level::Low);
-        let led2 = p0.p0_14.into_push_pull_output(gpio::Level::High).degrade();
+        let led = p0.p0_14.into_push_pull_output(gpio::Level::High).degrade();
 
         let scl = p0.p0_28.into_floating_input().degrade();
         let sda = p0.p0_29.into_floating_input().degrade();
-
-        let i2c = hal::twim::Twim::new(
+        let i2c = Twim::new(
             ctx.device.TWIM0,
-            Pins { scl, sda },
-            hal::twim::Frequency::K100,
+            twim::Pins { scl, sda },
+            twim::Frequency::K100,
         );
-        defmt::debug!("i2c initialised");
-        let delay = Timer::new(ctx.device.TIMER0);
-        defmt::debug!("delay initialised");
-        let ltc = ltc690x::LTC6904::new(
+
+        let ltc = LTC6904::new(
             i2c,
             Address::AddressLow,
             p1.p1_01.into_push_pull_output(gpio::Level::Low).degrade(),
         );
-        defmt::debug!("ltc initialised");
-        init::LateResources {
-            delay,
-            ltc,
-            led: led2,
-        }
+        let (mut planner, mut writer) = ltc.split(&STAGED);
+        planner.set_output_mode(OutputMode::BothOutputs);
+        writer.enable_output().unwrap();
+        defmt::info!("LTC6904 initialised");
+
+        plan::spawn().unwrap();
+        (
+            Shared {},
+            Local {
+                planner,
+                writer,
+                led,
+            },
+        )
     }
 
-    #[idle(resources = [delay, ltc, led])]
-    fn idle(ctx: idle::Context) -> ! {
-        let delay: &mut hal::timer::Timer<hal::pac::TIMER0> = ctx.resources.delay;
-        let ltc: &mut LTC6904<hal::twim::Twim<hal::pac::TWIM0>, Pin<Output<PushPull>>> =
-            ctx.resources.ltc;
-        let led: &mut Pin<Output<PushPull>> = ctx.resources.led;
-
-        defmt::debug!("idle started");
-
-        defmt::debug!(
-            "Oct: {:?}, Dac: {:?}, Cnf: {:?}",
-            ltc.get_oct(),
-            ltc.get_dac(),
-            ltc.get_cnf()
-        );
-        defmt::debug!("Reg: {:?}", ltc.get_reg());
-        defmt::unwrap!(ltc.set_frequency(108_000));
-        ltc.set_output_conf(OutputSettings::ClkBoth);
-
-        defmt::debug!(
-            "Oct: {:?}, Dac: {:?}, Cnf: {:?}",
-            ltc.get_oct(),
-            ltc.get_dac(),
-            ltc.get_cnf()
-        );
-
-        defmt::debug!("Setup the LTC, now call write-out");
-
-        let mut i = 0usize;
-        let mut cnt = 0usize;
+    /// Steps through the frequencies, one every 500 ms
+    #[task(priority = 1, local = [planner])]
+    async fn plan(ctx: plan::Context) {
+        let planner = ctx.local.planner;
+        let mut next = Mono::now();
         loop {
-            defmt::info!("{:?}->{:?}: idle loop", cnt, i);
-            cnt += 1;
-            delay.delay(500_000);
-
-            match i {
-                0 => {
-                    ltc.set_output_conf(OutputSettings::ClkPos);
-                }
-                1 => {
-                    ltc.set_output_conf(OutputSettings::ClkNeg);
-                }
-                2 => {
-                    ltc.set_output_conf(OutputSettings::ClkBoth);
-                }
-                3 => {
-                    ltc.set_output_conf(OutputSettings::PowerDown);
-
-                    match ltc.get_frequency() {
-                        108_000 => {
-                            ltc.set_frequency(5000).ok().unwrap();
-                        }
-                        5_000 => {
-                            ltc.set_frequency(1_000_000).ok().unwrap();
-                        }
-                        1_000_000 => {
-                            ltc.set_frequency(108_000).ok().unwrap();
-                        }
-                        _ => {
-                            ltc.set_frequency(108_000).ok().unwrap();
-                        }
-                    }
-                    defmt::debug!(
-                        "Oct: {:?}, Dac: {:?}, Cnf: {:?}",
-                        ltc.get_oct(),
-                        ltc.get_dac(),
-                        ltc.get_cnf()
-                    );
+            for f in [108_000, 5_000, 1_000_000] {
+                match planner.set_frequency(f) {
+                    Ok(reg) => defmt::debug!("{=u32} Hz staged as {=u16:#x}", f, reg),
+                    Err(e) => defmt::error!("{=u32} Hz: {:?}", f, e),
                 }
-                _ => {}
+                // the writer refuses to spawn while it's busy, the next commit picks the
+                // latest staged word up anyway
+                write::spawn().ok();
+                next += 500.millis();
+                Mono::delay_until(next).await;
             }
+        }
+    }
 
-            i = if i < 3 { i + 1 } else { 0 };
-
-            match ltc.write_out() {
-                Ok(_) => {
-                    defmt::debug!("Write Out Ok")
-                }
-                Err(e) => {
-                    defmt::error!("I2C Error: {:?}", defmt::Debug2Format(&e));
-                }
-            }
+    /// Writes the staged register word to the device
+    #[task(priority = 2, local = [writer, led])]
+    async fn write(ctx: write::Context) {
+        match ctx.local.writer.commit() {
+            Ok(true) => defmt::debug!("Write Out Ok"),
+            Ok(false) => {}
+            Err(e) => defmt::error!("{}", Error::<_, ()>::I2c(e)),
+        }
 
-            if led.is_set_high().unwrap() {
-                led.set_low().unwrap();
-            } else {
-                led.set_high().unwrap();
-            }
+        let led = ctx.local.led;
+        if led.is_set_high().unwrap() {
+            led.set_low().unwrap();
+        } else {
+            led.set_high().unwrap();
         }
     }
-};
+}
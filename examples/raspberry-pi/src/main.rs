@@ -0,0 +1,41 @@
+//! Raspberry Pi driving an LTC6904 through Linux' I2C and GPIO character devices
+//!
+//! SDA and SCL on the I2C1 pins of the header (GPIO2, GPIO3), OE on GPIO17, ADR strapped low.
+//! I2C has to be enabled, e.g. with `raspi-config`. The repository's cargo config targets the
+//! nRF52840, so pass the Pi's target, natively or cross compiling:
+//!
+//! ```text
+//! cargo run --target aarch64-unknown-linux-gnu -- 4096000
+//! ```
+
+use std::{env, error::Error, thread::sleep, time::Duration};
+
+use linux_embedded_hal::{
+    gpio_cdev::{Chip, LineRequestFlags},
+    CdevPin, I2cdev,
+};
+use ltc690x::{Address, OutputMode, LTC6904};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let f: u32 = match env::args().nth(1) {
+        Some(arg) => arg.parse()?,
+        None => 1_000_000,
+    };
+
+    let i2c = I2cdev::new("/dev/i2c-1")?;
+    let line = Chip::new("/dev/gpiochip0")?.get_line(17)?;
+    let oe = CdevPin::new(line.request(LineRequestFlags::OUTPUT, 0, "ltc6904-oe")?)?;
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, oe);
+
+    let programmed = ltc.configure(f, OutputMode::BothOutputs)?;
+    println!(
+        "{} Hz requested, {} Hz programmed ({} ppm)",
+        f, programmed.frequency, programmed.error_ppm
+    );
+
+    // keep the clock running for 10 s, then gate it again
+    ltc.enable_output()?;
+    sleep(Duration::from_secs(10));
+    ltc.disable_output()?;
+    Ok(())
+}
@@ -0,0 +1,78 @@
+//! Raspberry Pi Pico driving an LTC6904
+//!
+//! SDA on GPIO4, SCL on GPIO5 (I2C0), OE on GPIO6, ADR strapped low. Cycles through a few
+//! frequencies, enabling the output for a second each.
+
+#![no_std]
+#![no_main]
+
+use defmt_rtt as _;
+use panic_probe as _;
+
+use ltc690x::{Address, OutputMode, LTC6904};
+use rp_pico::{
+    entry,
+    hal::{
+        self,
+        fugit::RateExtU32,
+        gpio::{FunctionI2C, PullUp},
+        pac, Clock,
+    },
+};
+
+#[entry]
+fn main() -> ! {
+    let mut pac = pac::Peripherals::take().unwrap();
+    let core = pac::CorePeripherals::take().unwrap();
+
+    let mut watchdog = hal::Watchdog::new(pac.WATCHDOG);
+    let clocks = hal::clocks::init_clocks_and_plls(
+        rp_pico::XOSC_CRYSTAL_FREQ,
+        pac.XOSC,
+        pac.CLOCKS,
+        pac.PLL_SYS,
+        pac.PLL_USB,
+        &mut pac.RESETS,
+        &mut watchdog,
+    )
+    .ok()
+    .unwrap();
+    let mut delay = cortex_m::delay::Delay::new(core.SYST, clocks.system_clock.freq().to_Hz());
+
+    let sio = hal::Sio::new(pac.SIO);
+    let pins = rp_pico::Pins::new(
+        pac.IO_BANK0,
+        pac.PADS_BANK0,
+        sio.gpio_bank0,
+        &mut pac.RESETS,
+    );
+
+    let i2c = hal::I2C::i2c0(
+        pac.I2C0,
+        pins.gpio4.reconfigure::<FunctionI2C, PullUp>(),
+        pins.gpio5.reconfigure::<FunctionI2C, PullUp>(),
+        100.kHz(),
+        &mut pac.RESETS,
+        &clocks.system_clock,
+    );
+    let oe = pins.gpio6.into_push_pull_output();
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, oe);
+    defmt::info!("LTC6904 initialised");
+
+    loop {
+        for f in [1_000_000, 4_096_000, 12_000_000, 24_576_000] {
+            match ltc.configure(f, OutputMode::BothOutputs) {
+                Ok(programmed) => defmt::info!(
+                    "{=u32} Hz: {:?}, {=i32} ppm",
+                    f,
+                    ltc.dump(),
+                    programmed.error_ppm
+                ),
+                Err(_) => defmt::error!("programming {=u32} Hz failed", f),
+            }
+            ltc.enable_output().unwrap();
+            delay.delay_ms(1_000);
+            ltc.disable_output().unwrap();
+        }
+    }
+}
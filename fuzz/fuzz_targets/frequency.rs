@@ -0,0 +1,43 @@
+//! Frequency to register conversion over arbitrary inputs
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ltc690x::{
+    error_ppm, freq_to_reg, frequency_range, plan_frequency, reg_to_freq, resolution_at,
+    FrequencyError, Register,
+};
+
+fuzz_target!(|f: u32| {
+    let (min, max) = frequency_range();
+    let reg = match freq_to_reg(f) {
+        Ok(reg) => reg,
+        Err(e) => {
+            let expected = if f < min {
+                FrequencyError::TooLow
+            } else {
+                FrequencyError::TooHigh
+            };
+            assert!(f < min || f > max);
+            return assert_eq!(e, expected);
+        }
+    };
+    assert!((min..=max).contains(&f));
+
+    let r = Register::new(reg);
+    assert_eq!(r.cnf(), 0);
+    assert_eq!(Register::from_fields(r.oct(), r.dac(), 0).bits(), reg);
+
+    let achieved = reg_to_freq(reg);
+    assert!((min..=max).contains(&achieved));
+    assert!(resolution_at(f).unwrap() > 0);
+
+    let plan = plan_frequency(f).unwrap();
+    assert_eq!(plan.reg(), reg);
+    assert_eq!(plan.achieved, achieved);
+    assert_eq!(plan.error_ppm, error_ppm(f).unwrap());
+
+    if f < max {
+        assert!(achieved <= reg_to_freq(freq_to_reg(f + 1).unwrap()));
+    }
+});
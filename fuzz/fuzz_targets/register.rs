@@ -0,0 +1,23 @@
+//! Register packing and decoding over arbitrary words
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ltc690x::{frequency_range, reg_to_freq, Register};
+
+fuzz_target!(|bits: u16| {
+    let r = Register::new(bits);
+    assert_eq!(r.bits(), bits);
+    assert!(r.oct() <= 15 && r.dac() <= 1023 && r.cnf() <= 3);
+    assert_eq!(Register::from_fields(r.oct(), r.dac(), r.cnf()), r);
+    assert_eq!(Register::from_be_bytes(r.to_be_bytes()), r);
+    assert_eq!(r.with_output_mode(r.output_mode()), r);
+
+    let other = Register::new(!bits);
+    assert_eq!(r.with_oct(other.oct()).dac(), r.dac());
+    assert_eq!(r.with_dac(other.dac()).cnf(), r.cnf());
+    assert_eq!(r.with_cnf(other.cnf()).oct(), r.oct());
+
+    let (min, max) = frequency_range();
+    assert!((min..=max).contains(&reg_to_freq(bits)));
+});
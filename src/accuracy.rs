@@ -0,0 +1,107 @@
+//! Worst-case output frequency including the part tolerance
+//!
+//! The error reported elsewhere, e.g. by [`error_ppm()`](crate::error_ppm), is the quantization
+//! of the DAC alone, for an ideal part. A real part is off by up to the guaranteed accuracy of the
+//! datasheet on top, [`consts::ACCURACY_PPM`] for the octave. [`accuracy_bounds()`] combines both
+//! into the range the output is guaranteed to lie in, to check the margins of a clocked circuit
+//! in firmware:
+//!
+//! ```
+//! use ltc690x::accuracy::accuracy_bounds;
+//!
+//! let bounds = accuracy_bounds(12_288_000).unwrap();
+//! assert!(bounds.min < 12_288_000 && 12_288_000 < bounds.max);
+//! // e.g. an audio codec tolerating 2 %
+//! assert!(bounds.worst_error_ppm() < 20_000);
+//! ```
+
+#[cfg(feature = "driver")]
+use embedded_hal::{blocking::i2c::Write, digital::v2::OutputPin};
+
+#[cfg(feature = "driver")]
+use crate::LTC6904;
+use crate::{consts, freq_to_reg, reg_error_ppm, reg_to_freq, FrequencyError, Register};
+
+/// Range of the output frequency of a register across all parts, see [`accuracy_bounds()`]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AccuracyBounds {
+    /// output frequency of an ideal part in Hz, rounded
+    pub nominal: u32,
+    /// lowest output frequency in Hz, rounded down
+    pub min: u32,
+    /// highest output frequency in Hz, rounded up
+    pub max: u32,
+    /// error of the nominal frequency relative to the requested one in ppm
+    pub quantization_ppm: i32,
+    /// guaranteed accuracy of the part in the octave in ppm
+    pub tolerance_ppm: u32,
+}
+
+impl AccuracyBounds {
+    pub(crate) fn new(nominal: u32, quantization_ppm: i32, tolerance_ppm: u32) -> Self {
+        let spread = nominal as u64 * tolerance_ppm as u64;
+        Self {
+            nominal,
+            min: (nominal as u64 - spread.div_ceil(1_000_000)) as u32,
+            max: (nominal as u64 + spread.div_ceil(1_000_000)) as u32,
+            quantization_ppm,
+            tolerance_ppm,
+        }
+    }
+
+    /// Largest error relative to the requested frequency any part can have, in ppm
+    pub fn worst_error_ppm(&self) -> u32 {
+        self.quantization_ppm.unsigned_abs() + self.tolerance_ppm
+    }
+
+    /// Smallest error relative to the requested frequency guaranteed by the bounds, in ppm
+    ///
+    /// 0 unless the quantization alone exceeds the tolerance, a part may hit the request then.
+    pub fn best_error_ppm(&self) -> u32 {
+        self.quantization_ppm
+            .unsigned_abs()
+            .saturating_sub(self.tolerance_ppm)
+    }
+
+    /// Whether every part generates `f` within `tolerance_ppm`
+    pub fn within(&self, tolerance_ppm: u32) -> bool {
+        self.worst_error_ppm() <= tolerance_ppm
+    }
+}
+
+/// Bounds of the output of the register [`freq_to_reg()`] picks for `f`
+pub fn accuracy_bounds(f: u32) -> Result<AccuracyBounds, FrequencyError> {
+    let reg = freq_to_reg(f)?;
+    Ok(AccuracyBounds::new(
+        reg_to_freq(reg),
+        reg_error_ppm(reg, f),
+        tolerance_ppm(reg),
+    ))
+}
+
+/// Guaranteed accuracy in ppm of the octave of `reg`
+pub(crate) fn tolerance_ppm(reg: u16) -> u32 {
+    consts::ACCURACY_PPM[Register::new(reg).oct() as usize]
+}
+
+#[cfg(feature = "driver")]
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// [`accuracy_bounds()`] of the cached configuration for the requested frequency
+    ///
+    /// The nominal frequency includes the correction set with [`LTC6904::calibrate()`]. The
+    /// tolerance is kept in full: a calibration at one temperature and supply doesn't cover the
+    /// drift over the rest.
+    pub fn output_bounds(&self) -> AccuracyBounds {
+        let reg = self.reg.bits();
+        AccuracyBounds::new(
+            self.calibrated_freq(reg),
+            self.calibrated_error_ppm(reg, self.frequ),
+            tolerance_ppm(reg),
+        )
+    }
+}
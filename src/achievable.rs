@@ -0,0 +1,103 @@
+//! Enumeration of the frequencies the device can actually generate
+//!
+//! Ordering OCT and DAC as a single 14 bit code gives every achievable frequency in ascending
+//! order, which allows walking outwards from a target without touching the hardware.
+
+use crate::{freq_to_reg, reg_error_ppm, reg_to_freq, FrequencyError, Register};
+
+/// Number of OCT and DAC combinations
+pub(crate) const CODES: u16 = 16 * 1024;
+
+/// An achievable frequency and its quantization error to a target
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candidate {
+    /// register word with CNF = 0
+    pub reg: u16,
+    /// output frequency in Hz, rounded
+    pub frequency: u32,
+    /// error relative to the target in ppm
+    pub error_ppm: i32,
+}
+
+/// Iterator created by [`achievable_near()`]
+#[derive(Debug, Clone)]
+pub struct AchievableNear {
+    target: u32,
+    /// next candidates below and above the target, as code index
+    below: Option<u16>,
+    above: Option<u16>,
+    remaining: usize,
+}
+
+pub(crate) fn code_to_reg(code: u16) -> u16 {
+    Register::from_code(code).bits()
+}
+
+pub(crate) fn reg_to_code(reg: u16) -> u16 {
+    Register::new(reg).code()
+}
+
+/// Every register word (with CNF = 0) generating a frequency in `range`, with that frequency in
+/// Hz, rounded, in ascending order (requires the `std` feature)
+///
+/// Meant for build scripts and host tools generating lookup tables or documentation.
+#[cfg(feature = "std")]
+pub fn enumerate_achievable<R: core::ops::RangeBounds<u32>>(range: R) -> std::vec::Vec<(u16, u32)> {
+    (0..CODES)
+        .map(code_to_reg)
+        .map(|reg| (reg, reg_to_freq(reg)))
+        .filter(|(_, f)| range.contains(f))
+        .collect()
+}
+
+/// Yields the `n` achievable frequencies closest to `target`, closest first
+pub fn achievable_near(target: u32, n: usize) -> Result<AchievableNear, FrequencyError> {
+    let code = reg_to_code(freq_to_reg(target)?);
+    Ok(AchievableNear {
+        target,
+        below: code.checked_sub(1),
+        above: Some(code),
+        remaining: n,
+    })
+}
+
+impl AchievableNear {
+    fn candidate(&self, code: u16) -> Candidate {
+        let reg = code_to_reg(code);
+        Candidate {
+            reg,
+            frequency: reg_to_freq(reg),
+            error_ppm: reg_error_ppm(reg, self.target),
+        }
+    }
+}
+
+impl Iterator for AchievableNear {
+    type Item = Candidate;
+
+    fn next(&mut self) -> Option<Candidate> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let below = self.below.map(|c| self.candidate(c));
+        let above = self.above.map(|c| self.candidate(c));
+        let next = match (below, above) {
+            (Some(b), Some(a)) if b.error_ppm.unsigned_abs() < a.error_ppm.unsigned_abs() => {
+                self.below = self.below.and_then(|c| c.checked_sub(1));
+                b
+            }
+            (_, Some(a)) => {
+                self.above = self.above.map(|c| c + 1).filter(|c| *c < CODES);
+                a
+            }
+            (Some(b), None) => {
+                self.below = self.below.and_then(|c| c.checked_sub(1));
+                b
+            }
+            (None, None) => return None,
+        };
+        self.remaining -= 1;
+        Some(next)
+    }
+}
@@ -0,0 +1,43 @@
+//! Master clock rates for audio codecs
+//!
+//! Codecs are commonly clocked at 256, 384 or 512 times the sample rate. The crystal frequencies
+//! of the 48 kHz and 44.1 kHz families are provided as constants, [`mclk()`] calculates others.
+//! With [`mclk_error_ppm()`] the quantization error of the achievable frequency can be checked
+//! against the codec's tolerance before programming it.
+
+use crate::{error_ppm, FrequencyError};
+
+/// 256 × 48 kHz
+pub const MCLK_48K_256FS: u32 = 12_288_000;
+/// 384 × 48 kHz
+pub const MCLK_48K_384FS: u32 = 18_432_000;
+/// 512 × 48 kHz
+pub const MCLK_48K_512FS: u32 = 24_576_000;
+/// 256 × 44.1 kHz
+pub const MCLK_44K1_256FS: u32 = 11_289_600;
+/// 384 × 44.1 kHz
+pub const MCLK_44K1_384FS: u32 = 16_934_400;
+/// 512 × 44.1 kHz
+pub const MCLK_44K1_512FS: u32 = 22_579_200;
+
+/// Master clock frequency for sample rate `fs` and oversampling `ratio`, saturating
+pub fn mclk(fs: u32, ratio: u32) -> u32 {
+    fs.saturating_mul(ratio)
+}
+
+pub fn mclk_256fs(fs: u32) -> u32 {
+    mclk(fs, 256)
+}
+
+pub fn mclk_384fs(fs: u32) -> u32 {
+    mclk(fs, 384)
+}
+
+pub fn mclk_512fs(fs: u32) -> u32 {
+    mclk(fs, 512)
+}
+
+/// Error of the closest achievable master clock for `fs` and `ratio` in ppm
+pub fn mclk_error_ppm(fs: u32, ratio: u32) -> Result<i32, FrequencyError> {
+    error_ppm(mclk(fs, ratio))
+}
@@ -0,0 +1,39 @@
+//! Clocking UARTs from the oscillator
+//!
+//! A UART running off the LTC6904 needs `baud × oversampling` Hz, e.g. 16 × 115200 Hz. The baud
+//! rate is off by the same relative error as the clock, which is reported in ppm
+//! (10_000 ppm = 1 %). Most UARTs tolerate around 2 % in total between both ends.
+
+#[cfg(feature = "driver")]
+use embedded_hal::{blocking::i2c::Write, digital::v2::OutputPin};
+
+#[cfg(feature = "driver")]
+use crate::LTC6904;
+use crate::{error_ppm, FrequencyError};
+
+/// Oscillator frequency needed for `baud` with `oversampling` clocks per bit, saturating
+pub fn baud_clock(baud: u32, oversampling: u32) -> u32 {
+    baud.saturating_mul(oversampling)
+}
+
+/// Baud rate error in ppm when clocking a UART at `baud` with `oversampling` clocks per bit
+pub fn baud_error_ppm(baud: u32, oversampling: u32) -> Result<i32, FrequencyError> {
+    error_ppm(baud_clock(baud, oversampling))
+}
+
+#[cfg(feature = "driver")]
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// Sets the frequency to clock a UART at `baud` with `oversampling` clocks per bit
+    ///
+    /// Returns the resulting baud rate error in ppm. As with [`LTC6904::set_frequency()`],
+    /// [`LTC6904::write_out()`] needs to be called afterwards.
+    pub fn set_baud_clock(&mut self, baud: u32, oversampling: u32) -> Result<i32, FrequencyError> {
+        let f = baud_clock(baud, oversampling);
+        let reg = self.set_frequency(f)?;
+        Ok(self.calibrated_error_ppm(reg, f.into()))
+    }
+}
@@ -0,0 +1,83 @@
+//! Prints the register the driver programs for a frequency
+//!
+//! `ltc690x-calc [--mode <both|clk|clkinv|off>] <frequency>...`, with frequencies like
+//! "12.288MHz", "108kHz" or "1039" (Hz). Every frequency is calculated with
+//! [`ltc690x::plan_frequency()`], the function the driver uses as well.
+
+use std::process::ExitCode;
+
+use ltc690x::{
+    frequency::{format_frequency, FORMATTED_LEN},
+    plan_frequency, Frequency, OutputMode,
+};
+
+const USAGE: &str = "usage: ltc690x-calc [--mode <both|clk|clkinv|off>] <frequency>...";
+
+fn format(f: Frequency) -> String {
+    format_frequency(f, &mut [0; FORMATTED_LEN]).to_string()
+}
+
+/// Prints the plan for `arg`, or why there is none
+fn calc(arg: &str, mode: OutputMode) -> Result<(), String> {
+    let target: Frequency = arg.parse().map_err(|e| format!("{}: {}", arg, e))?;
+    let plan = plan_frequency(target.hz()).map_err(|e| format!("{}: {}", arg, e))?;
+    let reg = plan.reg() | mode.bits();
+    println!("target    {}", format(target));
+    println!("OCT       {}", plan.oct);
+    println!("DAC       {}", plan.dac);
+    println!(
+        "register  {:#06x} (CNF {:02b}, {})",
+        reg,
+        mode.bits(),
+        mode.name()
+    );
+    println!(
+        "achieved  {} ({} Hz)",
+        format(Frequency::from_hertz(plan.achieved)),
+        plan.achieved
+    );
+    println!("error     {:+} ppm", plan.error_ppm);
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let mut mode = OutputMode::BothOutputs;
+    let mut frequencies = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            "-m" | "--mode" => match args.next().map(|m| m.parse()) {
+                Some(Ok(m)) => mode = m,
+                Some(Err(e)) => {
+                    eprintln!("--mode: {}", e);
+                    return ExitCode::FAILURE;
+                }
+                None => {
+                    eprintln!("{}", USAGE);
+                    return ExitCode::FAILURE;
+                }
+            },
+            _ => frequencies.push(arg),
+        }
+    }
+    if frequencies.is_empty() {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    }
+
+    let mut status = ExitCode::SUCCESS;
+    for (i, arg) in frequencies.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        if let Err(e) = calc(arg, mode) {
+            eprintln!("{}", e);
+            status = ExitCode::FAILURE;
+        }
+    }
+    status
+}
@@ -0,0 +1,209 @@
+//! Trimming the part-to-part frequency error
+//!
+//! The LTC6904 is only specified to a frequency error of about 1 %. Measuring the output once,
+//! e.g. with a frequency counter during end of line test, gives a correction in ppm which the
+//! driver applies to every following [`LTC6904::set_frequency()`]. The correction is a plain
+//! number, so it can be stored on the board and restored with
+//! [`LTC6904::set_calibration_ppm()`] on the next start.
+//!
+//! Where the MCU can measure the output itself, e.g. with an input capture timer,
+//! [`LTC6904::trim_step()`] closes the loop and keeps the output on the requested frequency.
+//!
+//! The output also drifts with the temperature of the part. [`drift_ppm()`] estimates the drift
+//! from the typical coefficient of the datasheet, and with a temperature sensor on the board
+//! [`LTC6904::set_frequency_compensated()`] programs the frequency biased against it.
+
+#[cfg(feature = "driver")]
+use core::convert::TryFrom;
+
+#[cfg(feature = "driver")]
+use embedded_hal::{blocking::i2c::Write, digital::v2::OutputPin};
+
+#[cfg(feature = "driver")]
+use crate::{
+    achievable::reg_to_code,
+    chip::{self, Chip},
+    fixed_to_reg, Error, Frequency, FrequencyError, FRAC_BITS, LTC6904,
+};
+use crate::{
+    consts::{BASE_FREQUENCY, DRIFT_PPM_PER_C, DRIFT_REFERENCE_C},
+    Register,
+};
+
+/// Largest correction accepted by [`LTC6904::calibrate()`] and
+/// [`LTC6904::set_calibration_ppm()`]
+pub const MAX_CALIBRATION_PPM: i32 = 50_000;
+
+/// Typical drift of the output in ppm at `temp_c` °C relative to [`DRIFT_REFERENCE_C`]
+///
+/// A linear estimate with [`DRIFT_PPM_PER_C`], positive when the part runs fast. Single parts
+/// deviate from it, it's no bound like [`consts::ACCURACY_PPM`](crate::consts::ACCURACY_PPM).
+pub fn drift_ppm(temp_c: i16) -> i32 {
+    (temp_c as i32 - DRIFT_REFERENCE_C as i32) * DRIFT_PPM_PER_C
+}
+
+/// Exact frequency generated by `reg` as numerator and denominator
+pub(crate) fn exact(reg: u16) -> (i128, i128) {
+    let reg = Register::new(reg);
+    (
+        (BASE_FREQUENCY as i128) << (10 + reg.oct()),
+        2048 - reg.dac() as i128,
+    )
+}
+
+#[cfg(feature = "driver")]
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// Derives the correction from the frequency `measured` on an output programmed for
+    /// `requested`, returns the correction in ppm
+    ///
+    /// `requested` is the frequency passed to [`LTC6904::set_frequency()`] before the
+    /// measurement, the correction active at that time is taken into account. The new correction
+    /// replaces the previous one and applies from the next [`LTC6904::set_frequency()`].
+    pub fn calibrate(&mut self, requested: u32, measured: u32) -> Result<i32, FrequencyError> {
+        self.calibrate_precise(requested.into(), measured)
+    }
+
+    fn calibrate_precise(
+        &mut self,
+        requested: Frequency,
+        measured: u32,
+    ) -> Result<i32, FrequencyError> {
+        let (num, den) = exact(self.calibrated_reg(requested)?);
+        // measured / (num / den) - 1
+        let scaled = measured as i128 * den * 1_000_000;
+        let ppm = (scaled + num / 2) / num - 1_000_000;
+        let ppm = i32::try_from(ppm).map_err(|_| FrequencyError::CalibrationOutOfRange)?;
+        self.set_calibration_ppm(ppm)?;
+        Ok(ppm)
+    }
+
+    /// Sets the correction in ppm by which the part runs fast (positive) or slow (negative)
+    pub fn set_calibration_ppm(&mut self, ppm: i32) -> Result<(), FrequencyError> {
+        if ppm.unsigned_abs() > MAX_CALIBRATION_PPM as u32 {
+            return Err(FrequencyError::CalibrationOutOfRange);
+        }
+        self.calibration_ppm = ppm;
+        Ok(())
+    }
+
+    /// Corrects the output towards the requested frequency from its `measured` frequency
+    ///
+    /// Meant to be called in a control loop: the correction is updated from the measurement and
+    /// the register rewritten if it changes. Returns the number of codes the oscillator was moved
+    /// by, which drops to 0 once the output is as close to the requested frequency as the DAC
+    /// allows.
+    pub fn trim_step(&mut self, measured: u32) -> Result<i32, Error<E, PIN::Error>> {
+        let before = self.reg;
+        self.calibrate_precise(self.frequ, measured)?;
+        self.set_frequency_precise(self.frequ)?;
+        let moved = reg_to_code(self.reg.bits()) as i32 - reg_to_code(before.bits()) as i32;
+        if moved != 0 {
+            self.write_out().map_err(Error::I2c)?;
+        }
+        Ok(moved)
+    }
+
+    /// [`LTC6904::set_frequency()`] biased against the [`drift_ppm()`] at `temp_c` °C, on top of
+    /// the calibration
+    ///
+    /// The calibration is assumed to be taken at [`DRIFT_REFERENCE_C`]. Call it again when the
+    /// temperature changed, [`LTC6904::programmed_frequency()`] and the reported errors leave the
+    /// drift out.
+    pub fn set_frequency_compensated(
+        &mut self,
+        f: u32,
+        temp_c: i16,
+    ) -> Result<u16, FrequencyError> {
+        let ppm = self.calibration_ppm + drift_ppm(temp_c);
+        if ppm.unsigned_abs() > MAX_CALIBRATION_PPM as u32 {
+            return Err(FrequencyError::CalibrationOutOfRange);
+        }
+        let f = f.into();
+        let reg = checked_reg::<chip::Ltc6904>(f, ppm, self.max_error_ppm)?;
+        Ok(self.apply_frequency(f, Register::new(reg)))
+    }
+
+    /// Correction applied to all frequencies, 0 unless calibrated
+    pub fn calibration_ppm(&self) -> i32 {
+        self.calibration_ppm
+    }
+
+    pub(crate) fn calibrated_reg(&self, f: Frequency) -> Result<u16, FrequencyError> {
+        calibrated_reg::<chip::Ltc6904>(f, self.calibration_ppm)
+    }
+
+    pub(crate) fn calibrated_freq(&self, reg: u16) -> u32 {
+        calibrated_freq(reg, self.calibration_ppm)
+    }
+
+    pub(crate) fn calibrated_error_ppm(&self, reg: u16, f: Frequency) -> i32 {
+        calibrated_error_ppm(reg, f, self.calibration_ppm)
+    }
+}
+
+/// Register word programming the output of `C` corrected by `ppm` closest to `f`, rejecting it if
+/// the error exceeds `max_error_ppm`
+#[cfg(feature = "driver")]
+pub(crate) fn checked_reg<C: Chip>(
+    f: Frequency,
+    ppm: i32,
+    max_error_ppm: Option<u32>,
+) -> Result<u16, FrequencyError> {
+    let reg = calibrated_reg::<C>(f, ppm)?;
+    match max_error_ppm {
+        Some(max) if calibrated_error_ppm(reg, f, ppm).unsigned_abs() > max => {
+            Err(FrequencyError::ToleranceExceeded)
+        }
+        _ => Ok(reg),
+    }
+}
+
+/// Register word programming the output of `C` corrected by `ppm` closest to `f`
+///
+/// The range of `C` applies to the frequency programmed, a calibrated part may output a little
+/// beyond it.
+#[cfg(feature = "driver")]
+pub(crate) fn calibrated_reg<C: Chip>(f: Frequency, ppm: i32) -> Result<u16, FrequencyError> {
+    let nominal = nominal(f, ppm);
+    if nominal < (C::FREQUENCY_MIN as u64) << FRAC_BITS {
+        Err(FrequencyError::TooLow)
+    } else if nominal > (C::FREQUENCY_MAX as u64) << FRAC_BITS {
+        Err(FrequencyError::TooHigh)
+    } else {
+        fixed_to_reg(nominal)
+    }
+}
+
+/// Frequency the part has to be programmed for to output `f` when corrected by `ppm`, with
+/// [`FRAC_BITS`] fractional bits
+#[cfg(feature = "driver")]
+pub(crate) fn nominal(f: Frequency, ppm: i32) -> u64 {
+    // f / (1 + ppm / 10^6) from millihertz, which overflows u64 in between for large f
+    let num = ((f.millihertz() as u128) << FRAC_BITS) * 1_000;
+    let den = (1_000_000 + ppm as i64) as u128;
+    ((num + den / 2) / den).min(u64::MAX as u128) as u64
+}
+
+/// Output frequency of `reg` corrected by `ppm`, rounded to Hz
+#[cfg(feature = "driver")]
+pub(crate) fn calibrated_freq(reg: u16, ppm: i32) -> u32 {
+    let (num, den) = exact(reg);
+    let num = num * (1_000_000 + ppm as i128);
+    let den = den * 1_000_000;
+    ((num + den / 2) / den) as u32
+}
+
+/// Relative error of the output of `reg` corrected by `ppm` to `f` in ppm, rounded
+#[cfg(feature = "driver")]
+pub(crate) fn calibrated_error_ppm(reg: u16, f: Frequency, ppm: i32) -> i32 {
+    let (num, den) = exact(reg);
+    // num / den * (1 + ppm / 10^6) / f - 1, with f in millihertz
+    let num = num * (1_000_000 + ppm as i128) * 1_000;
+    let den = den * f.millihertz() as i128;
+    let diff = num - den * 1_000_000;
+    ((diff + diff.signum() * den / 2) / den) as i32
+}
@@ -0,0 +1,88 @@
+//! Constants of the members of the device family
+//!
+//! The LTC6903 and the LTC6904 share the register layout and frequency formula and differ in the
+//! bus they're programmed through. [`Chip`] collects what a driver needs to know about a variant,
+//! [`Ltc6903`] and [`Ltc6904`] implement it for the supported parts.
+//!
+//! Both drivers convert frequencies through the same calibrated conversion, generic over the
+//! `Chip` whose range the programmed frequency has to lie in. The driver types themselves aren't
+//! generic, [`LTC6903`](crate::ltc6903::LTC6903) passes [`Ltc6903`] and
+//! [`LTC6904`](crate::LTC6904) passes [`Ltc6904`], so another member of the family needs its
+//! constants and a driver naming them. [`Chip::freq_to_reg()`] is the uncalibrated conversion
+//! within the range of a part.
+//!
+//! ```
+//! use ltc690x::chip::{AddressScheme, Chip, Ltc6903, Ltc6904};
+//!
+//! assert_eq!(Ltc6904::ADDRESS_SCHEME, AddressScheme::I2c(&[0x17, 0x16]));
+//! assert_eq!(Ltc6903::ADDRESS_SCHEME, AddressScheme::ChipSelect);
+//! assert!(Ltc6904::freq_to_reg(Ltc6904::FREQUENCY_MAX).is_ok());
+//! ```
+
+use crate::{consts, Frequency, FrequencyError};
+
+/// How a part is selected on its bus
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AddressScheme {
+    /// I2C with one of the 7 bit addresses, selected by strapping
+    I2c(&'static [u8]),
+    /// SPI with a chip select, without an address
+    ChipSelect,
+}
+
+/// Per-variant constants of a member of the family
+pub trait Chip {
+    /// part number, e.g. for logs
+    const NAME: &'static str;
+    /// lowest output frequency in Hz
+    const FREQUENCY_MIN: u32;
+    /// highest output frequency in Hz
+    const FREQUENCY_MAX: u32;
+    /// time in µs the oscillator needs to settle after powering up
+    const SETTLING_US: u32;
+    /// how the part is selected on its bus
+    const ADDRESS_SCHEME: AddressScheme;
+
+    /// Rejects frequencies outside of the range of the part
+    fn check_frequency(f: Frequency) -> Result<(), FrequencyError> {
+        if f.millihertz() < Self::FREQUENCY_MIN as u64 * 1_000 {
+            Err(FrequencyError::TooLow)
+        } else if f.millihertz() > Self::FREQUENCY_MAX as u64 * 1_000 {
+            Err(FrequencyError::TooHigh)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// [`freq_to_reg()`](crate::freq_to_reg) within the range of the part
+    fn freq_to_reg(f: u32) -> Result<u16, FrequencyError> {
+        Self::check_frequency(Frequency::from_hertz(f))?;
+        crate::freq_to_reg(f)
+    }
+}
+
+/// The LTC6903, programmed through SPI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Ltc6903;
+
+impl Chip for Ltc6903 {
+    const NAME: &'static str = "LTC6903";
+    const FREQUENCY_MIN: u32 = consts::FREQUENCY_MIN;
+    const FREQUENCY_MAX: u32 = consts::FREQUENCY_MAX;
+    const SETTLING_US: u32 = consts::WAKE_SETTLING_US;
+    const ADDRESS_SCHEME: AddressScheme = AddressScheme::ChipSelect;
+}
+
+/// The LTC6904, programmed through I2C
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Ltc6904;
+
+impl Chip for Ltc6904 {
+    const NAME: &'static str = "LTC6904";
+    const FREQUENCY_MIN: u32 = consts::FREQUENCY_MIN;
+    const FREQUENCY_MAX: u32 = consts::FREQUENCY_MAX;
+    const SETTLING_US: u32 = consts::WAKE_SETTLING_US;
+    const ADDRESS_SCHEME: AddressScheme =
+        AddressScheme::I2c(&[consts::ADDRESS_LOW, consts::ADDRESS_HIGH]);
+}
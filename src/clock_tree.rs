@@ -0,0 +1,151 @@
+//! Named clocks of a board in one place
+//!
+//! Boards with several oscillators tend to spread the frequency of every clock over the code
+//! configuring its consumer. A [`ClockTree`] holds all of them by name, together with their
+//! target frequencies, and programs them with [`ClockTree::apply_all()`].
+//!
+//! ```
+//! # #[cfg(feature = "sim")]
+//! # fn main() -> Result<(), ltc690x::clock_tree::ClockError> {
+//! # use ltc690x::{sim::Ltc6904Sim, Address, LTC6904};
+//! use ltc690x::clock_tree::{Clock, ClockTree};
+//!
+//! # let codec = Ltc6904Sim::new(Address::AddressLow);
+//! # let fpga = Ltc6904Sim::new(Address::AddressHigh);
+//! # let mut codec_ltc = LTC6904::new(codec.i2c(), Address::AddressLow, codec.oe_pin());
+//! # let mut fpga_ltc = LTC6904::new(fpga.i2c(), Address::AddressHigh, fpga.oe_pin());
+//! let mut clocks = ClockTree::new([
+//!     Clock::new("codec_mclk", &mut codec_ltc, 12_288_000),
+//!     Clock::new("fpga_ref", &mut fpga_ltc, 25_000_000),
+//! ]);
+//! clocks.apply_all()?;
+//! clocks.retarget("codec_mclk", 11_289_600)?;
+//! # assert_eq!(codec.writes().len(), 2);
+//! # assert_eq!(fpga.writes().len(), 1);
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "sim"))]
+//! # fn main() {}
+//! ```
+
+use core::fmt;
+
+use crate::{ErrorCategory, Oscillator, OscillatorError, Programmed};
+
+/// An oscillator and the frequency it should generate
+pub struct Clock<'a> {
+    name: &'static str,
+    oscillator: &'a mut dyn Oscillator,
+    target: u32,
+}
+
+impl<'a> Clock<'a> {
+    pub fn new(name: &'static str, oscillator: &'a mut dyn Oscillator, target: u32) -> Self {
+        Self {
+            name,
+            oscillator,
+            target,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Frequency in Hz the clock is programmed for by [`ClockTree::apply_all()`]
+    pub fn target(&self) -> u32 {
+        self.target
+    }
+
+    /// Frequency generated by the oscillator, rounded to Hz
+    pub fn frequency(&self) -> u32 {
+        self.oscillator.frequency()
+    }
+
+    pub fn oscillator(&mut self) -> &mut dyn Oscillator {
+        self.oscillator
+    }
+
+    fn apply(&mut self) -> Result<Programmed, ClockError> {
+        let name = self.name;
+        self.oscillator
+            .program(self.target)
+            .map_err(|error| ClockError::Oscillator { name, error })
+    }
+}
+
+impl fmt::Debug for Clock<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Clock")
+            .field("name", &self.name)
+            .field("target", &self.target)
+            .finish()
+    }
+}
+
+/// Errors of a [`ClockTree`]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+pub enum ClockError {
+    /// no clock of that name
+    #[error("unknown clock")]
+    UnknownClock,
+    /// programming the named clock failed
+    #[error("programming clock {name} failed")]
+    Oscillator {
+        name: &'static str,
+        #[source]
+        error: OscillatorError,
+    },
+}
+
+impl ClockError {
+    /// What went wrong, for error reporting
+    pub const fn category(&self) -> ErrorCategory {
+        match self {
+            ClockError::UnknownClock => ErrorCategory::Range,
+            ClockError::Oscillator { error, .. } => error.category(),
+        }
+    }
+}
+
+/// Fixed set of `N` named [`Clock`]s
+#[derive(Debug)]
+pub struct ClockTree<'a, const N: usize> {
+    clocks: [Clock<'a>; N],
+}
+
+impl<'a, const N: usize> ClockTree<'a, N> {
+    pub fn new(clocks: [Clock<'a>; N]) -> Self {
+        Self { clocks }
+    }
+
+    /// Programs every clock to its target, in the order they were passed to
+    /// [`ClockTree::new()`]
+    ///
+    /// Stops at the first clock failing, the following ones aren't touched.
+    pub fn apply_all(&mut self) -> Result<(), ClockError> {
+        self.clocks
+            .iter_mut()
+            .try_for_each(|c| c.apply().map(|_| ()))
+    }
+
+    /// Changes the target of the clock `name` and programs it
+    pub fn retarget(&mut self, name: &str, target: u32) -> Result<Programmed, ClockError> {
+        let clock = self.get_mut(name).ok_or(ClockError::UnknownClock)?;
+        let previous = core::mem::replace(&mut clock.target, target);
+        clock.apply().inspect_err(|_| clock.target = previous)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Clock<'a>> {
+        self.clocks.iter().find(|c| c.name == name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Clock<'a>> {
+        self.clocks.iter_mut().find(|c| c.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Clock<'a>> {
+        self.clocks.iter()
+    }
+}
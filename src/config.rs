@@ -0,0 +1,161 @@
+//! Device configuration as a value
+
+#[cfg(feature = "driver")]
+use embedded_hal::{blocking::i2c::Write, digital::v2::OutputPin};
+
+#[cfg(feature = "driver")]
+use crate::{Error, LTC6904};
+use crate::{FrequencyError, OutputMode, FREQUENCY_MIN};
+
+/// Frequency and output mode of the device
+///
+/// The default matches the device after power-on: OCT = 0 and DAC = 0 generating 1039 Hz on both
+/// outputs. Other configurations can be written based on it:
+///
+/// ```
+/// use ltc690x::{Config, OutputMode};
+///
+/// let config = Config {
+///     frequency: 12_288_000,
+///     ..Default::default()
+/// };
+/// assert_eq!(config.output_mode, OutputMode::BothOutputs);
+/// ```
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Config {
+    /// frequency in Hz
+    pub frequency: u32,
+    pub output_mode: OutputMode,
+}
+
+impl Config {
+    /// State of the device after power-on
+    pub const POWER_ON: Config = Config {
+        frequency: FREQUENCY_MIN,
+        output_mode: OutputMode::BothOutputs,
+    };
+
+    pub const fn new(frequency: u32, output_mode: OutputMode) -> Self {
+        Config {
+            frequency,
+            output_mode,
+        }
+    }
+
+    /// [`Config::new()`] panicking if the frequency is out of range, which fails the build when
+    /// evaluated in const context
+    ///
+    /// ```
+    /// use ltc690x::{Config, OutputMode};
+    ///
+    /// static PROFILES: [Config; 2] = [
+    ///     Config::checked(32_768, OutputMode::ClkOnly),
+    ///     Config::checked(12_288_000, OutputMode::BothOutputs),
+    /// ];
+    /// assert_eq!(PROFILES[1].to_reg().unwrap() & 0b11, 0);
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use ltc690x::{Config, OutputMode};
+    ///
+    /// static TOO_HIGH: Config = Config::checked(100_000_000, OutputMode::ClkOnly);
+    /// ```
+    pub const fn checked(frequency: u32, output_mode: OutputMode) -> Self {
+        let config = Config::new(frequency, output_mode);
+        if config.to_reg().is_err() {
+            panic!("frequency out of the range of the device");
+        }
+        config
+    }
+
+    /// Register word generating this configuration
+    pub const fn to_reg(&self) -> Result<u16, FrequencyError> {
+        match crate::freq_to_reg(self.frequency) {
+            Ok(reg) => Ok(reg | self.output_mode.bits()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Fields of `other` that differ from this configuration
+    pub fn diff(&self, other: &Config) -> ConfigDelta {
+        ConfigDelta {
+            frequency: Some(other.frequency).filter(|f| *f != self.frequency),
+            output_mode: Some(other.output_mode).filter(|m| *m != self.output_mode),
+        }
+    }
+
+    /// This configuration with the changes of `delta` applied
+    pub fn with_delta(&self, delta: &ConfigDelta) -> Config {
+        Config {
+            frequency: delta.frequency.unwrap_or(self.frequency),
+            output_mode: delta.output_mode.unwrap_or(self.output_mode),
+        }
+    }
+}
+
+/// Changed fields between two [`Config`]s, created by [`Config::diff()`]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ConfigDelta {
+    /// new frequency in Hz, `None` if unchanged
+    pub frequency: Option<u32>,
+    /// new output mode, `None` if unchanged
+    pub output_mode: Option<OutputMode>,
+}
+
+impl ConfigDelta {
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.frequency.is_none() && self.output_mode.is_none()
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::POWER_ON
+    }
+}
+
+#[cfg(feature = "driver")]
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// Stages frequency and output mode of `config`, see [`LTC6904::configure()`] to write it as
+    /// well
+    pub fn set_config(&mut self, config: &Config) -> Result<u16, FrequencyError> {
+        self.set_frequency(config.frequency)?;
+        self.set_output_mode(config.output_mode);
+        Ok(self.get_reg())
+    }
+
+    /// Applies the changed fields of `delta` and writes them, returns whether the device was
+    /// written
+    ///
+    /// An empty delta causes no bus traffic, which lets a task receiving full configurations
+    /// pass them through [`Config::diff()`] first. Nothing is changed if the frequency can't be
+    /// set.
+    pub fn apply_delta(&mut self, delta: &ConfigDelta) -> Result<bool, Error<E, PIN::Error>> {
+        if delta.is_empty() {
+            return Ok(false);
+        }
+        if let Some(f) = delta.frequency {
+            self.set_frequency(f)?;
+        }
+        if let Some(mode) = delta.output_mode {
+            self.set_output_mode(mode);
+        }
+        self.write_out().map_err(Error::I2c)?;
+        Ok(true)
+    }
+
+    /// Cached configuration, with the frequency as requested
+    pub fn config(&self) -> Config {
+        Config {
+            frequency: self.requested_frequency(),
+            output_mode: self.output_mode(),
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Datasheet numbers the driver is built on
+//!
+//! The output frequency is f = 2^OCT * [`BASE_FREQUENCY`] / (2 - DAC / 1024), with OCT up to
+//! [`OCT_MAX`] and DAC up to [`DAC_MAX`]. [`OCTAVE_RANGES`] is checked against it when the crate is
+//! built.
+
+/// Frequency in Hz that OCT and DAC scale, 2^OCT * 2078 Hz at DAC = 1024 if that existed
+pub const BASE_FREQUENCY: u32 = 2078;
+
+/// Highest octave
+pub const OCT_MAX: u16 = 15;
+
+/// Highest DAC code
+pub const DAC_MAX: u16 = 1023;
+
+/// Frequency range (min, max) in Hz covered by each octave, from the datasheet
+///
+/// Adjacent octaves don't overlap and leave small gaps.
+pub const OCTAVE_RANGES: [(u32, u32); OCT_MAX as usize + 1] = [
+    /* 0 */ (1_039, 2_076),
+    /* 1 */ (2_078, 4_152),
+    /* 2 */ (4_156, 8_304),
+    /* 3 */ (8_312, 16_610),
+    /* 4 */ (16_620, 33_220),
+    /* 5 */ (33_250, 66_430),
+    /* 6 */ (66_500, 132_900),
+    /* 7 */ (133_000, 265_700),
+    /* 8 */ (266_000, 531_400),
+    /* 9 */ (532_000, 1_063_000),
+    /* 10 */ (1_064_000, 2_126_000),
+    /* 11 */ (2_128_000, 4_252_000),
+    /* 12 */ (4_256_000, 8_503_000),
+    /* 13 */ (8_511_000, 17_010_000),
+    /* 14 */ (17_020_000, 34_010_000),
+    /* 15 */ (34_050_000, 68_030_000),
+];
+
+// a typo in the table above fails the build
+const _: () = assert!(octaves_ascend(), "OCTAVE_RANGES isn't ascending with gaps");
+const _: () = assert!(
+    octaves_match_formula(),
+    "OCTAVE_RANGES deviates from the formula"
+);
+
+/// Every octave is a non-empty range and ends below the start of the next one
+const fn octaves_ascend() -> bool {
+    let mut oct = 0;
+    while oct <= OCT_MAX as usize {
+        let (min, max) = OCTAVE_RANGES[oct];
+        if min >= max || (oct < OCT_MAX as usize && max >= OCTAVE_RANGES[oct + 1].0) {
+            return false;
+        }
+        oct += 1;
+    }
+    true
+}
+
+/// Every bound is the frequency of DAC = 0 or DAC = [`DAC_MAX`] rounded to the four significant
+/// digits of the datasheet, i.e. within 500 ppm
+const fn octaves_match_formula() -> bool {
+    let mut oct = 0;
+    while oct <= OCT_MAX as usize {
+        let (min, max) = OCTAVE_RANGES[oct];
+        let k = (BASE_FREQUENCY as u64) << (10 + oct);
+        let bottom = k / 2048;
+        let top = k / (2048 - DAC_MAX as u64);
+        if !within_500_ppm(min as u64, bottom) || !within_500_ppm(max as u64, top) {
+            return false;
+        }
+        oct += 1;
+    }
+    true
+}
+
+const fn within_500_ppm(value: u64, exact: u64) -> bool {
+    value.abs_diff(exact) * 2_000 <= exact
+}
+
+/// Guaranteed frequency accuracy of an uncalibrated part in ppm for each octave, from the
+/// datasheet
+///
+/// 1.1 % up to 8 MHz, octave 12, and 1.5 % above. Used by
+/// [`accuracy::accuracy_bounds()`](crate::accuracy::accuracy_bounds).
+pub const ACCURACY_PPM: [u32; OCT_MAX as usize + 1] = [
+    11_000, 11_000, 11_000, 11_000, 11_000, 11_000, 11_000, 11_000, 11_000, 11_000, 11_000, 11_000,
+    11_000, 15_000, 15_000, 15_000,
+];
+
+/// DAC codes at each end of an octave rejected by
+/// [`LTC6904::set_frequency_checked()`](crate::LTC6904::set_frequency_checked)
+pub const DAC_EDGE_CODES: u16 = 8;
+
+/// DAC codes (min, max) accepted in each octave by
+/// [`LTC6904::set_frequency_checked()`](crate::LTC6904::set_frequency_checked)
+///
+/// The edge codes of every octave, where the accuracy of the device degrades, are left out.
+pub const CHECKED_DAC_RANGES: [(u16, u16); OCT_MAX as usize + 1] =
+    [(DAC_EDGE_CODES, DAC_MAX - DAC_EDGE_CODES); OCT_MAX as usize + 1];
+
+/// Lowest frequency in Hz accepted by [`freq_to_reg()`](crate::freq_to_reg) and
+/// [`LTC6904::set_frequency()`](crate::LTC6904::set_frequency)
+pub const FREQUENCY_MIN: u32 = OCTAVE_RANGES[0].0;
+
+/// Highest frequency in Hz accepted by [`freq_to_reg()`](crate::freq_to_reg) and
+/// [`LTC6904::set_frequency()`](crate::LTC6904::set_frequency)
+pub const FREQUENCY_MAX: u32 = OCTAVE_RANGES[OCT_MAX as usize].1;
+
+/// Time the oscillator is given to settle after [`LTC6904::wake()`](crate::LTC6904::wake)
+/// powered it up, before the outputs are enabled
+pub const WAKE_SETTLING_US: u32 = 100;
+
+/// Time the supply is given to ramp up after
+/// [`PoweredLtc6904::power_rail_up()`](crate::power::PoweredLtc6904::power_rail_up) switched it
+/// on, before the configuration is written
+pub const POWER_RAIL_SETTLING_US: u32 = 1_000;
+
+/// Typical supply current in µA of the running oscillator core at 3 V, see
+/// [`current::supply_current()`](crate::current::supply_current)
+pub const SUPPLY_CURRENT_CORE_UA: u32 = 1_700;
+
+/// Typical supply current in µA added per MHz by every running output at 3 V without load
+pub const SUPPLY_CURRENT_UA_PER_MHZ_OUTPUT: u32 = 17;
+
+/// Typical supply current in µA with the oscillator powered down (CNF = 11)
+pub const SUPPLY_CURRENT_POWER_DOWN_UA: u32 = 1;
+
+/// Typical temperature coefficient of the output frequency in ppm/°C, from the datasheet
+pub const DRIFT_PPM_PER_C: i32 = 10;
+
+/// Temperature in °C without drift, at which the datasheet specifies the accuracy
+pub const DRIFT_REFERENCE_C: i16 = 25;
+
+/// 7 bit address with the ADR pin low
+pub const ADDRESS_LOW: u8 = 0x17;
+
+/// 7 bit address with the ADR pin high
+pub const ADDRESS_HIGH: u8 = 0x16;
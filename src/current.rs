@@ -0,0 +1,75 @@
+//! Supply current estimates for power budgets
+//!
+//! The supply current of the device is a constant part for the oscillator core plus a part
+//! growing with the frequency for every running output, see the "Supply Current vs Frequency"
+//! curves of the datasheet. [`supply_current()`] follows the typical curve at 3 V without load,
+//! linearized, which is close enough for a battery budget but no guarantee for a single part.
+//!
+//! A capacitive load on an output draws C × V × f on top, estimated by [`load_current()`].
+//!
+//! ```
+//! use ltc690x::{current::supply_current, freq_to_reg, Register};
+//!
+//! let reg = Register::new(freq_to_reg(1_000_000).unwrap());
+//! assert!(supply_current(reg).0 < 2_000);
+//! ```
+
+use core::convert::TryFrom;
+
+#[cfg(feature = "driver")]
+use embedded_hal::{blocking::i2c::Write, digital::v2::OutputPin};
+
+#[cfg(feature = "driver")]
+use crate::LTC6904;
+use crate::{
+    consts::{
+        SUPPLY_CURRENT_CORE_UA, SUPPLY_CURRENT_POWER_DOWN_UA, SUPPLY_CURRENT_UA_PER_MHZ_OUTPUT,
+    },
+    reg_to_freq, OutputMode, Register,
+};
+
+/// Current in microamperes
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MicroAmps(pub u32);
+
+impl core::ops::Add for MicroAmps {
+    type Output = MicroAmps;
+
+    /// Saturating, like the estimates themselves
+    fn add(self, other: MicroAmps) -> MicroAmps {
+        MicroAmps(self.0.saturating_add(other.0))
+    }
+}
+
+/// Typical supply current without load for the configuration in `reg`
+pub fn supply_current(reg: Register) -> MicroAmps {
+    let outputs = match reg.output_mode() {
+        OutputMode::PowerDown => return MicroAmps(SUPPLY_CURRENT_POWER_DOWN_UA),
+        OutputMode::ClkOnly | OutputMode::ClkInvOnly => 1,
+        OutputMode::BothOutputs => 2,
+    };
+    let f = reg_to_freq(reg.bits()) as u64;
+    let dynamic = outputs * SUPPLY_CURRENT_UA_PER_MHZ_OUTPUT as u64 * f / 1_000_000;
+    MicroAmps(SUPPLY_CURRENT_CORE_UA + dynamic as u32)
+}
+
+/// Current charging a load of `load_pf` picofarads at `f` Hz from a supply of `supply_mv`
+/// millivolts, for every output driving one
+pub fn load_current(f: u32, load_pf: u32, supply_mv: u32) -> MicroAmps {
+    // pF * mV * Hz is 1e-15 A
+    let fa = load_pf as u64 * supply_mv as u64 * f as u64;
+    MicroAmps(u32::try_from(fa / 1_000_000_000).unwrap_or(u32::MAX))
+}
+
+#[cfg(feature = "driver")]
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// [`supply_current()`] of the cached configuration, with the outputs enabled
+    pub fn estimated_supply_current(&self) -> MicroAmps {
+        supply_current(self.reg)
+    }
+}
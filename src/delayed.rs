@@ -0,0 +1,184 @@
+//! Driver owning its delay
+//!
+//! The timed operations of [`LTC6904`] take a `&mut delay` on every call. Applications with one
+//! delay dedicated to the oscillator can hand it over with [`LTC6904::with_delay()`] instead, the
+//! returned [`DelayedLtc6904`] offers the same operations without the parameter and derefs to the
+//! driver for everything else.
+//!
+//! The owned delay also allows gating the outputs around [`DelayedLtc6904::write_out()`] with
+//! [`DelayedLtc6904::gate_writes()`], for loads that must not see the clock change.
+//!
+//! ```
+//! # #[cfg(feature = "sim")] {
+//! use embedded_hal_mock::delay::MockNoop;
+//! use ltc690x::{sim::Ltc6904Sim, Address, LTC6904};
+//!
+//! let sim = Ltc6904Sim::new(Address::AddressLow);
+//! let ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+//! let mut ltc = ltc.with_delay(MockNoop::new());
+//!
+//! ltc.deep_sleep().unwrap();
+//! ltc.wake().unwrap();
+//! ltc.ramp_to(1_000_000, 100_000, 50).unwrap();
+//! assert!(sim.is_output_enabled());
+//! # }
+//! ```
+
+use core::ops::{Deref, DerefMut};
+
+use embedded_hal::{
+    blocking::{delay::DelayUs, i2c::Write},
+    digital::v2::{OutputPin, StatefulOutputPin},
+};
+
+use crate::{profile::Profile, Error, LTC6904};
+
+/// [`LTC6904`] with the delay for its timed operations, created by [`LTC6904::with_delay()`]
+pub struct DelayedLtc6904<I2C, PIN, D>
+where
+    I2C: Write,
+    PIN: OutputPin,
+{
+    ltc: LTC6904<I2C, PIN>,
+    delay: D,
+    gate_settling_us: Option<u32>,
+}
+
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// Hands `delay` to the driver for its timed operations
+    pub fn with_delay<D: DelayUs<u32>>(self, delay: D) -> DelayedLtc6904<I2C, PIN, D> {
+        DelayedLtc6904 {
+            ltc: self,
+            delay,
+            gate_settling_us: None,
+        }
+    }
+}
+
+impl<I2C, E, PIN, D> DelayedLtc6904<I2C, PIN, D>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+    D: DelayUs<u32>,
+{
+    /// [`LTC6904::wake()`]
+    pub fn wake(&mut self) -> Result<(), Error<E, PIN::Error>> {
+        self.ltc.wake(&mut self.delay)
+    }
+
+    /// [`LTC6904::enable_output_for()`]
+    pub fn enable_output_for(&mut self, duration_us: u32) -> Result<(), PIN::Error> {
+        self.ltc.enable_output_for(duration_us, &mut self.delay)
+    }
+
+    /// [`LTC6904::burst()`]
+    pub fn burst(&mut self, cycles: u32) -> Result<u32, PIN::Error> {
+        self.ltc.burst(cycles, &mut self.delay)
+    }
+
+    /// [`LTC6904::tone()`]
+    pub fn tone(&mut self, f: u32, duration_us: u32) -> Result<(), Error<E, PIN::Error>> {
+        self.ltc.tone(f, duration_us, &mut self.delay)
+    }
+
+    /// [`LTC6904::ramp_to()`]
+    pub fn ramp_to(
+        &mut self,
+        target: u32,
+        max_step_hz: u32,
+        step_us: u32,
+    ) -> Result<(), Error<E, PIN::Error>> {
+        self.ltc
+            .ramp_to(target, max_step_hz, step_us, &mut self.delay)
+    }
+
+    /// [`LTC6904::hop()`]
+    pub fn hop(
+        &mut self,
+        center: u32,
+        deviation_ppm: u32,
+        dwell_us: u32,
+    ) -> Result<(), Error<E, PIN::Error>> {
+        self.ltc
+            .hop(center, deviation_ppm, dwell_us, &mut self.delay)
+    }
+
+    /// [`LTC6904::play()`]
+    pub fn play(&mut self, profile: &Profile) -> Result<(), Error<E, PIN::Error>> {
+        self.ltc.play(profile, &mut self.delay)
+    }
+
+    /// The owned delay, e.g. for waits of the application between operations
+    pub fn delay_mut(&mut self) -> &mut D {
+        &mut self.delay
+    }
+
+    /// Returns the driver and the delay
+    pub fn into_parts(self) -> (LTC6904<I2C, PIN>, D) {
+        (self.ltc, self.delay)
+    }
+}
+
+impl<I2C, E, PIN, D> DelayedLtc6904<I2C, PIN, D>
+where
+    I2C: Write<Error = E>,
+    PIN: StatefulOutputPin,
+    D: DelayUs<u32>,
+{
+    /// Makes [`DelayedLtc6904::write_out()`] disable the outputs for the write and enable them
+    /// `settling_us` microseconds after it, or write with the outputs left alone for `None`
+    ///
+    /// Meant for glitch-sensitive loads, the runt pulses of the switch and the oscillator settling
+    /// on the new frequency don't reach them. A time of
+    /// [`Chip::SETTLING_US`](crate::chip::Chip::SETTLING_US) covers the settling. Only
+    /// [`DelayedLtc6904::write_out()`] is gated, the timed operations manage the outputs
+    /// themselves, and the writes of the driver reached through `Deref`, e.g.
+    /// [`LTC6904::configure()`], [`LTC6904::program_frequency()`] and [`LTC6904::commit()`],
+    /// leave them alone.
+    pub fn gate_writes(&mut self, settling_us: Option<u32>) {
+        self.gate_settling_us = settling_us;
+    }
+
+    /// [`LTC6904::write_out()`], surrounded by disabling and enabling the outputs if enabled with
+    /// [`DelayedLtc6904::gate_writes()`]
+    ///
+    /// Disabled outputs are left disabled. Enabled ones are enabled again also if the write
+    /// failed, as the device keeps its previous frequency then.
+    pub fn write_out(&mut self) -> Result<(), Error<E, PIN::Error>> {
+        let settling_us = match self.gate_settling_us {
+            Some(us) if self.ltc.is_output_enabled().map_err(Error::Pin)? => us,
+            _ => return self.ltc.write_out().map_err(Error::I2c),
+        };
+        self.ltc.disable_output().map_err(Error::Pin)?;
+        let written = self.ltc.write_out().map_err(Error::I2c);
+        self.delay.delay_us(settling_us);
+        self.ltc.enable_output().map_err(Error::Pin)?;
+        written
+    }
+}
+
+impl<I2C, PIN, D> Deref for DelayedLtc6904<I2C, PIN, D>
+where
+    I2C: Write,
+    PIN: OutputPin,
+{
+    type Target = LTC6904<I2C, PIN>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.ltc
+    }
+}
+
+impl<I2C, PIN, D> DerefMut for DelayedLtc6904<I2C, PIN, D>
+where
+    I2C: Write,
+    PIN: OutputPin,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.ltc
+    }
+}
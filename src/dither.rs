@@ -0,0 +1,139 @@
+//! Dithering between adjacent DAC codes
+//!
+//! The DAC steps are up to about 0.1 % apart. Where the clock feeds something averaging over
+//! time, e.g. a frequency counter or a PLL with a slow loop, alternating between the two codes
+//! around the target gets the average frequency closer to it than either code alone.
+//!
+//! [`Dither`] yields the register word for each period with a first order sigma-delta pattern,
+//! which spreads the upper code evenly. [`LTC6904::dither_step()`] writes them, it has to be
+//! called at a fixed rate, e.g. from a timer interrupt.
+
+#[cfg(feature = "driver")]
+use embedded_hal::{blocking::i2c::Write, digital::v2::OutputPin};
+
+use crate::{
+    achievable::{code_to_reg, reg_to_code, CODES},
+    calibration::exact,
+    fixed_to_reg, FrequencyError, FRAC_BITS,
+};
+#[cfg(feature = "driver")]
+use crate::{calibration::nominal, Register, LTC6904};
+
+/// Steps of the duty cycle in [`Dither::duty()`]
+pub const DUTY_SCALE: u32 = 1 << 16;
+
+/// Register words alternating between the two codes around a target frequency
+///
+/// Created by [`Dither::new()`] or [`LTC6904::dither()`]. The iterator never ends.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dither {
+    /// code index of the code below and above the target
+    lo: u16,
+    hi: u16,
+    /// share of the upper code in 1 / DUTY_SCALE
+    duty: u32,
+    acc: u32,
+    frequency: u32,
+}
+
+impl Dither {
+    /// Dithers around `f` in Hz, without any calibration
+    pub fn new(f: u32) -> Result<Self, FrequencyError> {
+        Self::from_fixed(f, (f as u64) << FRAC_BITS)
+    }
+
+    /// Dithers around the fixed point frequency `fixed`, which is output as `f`
+    pub(crate) fn from_fixed(f: u32, fixed: u64) -> Result<Self, FrequencyError> {
+        let code = reg_to_code(fixed_to_reg(fixed)?);
+        let (num, den) = exact(code_to_reg(code));
+        let target = fixed as i128;
+        let (lo, hi) = match (num << FRAC_BITS).cmp(&(target * den)) {
+            core::cmp::Ordering::Greater => (code.saturating_sub(1), code),
+            core::cmp::Ordering::Equal => (code, code),
+            core::cmp::Ordering::Less => (code, (code + 1).min(CODES - 1)),
+        };
+        let (num_lo, den_lo) = exact(code_to_reg(lo));
+        let (num_hi, den_hi) = exact(code_to_reg(hi));
+        // (target - f_lo) / (f_hi - f_lo), with target carrying FRAC_BITS
+        let span = (num_hi * den_lo - num_lo * den_hi) << FRAC_BITS;
+        let duty = if span == 0 {
+            0
+        } else {
+            let above = (target * den_lo - (num_lo << FRAC_BITS)).max(0) * den_hi;
+            let scaled = above * DUTY_SCALE as i128;
+            ((scaled + span / 2) / span).min(DUTY_SCALE as i128) as u32
+        };
+        let mut dither = Self {
+            lo,
+            hi,
+            duty,
+            acc: 0,
+            frequency: f,
+        };
+        if duty == DUTY_SCALE {
+            dither.lo = hi;
+            dither.duty = 0;
+        }
+        Ok(dither)
+    }
+
+    /// Register words (with CNF = 0) of the codes below and above the target
+    ///
+    /// Both are the same if the target is achievable exactly or lies outside of the codes.
+    pub fn codes(&self) -> (u16, u16) {
+        (code_to_reg(self.lo), code_to_reg(self.hi))
+    }
+
+    /// Share of the periods on the upper code, in 1 / [`DUTY_SCALE`]
+    pub fn duty(&self) -> u32 {
+        self.duty
+    }
+
+    /// Target frequency in Hz
+    pub fn frequency(&self) -> u32 {
+        self.frequency
+    }
+}
+
+impl Iterator for Dither {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        self.acc += self.duty;
+        let code = if self.acc >= DUTY_SCALE {
+            self.acc -= DUTY_SCALE;
+            self.hi
+        } else {
+            self.lo
+        };
+        Some(code_to_reg(code))
+    }
+}
+
+#[cfg(feature = "driver")]
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// [`Dither`] around `f`, including the correction set with [`LTC6904::calibrate()`]
+    pub fn dither(&self, f: u32) -> Result<Dither, FrequencyError> {
+        Dither::from_fixed(f, nominal(f.into(), self.calibration_ppm))
+    }
+
+    /// Programs the next code of `dither`, writing the register only if the code changes
+    ///
+    /// The output mode is kept.
+    pub fn dither_step(&mut self, dither: &mut Dither) -> Result<(), E> {
+        if let Some(next) = dither.next() {
+            let next = Register::new(next);
+            self.frequ = dither.frequency.into();
+            self.reg = self.reg.with_oct(next.oct()).with_dac(next.dac());
+            if !self.is_written() {
+                self.write_out()?;
+            }
+        }
+        Ok(())
+    }
+}
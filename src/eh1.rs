@@ -0,0 +1,166 @@
+//! Adapters for embedded-hal 1.0 buses and pins (requires the `eh1` feature)
+//!
+//! The driver is implemented against the embedded-hal 0.2 traits. [`Eh1I2c`] and [`Eh1Pin`]
+//! wrap the bus and OE pin of a HAL which only implements the 1.0 traits, so the same driver
+//! works with HALs of both generations:
+//!
+//! ```
+//! # use core::convert::Infallible;
+//! # use embedded_hal_1::{digital, i2c};
+//! use ltc690x::{Address, LTC6904};
+//!
+//! # struct Bus;
+//! # impl i2c::ErrorType for Bus { type Error = Infallible; }
+//! # impl i2c::I2c for Bus {
+//! #     fn transaction(&mut self, _: u8, _: &mut [i2c::Operation<'_>]) -> Result<(), Infallible> {
+//! #         Ok(())
+//! #     }
+//! # }
+//! # struct Oe;
+//! # impl digital::ErrorType for Oe { type Error = Infallible; }
+//! # impl digital::OutputPin for Oe {
+//! #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+//! #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+//! # }
+//! # let (i2c, oe) = (Bus, Oe);
+//! // `i2c` and `oe` only implement the embedded-hal 1.0 traits
+//! let mut ltc = LTC6904::new_eh1(i2c, Address::AddressLow, oe);
+//! ltc.configure(1_000_000, ltc690x::OutputMode::BothOutputs).unwrap();
+//! ```
+//!
+//! Bus errors of such a HAL keep their [`ErrorKind`], [`Error::i2c_error_kind()`] tells a missing
+//! device apart from a contended bus.
+
+use core::cell::RefCell;
+
+use embedded_hal::{
+    blocking::i2c::{Read, Write, WriteRead},
+    digital::v2::{OutputPin, StatefulOutputPin},
+};
+use embedded_hal_1::{
+    digital,
+    i2c::{self, ErrorKind, I2c, NoAcknowledgeSource},
+};
+
+use crate::{Address, Error, LTC6904};
+
+/// embedded-hal 1.0 I2C bus usable as embedded-hal 0.2 bus
+pub struct Eh1I2c<I2C>(I2C);
+
+impl<I2C: I2c> Eh1I2c<I2C> {
+    pub fn new(i2c: I2C) -> Self {
+        Self(i2c)
+    }
+
+    /// Returns the wrapped bus
+    pub fn into_inner(self) -> I2C {
+        self.0
+    }
+}
+
+impl<I2C: I2c> Read for Eh1I2c<I2C> {
+    type Error = I2C::Error;
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.0.read(address, buffer)
+    }
+}
+
+impl<I2C: I2c> Write for Eh1I2c<I2C> {
+    type Error = I2C::Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.0.write(address, bytes)
+    }
+}
+
+impl<I2C: I2c> WriteRead for Eh1I2c<I2C> {
+    type Error = I2C::Error;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.0.write_read(address, bytes, buffer)
+    }
+}
+
+/// embedded-hal 1.0 output pin usable as embedded-hal 0.2 pin
+///
+/// Reading back the pin state takes `&mut self` in embedded-hal 1.0, the pin is kept in a
+/// [`RefCell`] to provide the 0.2 [`StatefulOutputPin`] on top of it.
+pub struct Eh1Pin<PIN>(RefCell<PIN>);
+
+impl<PIN: digital::OutputPin> Eh1Pin<PIN> {
+    pub fn new(pin: PIN) -> Self {
+        Self(RefCell::new(pin))
+    }
+
+    /// Returns the wrapped pin
+    pub fn into_inner(self) -> PIN {
+        self.0.into_inner()
+    }
+}
+
+impl<PIN: digital::OutputPin> OutputPin for Eh1Pin<PIN> {
+    type Error = PIN::Error;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0.get_mut().set_low()
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.0.get_mut().set_high()
+    }
+}
+
+impl<PIN: digital::StatefulOutputPin> StatefulOutputPin for Eh1Pin<PIN> {
+    fn is_set_high(&self) -> Result<bool, Self::Error> {
+        self.0.borrow_mut().is_set_high()
+    }
+
+    fn is_set_low(&self) -> Result<bool, Self::Error> {
+        self.0.borrow_mut().is_set_low()
+    }
+}
+
+impl<I2C, PIN> LTC6904<Eh1I2c<I2C>, Eh1Pin<PIN>>
+where
+    I2C: I2c,
+    PIN: digital::OutputPin,
+{
+    /// Creates a driver from an embedded-hal 1.0 bus and pin
+    ///
+    /// [`LTC6904::free()`] returns the adapters, [`Eh1I2c::into_inner()`] and
+    /// [`Eh1Pin::into_inner()`] the original bus and pin.
+    pub fn new_eh1(i2c: I2C, address: Address, out_enable: PIN) -> Self {
+        Self::new(Eh1I2c::new(i2c), address, Eh1Pin::new(out_enable))
+    }
+}
+
+impl<E: i2c::Error, PE> Error<E, PE> {
+    /// Kind of the bus error, `None` for errors not coming from the bus
+    pub fn i2c_error_kind(&self) -> Option<ErrorKind> {
+        match self {
+            Error::I2c(e) => Some(e.kind()),
+            _ => None,
+        }
+    }
+
+    /// Whether the device didn't acknowledge its address, e.g. because it's absent or unpowered
+    pub fn is_device_absent(&self) -> bool {
+        matches!(
+            self.i2c_error_kind(),
+            Some(ErrorKind::NoAcknowledge(
+                NoAcknowledgeSource::Address | NoAcknowledgeSource::Unknown
+            ))
+        )
+    }
+
+    /// Whether another controller won the bus, a retry may succeed
+    pub fn is_bus_contention(&self) -> bool {
+        self.i2c_error_kind() == Some(ErrorKind::ArbitrationLoss)
+    }
+}
@@ -0,0 +1,98 @@
+//! Test pattern for end-of-line testing
+//!
+//! [`eol_pattern()`] yields the bottom, middle and top of the range of every octave, 48 points
+//! covering the full range of the device, each with the register word to write and the range
+//! any part has to hit. Test firmware steps through them with
+//! [`LTC6904::write_reg()`](crate::LTC6904::write_reg) while an external counter measures CLK:
+//!
+//! ```
+//! use ltc690x::eol::eol_pattern;
+//!
+//! # fn measure_clk(_reg: u16) -> u32 { 12_000_000 }
+//! for point in eol_pattern() {
+//!     // ltc.write_reg(point.reg)?;
+//!     let measured = measure_clk(point.reg);
+//!     if !point.accepts(measured) {
+//!         // reject the board
+//!     }
+//! }
+//! assert_eq!(eol_pattern().len(), 48);
+//! ```
+//!
+//! The points don't depend on a calibration, a part needing one to hit them is out of spec.
+
+use crate::{
+    accuracy::{tolerance_ppm, AccuracyBounds},
+    consts::{DAC_MAX, OCT_MAX},
+    reg_error_ppm, reg_to_freq, OutputMode, Register,
+};
+
+/// Where in its octave a [`TestPoint`] lies
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OctavePosition {
+    /// lowest frequency of the octave
+    Bottom,
+    /// middle of the range of the octave
+    Middle,
+    /// highest frequency of the octave
+    Top,
+}
+
+/// One frequency of the [`eol_pattern()`]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TestPoint {
+    /// octave the point tests
+    pub oct: u16,
+    /// position of the point in the octave
+    pub position: OctavePosition,
+    /// register word to write, with both outputs enabled
+    pub reg: u16,
+    /// range the output of any part lies in, see
+    /// [`accuracy_bounds()`](crate::accuracy::accuracy_bounds), around the frequency of an ideal
+    /// part in `bounds.nominal`
+    pub bounds: AccuracyBounds,
+}
+
+impl TestPoint {
+    /// Whether a measured output frequency in Hz is within the guaranteed accuracy
+    pub fn accepts(&self, measured_hz: u32) -> bool {
+        (self.bounds.min..=self.bounds.max).contains(&measured_hz)
+    }
+}
+
+const POSITIONS: [OctavePosition; 3] = [
+    OctavePosition::Bottom,
+    OctavePosition::Middle,
+    OctavePosition::Top,
+];
+
+/// DAC code closest to the middle between the lowest and highest frequency of an octave
+///
+/// 2048 - 2 / (1 / 2048 + 1 / 1025)
+const MIDDLE_DAC: u16 = 682;
+
+/// Bottom, middle and top of every octave, from the lowest frequency of the device up
+///
+/// The bottom and top are the lowest and highest DAC code, the middle the code closest to the
+/// frequency between them.
+pub fn eol_pattern() -> impl ExactSizeIterator<Item = TestPoint> + Clone {
+    (0..(OCT_MAX as usize + 1) * POSITIONS.len()).map(|i| {
+        let oct = (i / POSITIONS.len()) as u16;
+        let position = POSITIONS[i % POSITIONS.len()];
+        let dac = match position {
+            OctavePosition::Bottom => 0,
+            OctavePosition::Middle => MIDDLE_DAC,
+            OctavePosition::Top => DAC_MAX,
+        };
+        let reg = Register::from_fields(oct, dac, OutputMode::BothOutputs.bits()).bits();
+        let nominal = reg_to_freq(reg);
+        TestPoint {
+            oct,
+            position,
+            reg,
+            bounds: AccuracyBounds::new(nominal, reg_error_ppm(reg, nominal), tolerance_ppm(reg)),
+        }
+    })
+}
@@ -0,0 +1,91 @@
+//! Frequencies with millihertz resolution
+//!
+//! The DAC steps by less than 1 Hz at the low end of the range, so whole Hz can't express every
+//! frequency the device can be tuned to. [`Frequency`] keeps millihertz, which
+//! [`LTC6904::set_frequency_precise()`](crate::LTC6904::set_frequency_precise) takes.
+//!
+//! [`format_frequency()`] renders one for a display or log without pulling in `core::fmt`.
+
+/// Frequency in millihertz
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Frequency(u64);
+
+impl Frequency {
+    pub const fn from_millihertz(millihertz: u64) -> Self {
+        Self(millihertz)
+    }
+
+    pub const fn from_hertz(hertz: u32) -> Self {
+        Self(hertz as u64 * 1_000)
+    }
+
+    pub const fn from_kilohertz(kilohertz: u32) -> Self {
+        Self(kilohertz as u64 * 1_000_000)
+    }
+
+    pub const fn from_megahertz(megahertz: u32) -> Self {
+        Self(megahertz as u64 * 1_000_000_000)
+    }
+
+    pub const fn millihertz(self) -> u64 {
+        self.0
+    }
+
+    /// Frequency in Hz, rounded and saturating
+    pub const fn hz(self) -> u32 {
+        let hz = self.0 / 1_000 + (self.0 % 1_000 >= 500) as u64;
+        if hz > u32::MAX as u64 {
+            u32::MAX
+        } else {
+            hz as u32
+        }
+    }
+}
+
+impl From<u32> for Frequency {
+    /// Frequency from Hz
+    fn from(hz: u32) -> Self {
+        Self::from_hertz(hz)
+    }
+}
+
+/// Length of the longest string [`format_frequency()`] produces
+pub const FORMATTED_LEN: usize = 19;
+
+/// Formats `f` with three decimals in Hz, kHz or MHz, like "1.006 MHz", without `core::fmt`
+///
+/// The unit is the largest one keeping at least 1 before the decimal point, the last decimal is
+/// rounded.
+pub fn format_frequency(f: Frequency, buf: &mut [u8; FORMATTED_LEN]) -> &str {
+    let millihertz = f.millihertz();
+    let round = |div: u64| millihertz / div + (millihertz % div >= div / 2) as u64;
+    let (thousandths, unit): (u64, &[u8]) = if round(1_000_000) >= 1_000 {
+        (round(1_000_000), b" MHz")
+    } else if round(1_000) >= 1_000 {
+        (round(1_000), b" kHz")
+    } else {
+        (millihertz, b" Hz")
+    };
+
+    let mut digits = [0u8; 20];
+    let mut n = thousandths;
+    let mut len = 0;
+    while n > 0 || len < 4 {
+        digits[len] = b'0' + (n % 10) as u8;
+        n /= 10;
+        len += 1;
+    }
+    let mut pos = 0;
+    for (i, d) in digits[..len].iter().rev().enumerate() {
+        if i == len - 3 {
+            buf[pos] = b'.';
+            pos += 1;
+        }
+        buf[pos] = *d;
+        pos += 1;
+    }
+    buf[pos..pos + unit.len()].copy_from_slice(unit);
+    pos += unit.len();
+    core::str::from_utf8(&buf[..pos]).unwrap_or("")
+}
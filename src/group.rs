@@ -0,0 +1,201 @@
+//! Retuning several devices together
+//!
+//! Boards with more than one LTC6904, e.g. on a shared bus, sometimes need their clocks to change
+//! at the same time. [`write_all()`] calculates every register word before it starts writing, so
+//! only the bus transfers lie between the changes of the first and the last device.
+//!
+//! Two devices generating related clocks, e.g. f and f/2 or f and 2f/3, are kept on their ratio by
+//! [`LinkedOscillators`], whichever of the two frequencies is changed.
+
+use embedded_hal::{
+    blocking::{delay::DelayUs, i2c::Write},
+    digital::v2::OutputPin,
+};
+
+use crate::{calibration, chip, ratio::multiple_of, Error, Frequency, Register, LTC6904};
+
+/// Programs `frequencies[i]` on `devices[i]` with back-to-back writes
+///
+/// Nothing is changed if one of the frequencies is rejected, see [`LTC6904::set_frequency()`].
+/// If a write fails, the devices before it have been written and the others keep the new
+/// configuration pending.
+pub fn write_all<I2C, E, PIN, const N: usize>(
+    devices: &mut [&mut LTC6904<I2C, PIN>; N],
+    frequencies: [u32; N],
+) -> Result<(), Error<E, PIN::Error>>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    stage_all(devices, frequencies.map(Frequency::from))?;
+    for ltc in devices.iter_mut() {
+        ltc.write_out().map_err(Error::I2c)?;
+    }
+    Ok(())
+}
+
+/// [`write_all()`] with a pause of `spacing_us` microseconds between two writes
+///
+/// For circuits that need their clocks to change in a fixed order rather than at once.
+pub fn write_all_spaced<I2C, E, PIN, D, const N: usize>(
+    devices: &mut [&mut LTC6904<I2C, PIN>; N],
+    frequencies: [u32; N],
+    spacing_us: u32,
+    delay: &mut D,
+) -> Result<(), Error<E, PIN::Error>>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+    D: DelayUs<u32>,
+{
+    stage_all(devices, frequencies.map(Frequency::from))?;
+    for (i, ltc) in devices.iter_mut().enumerate() {
+        if i > 0 {
+            delay.delay_us(spacing_us);
+        }
+        ltc.write_out().map_err(Error::I2c)?;
+    }
+    Ok(())
+}
+
+/// Caches the register words for all devices, or none if one of the frequencies is rejected
+fn stage_all<I2C, E, PIN, const N: usize>(
+    devices: &mut [&mut LTC6904<I2C, PIN>; N],
+    frequencies: [Frequency; N],
+) -> Result<(), Error<E, PIN::Error>>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    let mut regs = [0; N];
+    for ((reg, ltc), f) in regs.iter_mut().zip(devices.iter()).zip(frequencies) {
+        *reg =
+            calibration::checked_reg::<chip::Ltc6904>(f, ltc.calibration_ppm, ltc.max_error_ppm)?;
+    }
+    for ((ltc, reg), f) in devices.iter_mut().zip(regs).zip(frequencies) {
+        let reg = Register::new(reg);
+        ltc.reg = ltc.reg.with_oct(reg.oct()).with_dac(reg.dac());
+        ltc.frequ = f;
+    }
+    Ok(())
+}
+
+/// Device [`LinkedOscillators`] writes first
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum WriteOrder {
+    #[default]
+    PrimaryFirst,
+    SecondaryFirst,
+}
+
+/// Two devices with the secondary running at `n / d` times the primary frequency
+///
+/// Changing either frequency retunes the other one to keep the ratio. Both register words are
+/// calculated before the first write, so a frequency out of range for either device changes
+/// nothing, and the writes follow the [`WriteOrder`] back to back. If the first write fails,
+/// neither device is written and both keep the new configuration pending.
+pub struct LinkedOscillators<I2C, PIN>
+where
+    I2C: Write,
+    PIN: OutputPin,
+{
+    primary: LTC6904<I2C, PIN>,
+    secondary: LTC6904<I2C, PIN>,
+    n: u32,
+    d: u32,
+    order: WriteOrder,
+}
+
+impl<I2C, E, PIN> LinkedOscillators<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// Links `secondary` to `primary` at the ratio `n / d`, neither device is written
+    ///
+    /// A ratio with `n` or `d` of 0 rejects every frequency.
+    pub fn new(primary: LTC6904<I2C, PIN>, secondary: LTC6904<I2C, PIN>, n: u32, d: u32) -> Self {
+        Self {
+            primary,
+            secondary,
+            n,
+            d,
+            order: WriteOrder::default(),
+        }
+    }
+
+    pub fn set_write_order(&mut self, order: WriteOrder) {
+        self.order = order;
+    }
+
+    /// Ratio (n, d) of the secondary to the primary frequency
+    pub fn ratio(&self) -> (u32, u32) {
+        (self.n, self.d)
+    }
+
+    /// Programs `f` Hz on the primary and `n / d` × `f` on the secondary, returns the
+    /// [`LinkedOscillators::ratio_error_ppm()`]
+    pub fn set_primary(&mut self, f: u32) -> Result<i32, Error<E, PIN::Error>> {
+        let secondary = multiple_of(f, self.n, self.d)?;
+        self.write_both(f.into(), secondary)
+    }
+
+    /// Programs `f` Hz on the secondary and `d / n` × `f` on the primary, returns the
+    /// [`LinkedOscillators::ratio_error_ppm()`]
+    pub fn set_secondary(&mut self, f: u32) -> Result<i32, Error<E, PIN::Error>> {
+        let primary = multiple_of(f, self.d, self.n)?;
+        self.write_both(primary, f.into())
+    }
+
+    fn write_both(
+        &mut self,
+        primary: Frequency,
+        secondary: Frequency,
+    ) -> Result<i32, Error<E, PIN::Error>> {
+        let (first, second, frequencies) = match self.order {
+            WriteOrder::PrimaryFirst => {
+                (&mut self.primary, &mut self.secondary, [primary, secondary])
+            }
+            WriteOrder::SecondaryFirst => {
+                (&mut self.secondary, &mut self.primary, [secondary, primary])
+            }
+        };
+        let mut devices = [first, second];
+        stage_all(&mut devices, frequencies)?;
+        for ltc in devices.iter_mut() {
+            ltc.write_out().map_err(Error::I2c)?;
+        }
+        Ok(self.ratio_error_ppm())
+    }
+
+    /// Error in ppm of the ratio of the programmed frequencies to `n / d`, including the
+    /// calibration of both devices
+    pub fn ratio_error_ppm(&self) -> i32 {
+        let (pn, pd) = calibration::exact(self.primary.reg.bits());
+        let (sn, sd) = calibration::exact(self.secondary.reg.bits());
+        let pn = pn * (1_000_000 + self.primary.calibration_ppm as i128);
+        let sn = sn * (1_000_000 + self.secondary.calibration_ppm as i128);
+        // (sn / sd) / (pn / pd) / (n / d) - 1
+        let num = sn * pd * self.d as i128;
+        let den = sd * pn * self.n as i128;
+        if den == 0 {
+            return 0;
+        }
+        let diff = (num - den) * 1_000_000;
+        ((diff + diff.signum() * den / 2) / den) as i32
+    }
+
+    pub fn primary(&self) -> &LTC6904<I2C, PIN> {
+        &self.primary
+    }
+
+    pub fn secondary(&self) -> &LTC6904<I2C, PIN> {
+        &self.secondary
+    }
+
+    /// Returns the primary and the secondary driver
+    pub fn into_parts(self) -> (LTC6904<I2C, PIN>, LTC6904<I2C, PIN>) {
+        (self.primary, self.secondary)
+    }
+}
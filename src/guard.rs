@@ -0,0 +1,92 @@
+//! Output enable tied to a scope
+//!
+//! [`LTC6904::enable_output()`] and [`LTC6904::disable_output()`] leave it to the caller to turn
+//! the clock off again. For "never leave the clock on" policies, [`LTC6904::enable_scoped()`]
+//! returns an [`OutputGuard`] instead, which offers the operations that only make sense on a
+//! running clock, like [`OutputGuard::retune()`], and disables the outputs when dropped.
+//!
+//! The guard is opt-in: `enable_output()` keeps returning `()`. Returning the guard from it would
+//! turn every existing `ltc.enable_output()?;` into an enable immediately undone by the dropped
+//! guard, without a compile error. The policy is only enforced where `enable_scoped()` is used.
+
+use core::ops::{Deref, DerefMut};
+
+use embedded_hal::{blocking::i2c::Write, digital::v2::OutputPin};
+
+use crate::{Error, Programmed, LTC6904};
+
+/// Keeps the outputs enabled while alive, created by [`LTC6904::enable_scoped()`]
+///
+/// Dropping the guard disables the outputs again, including on early returns and `?`. Errors of
+/// the OE pin can't be reported from `drop` and are ignored, use [`OutputGuard::release()`] to
+/// handle them. The driver stays usable through the guard.
+#[must_use = "dropping the guard disables the outputs right away"]
+pub struct OutputGuard<'a, I2C, PIN>
+where
+    I2C: Write,
+    PIN: OutputPin,
+{
+    ltc: &'a mut LTC6904<I2C, PIN>,
+}
+
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// Enables the outputs until the returned guard is dropped
+    pub fn enable_scoped(&mut self) -> Result<OutputGuard<'_, I2C, PIN>, PIN::Error> {
+        self.enable_output()?;
+        Ok(OutputGuard { ltc: self })
+    }
+}
+
+impl<'a, I2C, E, PIN> OutputGuard<'a, I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// Programs `f` right away, keeping the output mode
+    pub fn retune(&mut self, f: u32) -> Result<Programmed, Error<E, PIN::Error>> {
+        let mode = self.ltc.output_mode();
+        self.ltc.configure(f, mode)
+    }
+
+    /// Disables the outputs, reporting the pin error dropping the guard would swallow
+    pub fn release(self) -> Result<(), PIN::Error> {
+        let mut guard = core::mem::ManuallyDrop::new(self);
+        guard.ltc.disable_output()
+    }
+}
+
+impl<'a, I2C, PIN> Deref for OutputGuard<'a, I2C, PIN>
+where
+    I2C: Write,
+    PIN: OutputPin,
+{
+    type Target = LTC6904<I2C, PIN>;
+
+    fn deref(&self) -> &Self::Target {
+        self.ltc
+    }
+}
+
+impl<'a, I2C, PIN> DerefMut for OutputGuard<'a, I2C, PIN>
+where
+    I2C: Write,
+    PIN: OutputPin,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.ltc
+    }
+}
+
+impl<'a, I2C, PIN> Drop for OutputGuard<'a, I2C, PIN>
+where
+    I2C: Write,
+    PIN: OutputPin,
+{
+    fn drop(&mut self) {
+        let _ = self.ltc.disable_output();
+    }
+}
@@ -0,0 +1,136 @@
+//! Frequency hopping to spread the emissions of a clocked circuit
+//!
+//! Switching regulators and other circuits clocked at a fixed frequency concentrate their
+//! emissions in narrow peaks. Cycling the clock through a few frequencies around the nominal one
+//! spreads them out, which lowers the peaks measured in EMI tests.
+//!
+//! [`hop_pattern()`] yields one triangle of register words from the lowest to the highest
+//! frequency and back, [`LTC6904::hop()`] plays it on the device.
+
+#[cfg(feature = "driver")]
+use embedded_hal::{
+    blocking::{delay::DelayUs, i2c::Write},
+    digital::v2::OutputPin,
+};
+
+use crate::{
+    achievable::{code_to_reg, reg_to_code},
+    freq_to_reg, FrequencyError,
+};
+#[cfg(feature = "driver")]
+use crate::{Error, Register, LTC6904};
+
+/// Largest number of steps from the lowest to the highest frequency of a [`HopPattern`]
+pub const HOP_STEPS: u16 = 8;
+
+/// One cycle of register words created by [`hop_pattern()`]
+#[derive(Debug, Clone)]
+pub struct HopPattern {
+    /// code index of the lowest frequency
+    lo: u16,
+    /// codes between the lowest and the highest frequency
+    span: u16,
+    steps: u16,
+    next: u16,
+}
+
+/// Lower and upper end of `center` ± `deviation_ppm`
+fn bounds(center: u32, deviation_ppm: u32) -> (u32, u32) {
+    let deviation = (center as u64 * deviation_ppm as u64 / 1_000_000).min(u32::MAX as u64) as u32;
+    (
+        center.saturating_sub(deviation),
+        center.saturating_add(deviation),
+    )
+}
+
+/// Hops over `center` ± `deviation_ppm` in up to [`HOP_STEPS`] steps in each direction
+///
+/// The register words have CNF = 0. Both ends have to be in the range of the device.
+pub fn hop_pattern(center: u32, deviation_ppm: u32) -> Result<HopPattern, FrequencyError> {
+    let (lo, hi) = bounds(center, deviation_ppm);
+    Ok(HopPattern::new(freq_to_reg(lo)?, freq_to_reg(hi)?))
+}
+
+impl HopPattern {
+    fn new(lo: u16, hi: u16) -> Self {
+        let (lo, hi) = (reg_to_code(lo), reg_to_code(hi));
+        let span = hi - lo;
+        Self {
+            lo,
+            span,
+            steps: span.min(HOP_STEPS),
+            next: 0,
+        }
+    }
+}
+
+impl Iterator for HopPattern {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        // up from the lowest to the highest code, then back down without repeating either end
+        let len = (2 * self.steps).max(1);
+        if self.next >= len {
+            return None;
+        }
+        let i = if self.next <= self.steps {
+            self.next
+        } else {
+            len - self.next
+        };
+        self.next += 1;
+        let offset = if self.steps == 0 {
+            0
+        } else {
+            (i as u32 * self.span as u32 + self.steps as u32 / 2) / self.steps as u32
+        };
+        Some(code_to_reg(self.lo + offset as u16))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = ((2 * self.steps).max(1) - self.next) as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for HopPattern {}
+
+#[cfg(feature = "driver")]
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// Hops once over `center` ± `deviation_ppm`, staying `dwell_us` microseconds on every
+    /// frequency
+    ///
+    /// Meant to be called in a loop. The correction set with [`LTC6904::calibrate()`] and the
+    /// output mode are kept, the center frequency is programmed when it returns.
+    pub fn hop<D: DelayUs<u32>>(
+        &mut self,
+        center: u32,
+        deviation_ppm: u32,
+        dwell_us: u32,
+        delay: &mut D,
+    ) -> Result<(), Error<E, PIN::Error>> {
+        let (lo, hi) = bounds(center, deviation_ppm);
+        let pattern = HopPattern::new(
+            self.calibrated_reg(lo.into())?,
+            self.calibrated_reg(hi.into())?,
+        );
+        self.set_frequency(center)?;
+        let restore = self.reg;
+        for reg in pattern {
+            let reg = Register::new(reg);
+            self.reg = self.reg.with_oct(reg.oct()).with_dac(reg.dac());
+            if let Err(e) = self.write_out() {
+                // keep the center cached, the device never received this hop
+                self.reg = restore;
+                return Err(Error::I2c(e));
+            }
+            delay.delay_us(dwell_us);
+        }
+        self.reg = restore;
+        self.write_out().map_err(Error::I2c)
+    }
+}
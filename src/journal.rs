@@ -0,0 +1,58 @@
+//! Record of the last register writes (requires the `journal` feature)
+//!
+//! The driver keeps the last [`JOURNAL_LEN`] writes with their outcome, which can be dumped over
+//! RTT or a debug console with [`LTC6904::journal()`](crate::LTC6904::journal) when analysing a
+//! failure in the field.
+
+use crate::Register;
+
+/// Number of writes kept in the [`Journal`]
+pub const JOURNAL_LEN: usize = 8;
+
+/// One register write
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JournalEntry {
+    /// register word written
+    pub reg: Register,
+    /// whether the bus reported success
+    pub ok: bool,
+}
+
+/// Ring buffer of the last [`JOURNAL_LEN`] writes, the oldest is dropped first
+#[derive(Debug, Clone, Default)]
+pub struct Journal {
+    entries: [Option<JournalEntry>; JOURNAL_LEN],
+    /// index the next entry goes to
+    next: usize,
+}
+
+impl Journal {
+    pub(crate) fn record(&mut self, reg: Register, ok: bool) {
+        self.entries[self.next] = Some(JournalEntry { reg, ok });
+        self.next = (self.next + 1) % JOURNAL_LEN;
+    }
+
+    /// Recorded writes, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = JournalEntry> + '_ {
+        let (newer, older) = self.entries.split_at(self.next);
+        older.iter().chain(newer).flatten().copied()
+    }
+
+    /// Most recent write
+    pub fn last(&self) -> Option<JournalEntry> {
+        self.entries[(self.next + JOURNAL_LEN - 1) % JOURNAL_LEN]
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.iter().flatten().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.last().is_none()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
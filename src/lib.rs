@@ -1,96 +1,842 @@
 //! This is a platform agnostic Rust driver for the LTC6904 I2C programmable Oscillator
 //! base on the [`embedded-hal`] traits.
 //!
-//! The LTC6903 uses SPI as interface which is not yet implemented, but should be straightforward
+//! The LTC6903, the SPI version of the device, is driven by the [`ltc6903`] module. The constants
+//! telling the members of the family apart are in [`chip`].
 //!
 //! [`embedded-hal`]: https://github.com/rust-embedded/embedded-hal
 //!
 //! This driver allows you to
-//! - configure output to posive, negative, both or none of the edges
-//! - set the generated frequency
-//! - after setting up the configuration, [`write_out()`] needs to be called to write the configuration to the IC
+//! - configure which of the complementary outputs CLK and CLK̅ are active, or power down
+//! - set the generated frequency, or set and write it in one go with [`LTC6904::program_frequency()`]
+//! - tune in millihertz steps with [`Frequency`] and [`LTC6904::set_frequency_precise()`]
+//! - after setting up the configuration, [`write_out()`] needs to be called to write the configuration to the IC,
+//!   [`LTC6904::configure()`] does all three in one call
+//! - precompute register words for linear or logarithmic frequency sweeps with [`sweep()`] and
+//!   [`log_sweep()`]
+//! - pack and decode register words with [`Register`]
+//! - query the supported frequency range with [`frequency_range()`] and [`octave_range()`]
+//! - find the achievable frequencies closest to a target with [`achievable_near()`]
+//! - inspect the octave and DAC code chosen for a frequency with [`plan_frequency()`]
+//! - step through the full range in end-of-line tests with [`eol::eol_pattern()`]
+//! - bound the output frequency across all parts, including their tolerance, with
+//!   [`accuracy::accuracy_bounds()`]
+//! - find a frequency satisfying several consumers sharing a clock with [`solver::solve()`]
+//! - calculate audio master clock rates and their achievable error with [`audio`]
+//! - clock a UART and check the baud rate error with [`LTC6904::set_baud_clock()`]
+//! - generate a ratio of an existing clock with [`LTC6904::set_multiple_of()`]
+//! - trim out the part-to-part frequency error with [`LTC6904::calibrate()`], and the
+//!   temperature drift with [`LTC6904::set_frequency_compensated()`]
+//! - calculate frequencies and write them from different tasks after [`LTC6904::split()`]
+//! - store the driver without its type parameters as `dyn` [`Oscillator`]
+//! - manage the named clocks of a board in a [`clock_tree::ClockTree`]
+//! - calculate a frequency ahead of time with [`LTC6904::stage_frequency()`] and write it from an
+//!   interrupt with [`LTC6904::commit()`]
+//! - hand a delay to the driver once with [`LTC6904::with_delay()`] instead of passing it to
+//!   every timed operation
+//! - switch the supply of the device off and back on with [`LTC6904::with_power_rail()`]
+//! - estimate the supply current of a configuration for a power budget with
+//!   [`LTC6904::estimated_supply_current()`]
+//! - retune several devices together with [`group::write_all()`], or keep two on a frequency
+//!   ratio with [`group::LinkedOscillators`]
+//! - write without blocking on buses implementing [`nonblocking::NbWrite`]
+//! - switch between precomputed frequencies with [`Presets`], or pick a common crystal frequency
+//!   from [`presets::STANDARD_FREQUENCIES`]
+//! - ramp to a new frequency in bounded steps with [`ramp_to()`]
+//! - beep a buzzer with [`LTC6904::tone()`]
+//! - strobe about a given number of clock cycles with [`LTC6904::burst()`]
+//! - play timed sequences of frequencies declared as a [`profile::Profile`]
+//! - get closer to a frequency on average than the DAC resolution allows with [`Dither`]
+//! - spread the emissions of a clocked circuit by hopping around its frequency with
+//!   [`LTC6904::hop()`]
 //!
 //! [`write_out()`]: (struct.LTC6904.html#method.write_out)
+//! [`ramp_to()`]: (struct.LTC6904.html#method.ramp_to)
 //!
-//! The 'output enable' pin of the device needs to be pulled from the application independently of the driver
+//! The 'output enable' pin of the device needs to be pulled from the application independently of the driver,
+//! using [`LTC6904::enable_output()`] and [`LTC6904::disable_output()`]. Boards inverting the OE signal
+//! are supported through [`LTC6904::new_with_polarity()`]. [`LTC6904::enable_scoped()`] enables the
+//! outputs only until the returned [`OutputGuard`] goes out of scope. [`LTC6904::split_oe()`]
+//! hands the OE pin to a separate [`oe::OutputEnable`] owned by another task. For OE pins that can't
+//! fail, [`oe::InfallibleOe`] switches the outputs without a `Result`.
+//!
+//! The [`ltc6903`] module drives the LTC6903, the SPI version of the device, and the [`ltc6905`]
+//! module the divider pin of the LTC6905, the fixed frequency sibling without a bus.
+//!
+//! With the `midi` feature, the `midi` module converts MIDI notes to frequencies.
+//!
+//! With the `parse` feature, the `parse` module parses output modes and frequencies from
+//! strings like "clk" and "12.288MHz", for command shells and host tools.
+//!
+//! The driver needs the default `driver` feature. Without it, only the frequency and register
+//! math is built, e.g. [`freq_to_reg()`], [`Register`] and [`sweep()`], with no dependency on
+//! embedded-hal, for host calculators, build scripts and WASM tools.
+//!
+//! The device is only ever written to, so a bus implementing `Write` is enough. With the
+//! `readback` feature, `LTC6904::read_back()`, `LTC6904::verify()` and `detect()` read the
+//! register back, which additionally needs `Read`, and `LTC6904::sync()` adopts it as the cached
+//! configuration. Without it, nothing in the crate requires a bus supporting reads.
+//!
+//! The driver is built on the embedded-hal 0.2 traits. With the `eh1` feature, the `eh1`
+//! module adapts buses and pins implementing the embedded-hal 1.0 traits instead.
+//!
+//! With the `async` feature, the timed helpers get variants waiting on an `embedded-hal-async`
+//! delay, e.g. `LTC6904::play_async()`, and the LTC6903 writes through an async `SpiDevice`
+//! with `ltc6903::LTC6903::write_out_async()`.
+//!
+//! With the `heapless` feature, `presets::NamedPresets` stores frequencies by name, for shells
+//! and command line firmware without an allocator.
+//!
+//! With the `uom` feature, the `units` module converts frequencies from and to
+//! `uom::si::f64::Frequency` and the driver accepts them, e.g. `LTC6904::set_frequency_uom()`.
+//!
+//! With the `critical-section` feature, `shared::SharedLtc6904` wraps the driver for use from
+//! several tasks or interrupt handlers.
+//!
+//! With the `defmt` feature, the public types implement `defmt::Format`. [`Error`] formats the
+//! bus and pin errors through their `Debug` impls, so `defmt::error!("{}", e)` works with any HAL.
+//!
+//! With the `log` feature, register writes, programmed frequencies and errors are logged
+//! through the [`log`](https://docs.rs/log) crate.
+//!
+//! The error types implement `core::error::Error`, also without `std`, and `category()` sorts
+//! them into an [`ErrorCategory`], for error reporting which doesn't know every error type of
+//! the crate.
+//!
+//! With the `std` feature, `enumerate_achievable()` lists every achievable frequency in a
+//! range, e.g. for host programs driving the device through a USB to I2C bridge or Linux' I2C
+//! devices.
+//!
+//! With the `sim` feature, the `sim` module provides a host-side model of the device to run
+//! application code against in unit tests.
+//!
+//! With the `journal` feature, the driver keeps the last register writes and their outcome, see
+//! `journal`.
+//!
+//! With the `test-hooks` feature, `test_hooks::FaultyI2c` injects bus errors into chosen
+//! transactions, to exercise the error handling of the application deterministically.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::{convert::TryFrom, result::Result};
+#[cfg(feature = "driver")]
+use embedded_hal::{
+    self as hal,
+    digital::v2::{OutputPin, StatefulOutputPin},
+};
+
+#[cfg(feature = "readback")]
+use hal::blocking::i2c::Read;
+#[cfg(feature = "driver")]
+use hal::blocking::{delay::DelayUs, i2c::Write};
+
+#[cfg(feature = "driver")]
+use chip::Chip;
+use consts::{BASE_FREQUENCY, DAC_MAX, OCT_MAX};
+
+#[cfg(feature = "driver")]
+#[macro_use]
+mod fmt;
 
-#![no_std]
+pub mod accuracy;
+pub mod achievable;
+pub mod audio;
+pub mod baud;
+pub mod calibration;
+pub mod chip;
+pub mod clock_tree;
+pub mod config;
+pub mod consts;
+pub mod current;
+#[cfg(feature = "driver")]
+pub mod delayed;
+pub mod dither;
+#[cfg(feature = "eh1")]
+#[cfg(feature = "driver")]
+pub mod eh1;
+pub mod eol;
+pub mod frequency;
+#[cfg(feature = "driver")]
+pub mod group;
+#[cfg(feature = "driver")]
+pub mod guard;
+pub mod hopping;
+#[cfg(feature = "journal")]
+#[cfg(feature = "driver")]
+pub mod journal;
+#[cfg(feature = "driver")]
+pub mod ltc6903;
+#[cfg(feature = "driver")]
+pub mod ltc6905;
+#[cfg(feature = "midi")]
+pub mod midi;
+#[cfg(feature = "driver")]
+pub mod nonblocking;
+#[cfg(feature = "driver")]
+pub mod oe;
+pub mod oscillator;
+#[cfg(feature = "parse")]
+pub mod parse;
+#[cfg(feature = "driver")]
+pub mod power;
+pub mod presets;
+pub mod profile;
+pub mod ratio;
+pub mod register;
+#[cfg(feature = "driver")]
+pub mod self_test;
+#[cfg(feature = "critical-section")]
+#[cfg(feature = "driver")]
+pub mod shared;
+#[cfg(feature = "sim")]
+#[cfg(feature = "driver")]
+pub mod sim;
+pub mod solver;
+#[cfg(feature = "driver")]
+pub mod stats;
+// the staged word relies on atomic read-modify-write, which e.g. Cortex-M0 lacks
+#[cfg(target_has_atomic = "32")]
+#[cfg(feature = "driver")]
+pub mod split;
+pub mod sweep;
+#[cfg(feature = "test-hooks")]
+#[cfg(feature = "driver")]
+pub mod test_hooks;
+#[cfg(feature = "uom")]
+pub mod units;
 
-use core::result::Result;
-use embedded_hal::{self as hal, digital::v2::OutputPin};
+#[cfg(feature = "std")]
+pub use achievable::enumerate_achievable;
+pub use achievable::{achievable_near, Candidate};
+pub use config::{Config, ConfigDelta};
+pub use consts::{FREQUENCY_MAX, FREQUENCY_MIN, WAKE_SETTLING_US};
+pub use dither::Dither;
+pub use frequency::Frequency;
+#[cfg(feature = "driver")]
+pub use guard::OutputGuard;
+pub use oscillator::{Oscillator, OscillatorError};
+pub use presets::Presets;
+pub use register::Register;
+#[cfg(feature = "driver")]
+pub use self_test::SelfTestReport;
+#[cfg(feature = "driver")]
+pub use stats::Stats;
+pub use sweep::{log_sweep, sweep, LogSpacing, LogSweep, Sweep};
 
-use hal::blocking::i2c::{Read, Write, WriteRead};
+/// Compiles the examples of the README
+#[cfg(all(doctest, feature = "driver"))]
+#[doc = include_str!("../README.md")]
+pub struct ReadmeDoctests;
 
+#[cfg(feature = "driver")]
 pub struct LTC6904<I2C, PIN>
 where
-    I2C: Read + Write + WriteRead,
+    I2C: Write,
     PIN: OutputPin,
 {
     i2c: I2C,
-    reg: u16,
+    reg: Register,
     addr: Address,
-    frequ: u32,
+    frequ: Frequency,
     out_enable: PIN,
+    oe_polarity: OePolarity,
+    max_error_ppm: Option<u32>,
+    calibration_ppm: i32,
+    write_pending: Option<Register>,
+    /// OCT and DAC prepared by [`LTC6904::stage_frequency()`], with the requested frequency
+    staged: Option<(Register, Frequency)>,
+    device_reg: Option<Register>,
+    refreshes: u32,
+    /// output mode to restore on [`LTC6904::wake()`]
+    asleep: Option<OutputMode>,
+    on_write: Option<fn(Register)>,
+    stats: Stats,
+    /// register word of the last write if it failed
+    failed_write: Option<Register>,
+    #[cfg(feature = "journal")]
+    journal: journal::Journal,
 }
+/// Output configuration (CNF bits) selecting which of the complementary outputs are active
+///
+/// Defaults to [`OutputMode::BothOutputs`], the power-on state of the device.
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
-pub enum OutputSettings {
-    ClkNeg = 0,
-    ClkBoth = 1,
-    ClkPos = 2,
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OutputMode {
+    /// CLK and the inverted CLK̅ both running
+    #[default]
+    BothOutputs = 0,
+    /// CLK̅ running, CLK held low
+    ClkInvOnly = 1,
+    /// CLK running, CLK̅ held low
+    ClkOnly = 2,
+    /// both outputs off, oscillator powered down
     PowerDown = 3,
 }
 
-impl Into<u16> for OutputSettings {
-    fn into(self) -> u16 {
-        return self as u16;
+impl From<OutputMode> for u16 {
+    fn from(x: OutputMode) -> Self {
+        x.bits()
     }
 }
 
-impl From<u16> for OutputSettings {
-    fn from(x: u16) -> Self {
-        match x {
-            0 => OutputSettings::ClkNeg,
-            1 => OutputSettings::ClkBoth,
-            2 => OutputSettings::ClkPos,
-            _ => OutputSettings::PowerDown,
+impl From<OutputMode> for u8 {
+    fn from(x: OutputMode) -> Self {
+        x.bits() as u8
+    }
+}
+
+impl OutputMode {
+    /// Value of the CNF field
+    pub const fn bits(self) -> u16 {
+        self as u16
+    }
+
+    /// Decodes the two CNF bits, ignoring all others
+    pub(crate) const fn from_cnf(cnf: u16) -> Self {
+        match Register::new(cnf).cnf() {
+            0 => OutputMode::BothOutputs,
+            1 => OutputMode::ClkInvOnly,
+            2 => OutputMode::ClkOnly,
+            _ => OutputMode::PowerDown,
+        }
+    }
+
+    /// Next mode of the cycle CLK only, CLK̅ only, both outputs, e.g. for a button in a demo
+    ///
+    /// With `power_down`, [`OutputMode::PowerDown`] follows both outputs, otherwise it's skipped
+    /// and only left.
+    pub const fn next(self, power_down: bool) -> Self {
+        match self {
+            OutputMode::ClkOnly => OutputMode::ClkInvOnly,
+            OutputMode::ClkInvOnly => OutputMode::BothOutputs,
+            OutputMode::BothOutputs if power_down => OutputMode::PowerDown,
+            OutputMode::BothOutputs | OutputMode::PowerDown => OutputMode::ClkOnly,
         }
     }
 }
 
+/// Value outside of the two bit CNF range
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InvalidOutputMode(pub u16);
+
+impl TryFrom<u16> for OutputMode {
+    type Error = InvalidOutputMode;
+
+    fn try_from(x: u16) -> Result<Self, Self::Error> {
+        if x > 0b11 {
+            Err(InvalidOutputMode(x))
+        } else {
+            Ok(Self::from_cnf(x))
+        }
+    }
+}
+
+impl TryFrom<u8> for OutputMode {
+    type Error = InvalidOutputMode;
+
+    fn try_from(x: u8) -> Result<Self, Self::Error> {
+        Self::try_from(x as u16)
+    }
+}
+
+#[allow(deprecated)]
+pub use legacy::OutputSettings;
+
+mod legacy {
+    #![allow(deprecated)]
+
+    use core::convert::TryFrom;
+
+    use super::{InvalidOutputMode, OutputMode};
+
+    /// Previous name of [`OutputMode`]
+    ///
+    /// The variants keep their CNF values, which don't match their names: `ClkNeg` enables both
+    /// outputs, `ClkBoth` only CLK̅ and `ClkPos` only CLK.
+    #[deprecated(note = "use `OutputMode`, whose variants are named after the datasheet")]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub enum OutputSettings {
+        #[default]
+        ClkNeg = 0,
+        ClkBoth = 1,
+        ClkPos = 2,
+        PowerDown = 3,
+    }
+
+    impl OutputSettings {
+        /// Value of the CNF field
+        pub const fn bits(self) -> u16 {
+            self as u16
+        }
+
+        /// [`OutputMode`] with the same CNF bits, usable in const context unlike the `From` impl
+        pub const fn output_mode(self) -> OutputMode {
+            OutputMode::from_cnf(self.bits())
+        }
+    }
+
+    impl From<OutputSettings> for u16 {
+        fn from(x: OutputSettings) -> Self {
+            x.bits()
+        }
+    }
+
+    impl From<OutputSettings> for u8 {
+        fn from(x: OutputSettings) -> Self {
+            x.bits() as u8
+        }
+    }
+
+    impl TryFrom<u16> for OutputSettings {
+        type Error = InvalidOutputMode;
+
+        fn try_from(x: u16) -> Result<Self, Self::Error> {
+            OutputMode::try_from(x).map(Self::from)
+        }
+    }
+
+    impl TryFrom<u8> for OutputSettings {
+        type Error = InvalidOutputMode;
+
+        fn try_from(x: u8) -> Result<Self, Self::Error> {
+            OutputMode::try_from(x).map(Self::from)
+        }
+    }
+
+    impl From<OutputSettings> for OutputMode {
+        fn from(x: OutputSettings) -> Self {
+            x.output_mode()
+        }
+    }
+
+    impl From<OutputMode> for OutputSettings {
+        fn from(x: OutputMode) -> Self {
+            match x {
+                OutputMode::BothOutputs => OutputSettings::ClkNeg,
+                OutputMode::ClkInvOnly => OutputSettings::ClkBoth,
+                OutputMode::ClkOnly => OutputSettings::ClkPos,
+                OutputMode::PowerDown => OutputSettings::PowerDown,
+            }
+        }
+    }
+}
+
+/// Level of the MCU pin that enables the outputs
+///
+/// The device itself enables its outputs with OE high. Use [`OePolarity::ActiveLow`] if the
+/// signal is inverted between MCU and device, e.g. by a transistor.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OePolarity {
+    #[default]
+    ActiveHigh,
+    ActiveLow,
+}
+
+/// Outputs gated by the OE pin, as read back by [`LTC6904::oe_state()`]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OeState {
+    Enabled,
+    Disabled,
+}
+
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Address {
     AddressHigh,
     AddressLow,
 }
 
-impl Into<u8> for Address {
-    fn into(self) -> u8 {
-        match self {
-            Address::AddressLow => Self::ADDRESS_0,
-            Address::AddressHigh => Self::ADDRESS_1,
+impl From<Address> for u8 {
+    fn from(x: Address) -> Self {
+        x.as_7bit()
+    }
+}
+
+/// 7 bit address the device can't be strapped to
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InvalidAddress(pub u8);
+
+impl TryFrom<u8> for Address {
+    type Error = InvalidAddress;
+
+    /// Address from its 7 bit form, see [`Address::as_7bit()`]
+    fn try_from(x: u8) -> Result<Self, Self::Error> {
+        match x {
+            consts::ADDRESS_LOW => Ok(Address::AddressLow),
+            consts::ADDRESS_HIGH => Ok(Address::AddressHigh),
+            _ => Err(InvalidAddress(x)),
         }
     }
 }
 
 impl Address {
-    const ADDRESS_0: u8 = 0x17; // 7 bit address address pin low
-    const ADDRESS_1: u8 = 0x16; // 7 bit address address pin high
+    /// Same as [`Address::as_7bit()`], the form `u8::from()` returns
+    pub const fn as_u8(self) -> u8 {
+        self.as_7bit()
+    }
+
+    /// 7 bit address as expected by embedded-hal and most HALs
+    pub const fn as_7bit(self) -> u8 {
+        match self {
+            Address::AddressLow => consts::ADDRESS_LOW,
+            Address::AddressHigh => consts::ADDRESS_HIGH,
+        }
+    }
+
+    /// 8 bit form of the address with the R/W bit cleared, for HALs and bridges expecting the
+    /// address byte as sent on the bus
+    pub const fn as_8bit_write(self) -> u8 {
+        self.as_7bit() << 1
+    }
+
+    /// 8 bit form of the address with the R/W bit set
+    pub const fn as_8bit_read(self) -> u8 {
+        self.as_7bit() << 1 | 1
+    }
 }
 
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
 pub enum FrequencyError {
+    /// below the range of the device
+    #[error("frequency below {} Hz", FREQUENCY_MIN)]
     TooLow,
+    /// above the range of the device
+    #[error("frequency above {} Hz", FREQUENCY_MAX)]
     TooHigh,
+    /// the closest achievable frequency misses the limit set with [`LTC6904::with_max_error_ppm()`]
+    #[error("frequency error above the limit")]
+    ToleranceExceeded,
+    /// correction beyond [`calibration::MAX_CALIBRATION_PPM`]
+    #[error("calibration out of range")]
+    CalibrationOutOfRange,
+    /// the DAC code is outside of [`consts::CHECKED_DAC_RANGES`], see
+    /// [`LTC6904::set_frequency_checked()`]
+    #[error("DAC code at the edge of its octave")]
+    EdgeCode,
+}
+
+impl FrequencyError {
+    /// What went wrong, for error reporting
+    pub const fn category(&self) -> ErrorCategory {
+        match self {
+            FrequencyError::TooLow
+            | FrequencyError::TooHigh
+            | FrequencyError::CalibrationOutOfRange => ErrorCategory::Range,
+            FrequencyError::ToleranceExceeded | FrequencyError::EdgeCode => {
+                ErrorCategory::Tolerance
+            }
+        }
+    }
+}
+
+/// Errors of driver operations touching more than one of bus, pin and frequency calculation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+pub enum Error<E, PE> {
+    /// I2C bus error
+    #[error("I2C error: {0:?}")]
+    I2c(E),
+    /// output enable pin error
+    #[error("OE pin error: {0:?}")]
+    Pin(PE),
+    /// requested frequency out of range
+    #[error(transparent)]
+    Frequency(#[from] FrequencyError),
+    /// the bus didn't finish the transfer in time, see [`LTC6904::write_out_timeout()`]
+    #[error("I2C timeout")]
+    Timeout,
+}
+
+impl<E, PE> Error<E, PE> {
+    /// What went wrong, for error reporting
+    pub const fn category(&self) -> ErrorCategory {
+        match self {
+            Error::I2c(_) => ErrorCategory::Bus,
+            Error::Pin(_) => ErrorCategory::Pin,
+            Error::Frequency(e) => e.category(),
+            Error::Timeout => ErrorCategory::Timeout,
+        }
+    }
+}
+
+/// Coarse classification of the errors of this crate, returned by their `category()`
+///
+/// Lets error reporting group failures, e.g. count bus errors separately from rejected
+/// frequencies, without matching on every error type.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// the I2C or SPI bus failed
+    Bus,
+    /// a GPIO pin failed, e.g. OE, CS or a power rail enable
+    Pin,
+    /// a value the device can't generate or the driver doesn't accept
+    Range,
+    /// achievable, but not as accurately as required
+    Tolerance,
+    /// a transfer didn't finish in time
+    Timeout,
+}
+
+// the bus and pin errors of most HALs don't implement `defmt::Format`, but all implement `Debug`
+#[cfg(feature = "defmt")]
+impl<E: core::fmt::Debug, PE: core::fmt::Debug> defmt::Format for Error<E, PE> {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Error::I2c(e) => defmt::write!(f, "I2C error: {}", defmt::Debug2Format(e)),
+            Error::Pin(e) => defmt::write!(f, "OE pin error: {}", defmt::Debug2Format(e)),
+            Error::Frequency(e) => defmt::write!(f, "{}", e),
+            Error::Timeout => defmt::write!(f, "I2C timeout"),
+        }
+    }
+}
+
+/// Result of [`LTC6904::program_frequency()`]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Programmed {
+    /// register word written to the device
+    pub reg: u16,
+    /// achieved output frequency in Hz, rounded
+    pub frequency: u32,
+    /// error relative to the requested frequency in ppm
+    pub error_ppm: i32,
+}
+
+/// Decoded register content, as returned by [`LTC6904::dump()`]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RegisterSnapshot {
+    pub oct: u16,
+    pub dac: u16,
+    pub cnf: u16,
+    pub reg: u16,
+}
+
+impl From<Register> for RegisterSnapshot {
+    fn from(reg: Register) -> Self {
+        Self {
+            oct: reg.oct(),
+            dac: reg.dac(),
+            cnf: reg.cnf(),
+            reg: reg.bits(),
+        }
+    }
+}
+
+impl From<u16> for RegisterSnapshot {
+    fn from(reg: u16) -> Self {
+        Register::new(reg).into()
+    }
+}
+
+/// Intermediate values of the frequency calculation, as returned by [`plan_frequency()`]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Plan {
+    /// octave selected for the frequency
+    pub oct: u16,
+    /// DAC code closest to the frequency within the octave
+    pub dac: u16,
+    /// achieved output frequency in Hz, rounded
+    pub achieved: u32,
+    /// error of the achieved frequency relative to the requested one in ppm
+    pub error_ppm: i32,
+}
+
+impl Plan {
+    fn new(reg: Register, achieved: u32, error_ppm: i32) -> Self {
+        Self {
+            oct: reg.oct(),
+            dac: reg.dac(),
+            achieved,
+            error_ppm,
+        }
+    }
+
+    /// Register word of the plan with CNF = 0
+    pub const fn reg(&self) -> u16 {
+        Register::new(0)
+            .with_oct(self.oct)
+            .with_dac(self.dac)
+            .bits()
+    }
+}
+
+/// Probes both addresses with a read and returns the one that acknowledged
+///
+/// Reading doesn't change the configuration of the device, which makes this safe to run before
+/// the driver is created on boards whose revisions strap the ADR pin differently.
+#[cfg(feature = "readback")]
+pub fn detect<I2C: Read>(i2c: &mut I2C) -> Option<Address> {
+    let addresses = match chip::Ltc6904::ADDRESS_SCHEME {
+        chip::AddressScheme::I2c(addresses) => addresses,
+        chip::AddressScheme::ChipSelect => &[],
+    };
+    addresses
+        .iter()
+        .filter_map(|&a| Address::try_from(a).ok())
+        .find(|addr| i2c.read(addr.as_7bit(), &mut [0; 2]).is_ok())
+}
+
+/// Accepted frequency range (min, max) in Hz
+pub const fn frequency_range() -> (u32, u32) {
+    (FREQUENCY_MIN, FREQUENCY_MAX)
+}
+
+/// Frequency range (min, max) in Hz of octave `oct` as specified in the datasheet
+///
+/// Adjacent octaves don't overlap and leave small gaps, frequencies in those are rounded to the
+/// closer octave.
+pub fn octave_range(oct: u16) -> Option<(u32, u32)> {
+    consts::OCTAVE_RANGES.get(oct as usize).copied()
+}
+
+/// Frequency plan of the device as `(oct, min_hz, max_hz, step_hz)` for every octave
+///
+/// The range is the one of [`octave_range()`], the step the largest one between adjacent DAC
+/// codes, at the top of the octave.
+pub fn iter_octaves() -> impl ExactSizeIterator<Item = (u16, u32, u32, u32)> + Clone {
+    (0..=OCT_MAX).map(|oct| {
+        let (min, max) = consts::OCTAVE_RANGES[oct as usize];
+        let top = reg_to_freq(Register::from_fields(oct, DAC_MAX, 0).bits());
+        // the top code is its own closest one, so this is the step below it
+        let step = resolution_at(top).unwrap_or(0);
+        (oct, min, max, step)
+    })
+}
+
+/// Fractional bits of the fixed point frequencies used internally
+pub(crate) const FRAC_BITS: u16 = 16;
+
+/// Octave of the fixed point frequency `f`
+///
+/// Frequencies just below the bottom of an octave end up in the one below with the DAC clamped,
+/// [`fixed_to_reg()`] moves them up if that is closer.
+const fn calc_oct(f: u64) -> Result<u16, FrequencyError> {
+    if f < (FREQUENCY_MIN as u64) << FRAC_BITS {
+        Err(FrequencyError::TooLow)
+    } else if f > (FREQUENCY_MAX as u64) << FRAC_BITS {
+        Err(FrequencyError::TooHigh)
+    } else {
+        // DAC = 0 generates exactly 1039 Hz * 2^OCT, the bottom of each octave
+        let octaves = f / ((FREQUENCY_MIN as u64) << FRAC_BITS);
+        let oct = octaves.ilog2() as u16;
+        Ok(if oct < OCT_MAX { oct } else { OCT_MAX })
+    }
+}
+
+/// DAC code closest to the fixed point frequency `f` within octave `oct`, clamped to the 10 bit
+/// range
+///
+/// DAC = 2048 - 2078 * 2^(10 + OCT) / f
+const fn calc_dac(f: u64, oct: u16) -> u16 {
+    let k = (BASE_FREQUENCY as u64) << (10 + oct + FRAC_BITS);
+    // f lies between k / (n + 1) and k / n, pick the closer one
+    let n = k / f;
+    let above = k - f * n;
+    let below = f * (n + 1) - k;
+    let den = if above * (n + 1) <= below * n {
+        n
+    } else {
+        n + 1
+    };
+    let dac = 2048u64.saturating_sub(den);
+    if dac < DAC_MAX as u64 {
+        dac as u16
+    } else {
+        DAC_MAX
+    }
+}
+
+/// Calculates the register word (with CNF = 0) producing the frequency closest to `f` in Hz
+pub const fn freq_to_reg(f: u32) -> Result<u16, FrequencyError> {
+    fixed_to_reg((f as u64) << FRAC_BITS)
+}
+
+/// [`freq_to_reg()`] for a frequency with [`FRAC_BITS`] fractional bits
+pub(crate) const fn fixed_to_reg(f: u64) -> Result<u16, FrequencyError> {
+    // `?` isn't available in const fn
+    let oct = match calc_oct(f) {
+        Ok(oct) => oct,
+        Err(e) => return Err(e),
+    };
+    let dac = calc_dac(f, oct);
+    let reg = Register::from_fields(oct, dac, 0).bits();
+    // frequencies in the gap between two octaves may be closer to the bottom of the next one,
+    // which generates k / 1024 compared to k / 1025 at the top of this one
+    if dac == DAC_MAX && oct < OCT_MAX {
+        let k = (BASE_FREQUENCY as i128) << (10 + oct + FRAC_BITS);
+        let f = f as i128;
+        if (k - 1024 * f).abs() * 1025 < (1025 * f - k).abs() * 1024 {
+            return Ok(Register::from_fields(oct + 1, 0, 0).bits());
+        }
+    }
+    Ok(reg)
+}
+
+/// Calculates the frequency in Hz, rounded to the nearest Hz, generated by a register word
+///
+/// f = 2^OCT * 2078 Hz / (2 - DAC / 1024)
+pub fn reg_to_freq(reg: u16) -> u32 {
+    let reg = Register::new(reg);
+    let num = (BASE_FREQUENCY as u64) << (10 + reg.oct());
+    let den = 2048 - reg.dac() as u64;
+    ((num + den / 2) / den) as u32
+}
+
+/// Frequency step in Hz between adjacent DAC codes around `f`, rounded
+///
+/// Adjustments smaller than this can't change the programmed register. The step grows with the
+/// square of the frequency within an octave and doubles from one octave to the next.
+pub fn resolution_at(f: u32) -> Result<u32, FrequencyError> {
+    let reg = Register::new(freq_to_reg(f)?);
+    let k = (BASE_FREQUENCY as u64) << (10 + reg.oct());
+    // k / (den - 1) - k / den for the step to the next code, from the previous one at the top
+    let den = 2048 - reg.dac().min(1022) as u64;
+    let prod = den * (den - 1);
+    Ok(((k + prod / 2) / prod) as u32)
+}
+
+/// Relative error of the closest achievable frequency to `f` in parts per million
+pub fn error_ppm(f: u32) -> Result<i32, FrequencyError> {
+    Ok(reg_error_ppm(freq_to_reg(f)?, f))
+}
+
+/// Octave and DAC code [`freq_to_reg()`] picks for `f`, with the achieved frequency and error
+pub fn plan_frequency(f: u32) -> Result<Plan, FrequencyError> {
+    let reg = freq_to_reg(f)?;
+    Ok(Plan::new(
+        reg.into(),
+        reg_to_freq(reg),
+        reg_error_ppm(reg, f),
+    ))
+}
+
+/// Relative error of the frequency generated by `reg` to `f` in ppm, rounded
+///
+/// Calculated from the exact output frequency rather than [`reg_to_freq()`], whose rounding to Hz
+/// alone amounts to several hundred ppm at the low end of the range.
+pub(crate) fn reg_error_ppm(reg: u16, f: u32) -> i32 {
+    let reg = Register::new(reg);
+    let dac = reg.dac() as i64;
+    let k = (BASE_FREQUENCY as i64) << (10 + reg.oct());
+    // (k / (2048 - dac) - f) / f
+    let den = f as i64 * (2048 - dac);
+    let num = (k - den) * 1_000_000;
+    ((num + num.signum() * den / 2) / den) as i32
 }
 
 // currently needs the git version of defmt
 // #[cfg(feature = "defmt")]
 // impl<I2C> defmt::Format for LTC6904<I2C>
 // where
-//     I2C: Read + Write + WriteRead,
+//     I2C: Write,
 // {
 //     fn format(&self, fmt: defmt::Formatter) {
 //         defmt::write!(
@@ -104,151 +850,701 @@ pub enum FrequencyError {
 //     }
 // }
 
+// pin handling doesn't depend on the bus error type, which keeps it usable from `Drop`
+#[cfg(feature = "driver")]
+impl<I2C, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write,
+    PIN: OutputPin,
+{
+    pub fn oe_polarity(&self) -> OePolarity {
+        self.oe_polarity
+    }
+
+    pub fn enable_output(&mut self) -> Result<(), <PIN as OutputPin>::Error> {
+        match self.oe_polarity {
+            OePolarity::ActiveHigh => self.out_enable.set_high(),
+            OePolarity::ActiveLow => self.out_enable.set_low(),
+        }
+    }
+
+    pub fn disable_output(&mut self) -> Result<(), <PIN as OutputPin>::Error> {
+        match self.oe_polarity {
+            OePolarity::ActiveHigh => self.out_enable.set_low(),
+            OePolarity::ActiveLow => self.out_enable.set_high(),
+        }
+    }
+
+    /// Enables the outputs for `duration_us` microseconds, e.g. to gate the clock into a counter
+    /// for a fixed window
+    ///
+    /// The outputs are disabled afterwards regardless of their state before.
+    pub fn enable_output_for<D: DelayUs<u32>>(
+        &mut self,
+        duration_us: u32,
+        delay: &mut D,
+    ) -> Result<(), <PIN as OutputPin>::Error> {
+        self.enable_output()?;
+        delay.delay_us(duration_us);
+        self.disable_output()
+    }
+
+    /// Enables the outputs for about `cycles` periods of the programmed frequency, e.g. to
+    /// strobe a counter or shift register a fixed number of times, returns the time enabled in
+    /// microseconds
+    ///
+    /// The time is rounded to the microsecond resolution of the delay, so the actual count is off
+    /// by up to half the cycles of a microsecond, plus the time the pin takes to switch. The
+    /// frequency has to be written and the oscillator running, see [`LTC6904::is_written()`].
+    pub fn burst<D: DelayUs<u32>>(
+        &mut self,
+        cycles: u32,
+        delay: &mut D,
+    ) -> Result<u32, <PIN as OutputPin>::Error> {
+        let f = self.calibrated_freq(self.reg.bits()) as u64;
+        let duration_us = (cycles as u64 * 1_000_000 + f / 2) / f;
+        let duration_us = u32::try_from(duration_us).unwrap_or(u32::MAX);
+        self.enable_output_for(duration_us, delay)?;
+        Ok(duration_us)
+    }
+}
+
+#[cfg(feature = "driver")]
 #[allow(dead_code)]
 impl<I2C, E, PIN> LTC6904<I2C, PIN>
 where
-    I2C: Read<Error = E> + Write<Error = E> + WriteRead<Error = E>,
+    I2C: Write<Error = E>,
     PIN: OutputPin,
 {
-    const OCT: [(u32, u32); 16] = [
-        /* 0 */ (1_039, 2_076),
-        /* 1 */ (2_078, 4_152),
-        /* 2 */ (4_156, 8_304),
-        /* 3 */ (8_312, 16_610),
-        /* 4 */ (16_620, 33_220),
-        /* 5 */ (33_250, 66_430),
-        /* 6 */ (66_500, 132_900),
-        /* 7 */ (133_000, 265_700),
-        /* 8 */ (266_000, 531_400),
-        /* 9 */ (532_000, 1063_000),
-        /* 10 */ (1_064_000, 2_126_000),
-        /* 11 */ (2_128_000, 4_252_000),
-        /* 12 */ (4_256_000, 8_503_000),
-        /* 13 */ (8_511_000, 17_010_000),
-        /* 14 */ (17_020_000, 34_010_000),
-        /* 15 */ (34_050_000, 68_030_000),
-    ];
-
-    const OCT_POS: u16 = 12;
-    const OCT_SIZE: u16 = 4;
-    const DAC_POS: u16 = 2;
-    const DAC_SIZE: u16 = 10;
-    const CNF_POS: u16 = 0;
-    const CNF_SIZE: u16 = 2;
-
-    const OCT_MASK: u16 = 0b1111_0000_0000_0000;
-    const DAC_MASK: u16 = 0b0000_1111_1111_1100;
-    const CNF_MASK: u16 = 0b0000_0000_0000_0011;
-
-    const FREQU_MIN: u32 = 1_039;
-    const FREQU_MAX: u32 = 68_030_000;
-
     pub fn new(i2c: I2C, address: Address, out_enable: PIN) -> Self {
+        Self::new_with_polarity(i2c, address, out_enable, OePolarity::ActiveHigh)
+    }
+
+    /// Creates the driver and writes `f` and `mode` to the device right away
+    ///
+    /// The outputs stay disabled, but the device doesn't wait at its power-on frequency for the
+    /// first write. On an error, the peripherals are dropped with the driver.
+    pub fn with_initial(
+        i2c: I2C,
+        address: Address,
+        out_enable: PIN,
+        f: u32,
+        mode: OutputMode,
+    ) -> Result<Self, Error<E, PIN::Error>> {
+        let mut ltc = Self::new(i2c, address, out_enable);
+        ltc.configure(f, mode)?;
+        Ok(ltc)
+    }
+
+    /// Creates a driver for boards where the OE signal is inverted on its way to the device
+    pub fn new_with_polarity(
+        i2c: I2C,
+        address: Address,
+        out_enable: PIN,
+        oe_polarity: OePolarity,
+    ) -> Self {
         Self {
             i2c,
-            reg: 0,
-            addr: address.into(),
-            frequ: Self::FREQU_MIN,
+            // OCT = 0, DAC = 0 and both outputs are the power-on state
+            reg: Register::default(),
+            addr: address,
+            frequ: Config::POWER_ON.frequency.into(),
             out_enable,
+            oe_polarity,
+            max_error_ppm: None,
+            calibration_ppm: 0,
+            write_pending: None,
+            staged: None,
+            device_reg: None,
+            refreshes: 0,
+            asleep: None,
+            on_write: None,
+            stats: Stats::default(),
+            failed_write: None,
+            #[cfg(feature = "journal")]
+            journal: journal::Journal::default(),
         }
     }
 
-    pub fn enable_output(&mut self) -> Result<(), <PIN as OutputPin>::Error> {
-        self.out_enable.set_high()
+    /// Rejects frequencies that can't be generated within `ppm` instead of programming the
+    /// closest one
+    ///
+    /// [`LTC6904::set_frequency()`] and the methods built on it then fail with
+    /// [`FrequencyError::ToleranceExceeded`] and leave the configuration unchanged.
+    pub fn with_max_error_ppm(mut self, ppm: u32) -> Self {
+        self.max_error_ppm = Some(ppm);
+        self
     }
 
-    pub fn disable_output(&mut self) -> Result<(), <PIN as OutputPin>::Error> {
-        self.out_enable.set_low()
+    /// Error limit set with [`LTC6904::with_max_error_ppm()`]
+    pub fn max_error_ppm(&self) -> Option<u32> {
+        self.max_error_ppm
     }
 
-    fn set_oct(&mut self, oct: u16) {
-        self.reg &= !Self::OCT_MASK;
-        self.reg |= oct << 12;
+    /// Bus address the driver talks to
+    pub fn address(&self) -> Address {
+        self.addr
     }
 
-    pub fn get_oct(&self) -> u16 {
-        (self.reg & Self::OCT_MASK) >> Self::OCT_POS
+    /// Retargets the driver to the device at `address`, e.g. with the ADR pin driven by a GPIO
+    ///
+    /// The cached configuration is kept and has to be written to apply to the device at the new
+    /// address, see [`LTC6904::has_pending_changes()`].
+    pub fn set_address(&mut self, address: Address) {
+        if address != self.addr {
+            self.addr = address;
+            self.device_reg = None;
+        }
     }
 
-    fn set_dac(&mut self, dac: u16) {
-        self.reg &= !Self::DAC_MASK;
-        self.reg |= dac << Self::DAC_POS;
+    fn set_cnf(&mut self, cnf: u16) {
+        self.reg = self.reg.with_cnf(cnf);
     }
 
-    pub fn get_dac(&self) -> u16 {
-        self.reg & Self::DAC_MASK >> Self::DAC_POS
+    pub fn get_oct(&self) -> u16 {
+        self.reg.oct()
     }
 
-    fn set_cnf(&mut self, cnf: u16) {
-        self.reg &= !Self::CNF_MASK;
-        self.reg |= cnf << Self::CNF_POS;
+    pub fn get_dac(&self) -> u16 {
+        self.reg.dac()
     }
 
     pub fn get_cnf(&self) -> u16 {
-        self.reg & Self::CNF_MASK >> Self::CNF_POS
+        self.reg.cnf()
     }
 
     pub fn get_reg(&self) -> u16 {
+        self.reg.bits()
+    }
+
+    /// Cached register with its decoded fields
+    pub fn register(&self) -> Register {
         self.reg
     }
 
-    fn update(&mut self) -> Result<(), E> {
-        let mut buffer = [0; 2];
-        self.i2c.read(self.addr.into(), &mut buffer)?;
-        self.reg = u16::from_be_bytes(buffer);
-        Ok(())
+    /// All fields of the cached register, decoded for logging
+    pub fn dump(&self) -> RegisterSnapshot {
+        self.reg.into()
     }
 
     pub fn write_out(&mut self) -> Result<(), E> {
         let data = self.reg.to_be_bytes();
-        Ok(self.i2c.write(self.addr.into(), &data)?)
+        let addr = self.addr.as_u8();
+        let result = self.i2c.write(addr, &data);
+        self.note_write(self.reg, result.is_ok());
+        result.inspect_err(|_| {
+            debug!(
+                "{}@{:#04x}: writing {:#06x} failed",
+                chip::Ltc6904::NAME,
+                addr,
+                self.reg
+            );
+        })?;
+        trace!(
+            "{}@{:#04x}: wrote {:#06x}",
+            chip::Ltc6904::NAME,
+            addr,
+            self.reg
+        );
+        self.mark_written();
+        Ok(())
+    }
+
+    /// Rewrites the cached register even if it has been written already
+    ///
+    /// Meant to be called periodically, so a register corrupted by a brown-out or ESD event is
+    /// restored within one period.
+    pub fn refresh(&mut self) -> Result<(), E> {
+        self.write_out()?;
+        self.refreshes = self.refreshes.wrapping_add(1);
+        Ok(())
+    }
+
+    /// Number of successful [`LTC6904::refresh()`] calls, wrapping on overflow
+    pub fn refresh_count(&self) -> u32 {
+        self.refreshes
+    }
+
+    /// Cached register word in bus order, for transfers done outside of the driver, e.g. by DMA
+    ///
+    /// Call [`LTC6904::mark_written()`] once the transfer has completed.
+    pub fn register_bytes(&self) -> [u8; 2] {
+        self.reg.to_be_bytes()
+    }
+
+    /// Records the cached register as written to the device
+    pub fn mark_written(&mut self) {
+        self.record_write(self.reg);
+    }
+
+    /// Calls `hook` with the register word after every successful write, `None` removes it
+    ///
+    /// Meant for mirroring the oscillator state to telemetry or a shadow log. Writes recorded
+    /// with [`LTC6904::mark_written()`] count as well. The hook runs in the context of the write,
+    /// so it should return quickly.
+    pub fn set_on_write(&mut self, hook: Option<fn(Register)>) {
+        self.on_write = hook;
+    }
+
+    /// Writes recorded since the driver was created or the journal was cleared (requires the
+    /// `journal` feature)
+    #[cfg(feature = "journal")]
+    pub fn journal(&self) -> &journal::Journal {
+        &self.journal
+    }
+
+    /// Clears the journal (requires the `journal` feature)
+    #[cfg(feature = "journal")]
+    pub fn clear_journal(&mut self) {
+        self.journal.clear();
+    }
+
+    /// Counts a write in the [`Stats`] and keeps it in the journal, if enabled
+    pub(crate) fn note_write(&mut self, reg: Register, ok: bool) {
+        self.stats
+            .count(reg, ok, self.failed_write, self.device_reg);
+        self.failed_write = if ok { None } else { Some(reg) };
+        #[cfg(feature = "journal")]
+        self.journal.record(reg, ok);
+    }
+
+    pub(crate) fn record_write(&mut self, reg: Register) {
+        self.device_reg = Some(reg);
+        if let Some(hook) = self.on_write {
+            hook(reg);
+        }
+    }
+
+    /// Whether the cached register has been written to the device since it last changed
+    pub fn is_written(&self) -> bool {
+        self.device_reg == Some(self.reg)
+    }
+
+    /// Whether the configuration was changed by a setter without being written to the device
+    ///
+    /// The setters only change the cached register, forgetting [`LTC6904::write_out()`] leaves
+    /// the device on its previous configuration. A freshly created driver has pending changes, as
+    /// the state of the device is unknown until the first write.
+    pub fn has_pending_changes(&self) -> bool {
+        !self.is_written()
+    }
+
+    /// Replaces the cached register with `reg` and writes it to the device
+    ///
+    /// Intended for precomputed words, e.g. from a [`Sweep`]
+    pub fn write_reg(&mut self, reg: u16) -> Result<(), E> {
+        self.reg = reg.into();
+        self.frequ = reg_to_freq(reg).into();
+        self.write_out()
+    }
+
+    /// Prepares the frequency for [`LTC6904::commit()`] without touching the cached register
+    ///
+    /// The calculation and the checks of [`LTC6904::set_frequency()`] happen here, e.g. in thread
+    /// context, leaving only the write to `commit()`, which can then run in an interrupt. A
+    /// frequency staged before and not committed yet is replaced.
+    pub fn stage_frequency(&mut self, f: u32) -> Result<u16, FrequencyError> {
+        let f = f.into();
+        let reg =
+            calibration::checked_reg::<chip::Ltc6904>(f, self.calibration_ppm, self.max_error_ppm)?;
+        self.staged = Some((reg.into(), f));
+        Ok(reg)
+    }
+
+    /// Whether a frequency is waiting for [`LTC6904::commit()`]
+    pub fn has_staged(&self) -> bool {
+        self.staged.is_some()
+    }
+
+    /// Drops a frequency staged with [`LTC6904::stage_frequency()`]
+    pub fn discard_staged(&mut self) {
+        self.staged = None;
+    }
+
+    /// Moves the staged frequency into the cached register and writes it, returns whether a
+    /// frequency was staged
+    ///
+    /// The output mode is the one configured at the time of the commit. If the write fails, the
+    /// cached register already holds the staged frequency, [`LTC6904::write_out()`] retries it.
+    pub fn commit(&mut self) -> Result<bool, E> {
+        match self.staged.take() {
+            Some((reg, f)) => {
+                self.reg = self.reg.with_oct(reg.oct()).with_dac(reg.dac());
+                self.frequ = f;
+                self.write_out().map(|_| true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Writes a register word calculated ahead of time for the frequency `frequ`
+    pub(crate) fn write_precomputed(&mut self, reg: u16, frequ: u32) -> Result<(), E> {
+        self.reg = reg.into();
+        self.frequ = frequ.into();
+        self.write_out()
     }
 
+    pub fn set_output_mode(&mut self, mode: OutputMode) {
+        self.set_cnf(mode.into());
+    }
+
+    pub fn output_mode(&self) -> OutputMode {
+        self.reg.output_mode()
+    }
+
+    /// Mode following the configured one, see [`OutputMode::next()`]
+    pub fn next_output_mode(&self, power_down: bool) -> OutputMode {
+        self.output_mode().next(power_down)
+    }
+
+    /// Switches to the [`LTC6904::next_output_mode()`] and writes it, returns the new mode
+    pub fn cycle_output(&mut self, power_down: bool) -> Result<OutputMode, E> {
+        let mode = self.next_output_mode(power_down);
+        self.set_output_mode(mode);
+        self.write_out()?;
+        Ok(mode)
+    }
+
+    /// Whether the configured output mode powers the oscillator down
+    ///
+    /// Like all getters, this reflects the cached configuration, which only applies to the device
+    /// after [`LTC6904::write_out()`].
+    pub fn is_powered_down(&self) -> bool {
+        self.output_mode() == OutputMode::PowerDown
+    }
+
+    #[deprecated(note = "use `set_output_mode()`")]
+    #[allow(deprecated)]
     pub fn set_output_conf(&mut self, output: OutputSettings) {
         self.set_cnf(output.into());
     }
 
+    #[deprecated(note = "use `output_mode()`")]
+    #[allow(deprecated)]
     pub fn get_output_conf(&self) -> OutputSettings {
-        self.get_cnf().into()
+        self.output_mode().into()
     }
 
-    pub(crate) fn calc_oct(f: u32) -> Result<u16, FrequencyError> {
-        if f < 1039 {
-            return Err(FrequencyError::TooLow);
-        } else if f > 68_030_000 {
-            return Err(FrequencyError::TooHigh);
-        } else {
-            let mut result = 0;
-            for (i, (min, max)) in Self::OCT.iter().enumerate() {
-                if f >= *min && f <= *max {
-                    result = i as u16;
-                    break;
-                }
-            }
-            Ok(result)
+    pub fn set_frequency(&mut self, f: u32) -> Result<u16, FrequencyError> {
+        self.set_frequency_precise(f.into())
+    }
+
+    /// [`LTC6904::set_frequency()`] for a frequency with millihertz resolution
+    pub fn set_frequency_precise(&mut self, f: Frequency) -> Result<u16, FrequencyError> {
+        let reg = self.checked_register(f)?;
+        Ok(self.apply_frequency(f, reg))
+    }
+
+    /// [`LTC6904::set_frequency()`] additionally rejecting DAC codes outside of
+    /// [`consts::CHECKED_DAC_RANGES`] for their octave
+    ///
+    /// For applications that rather fail with [`FrequencyError::EdgeCode`] than run on a code
+    /// with degraded accuracy. The configuration is left unchanged on errors.
+    pub fn set_frequency_checked(&mut self, f: u32) -> Result<u16, FrequencyError> {
+        let f = f.into();
+        let reg = self.checked_register(f)?;
+        let (min, max) = consts::CHECKED_DAC_RANGES[reg.oct() as usize];
+        if !(min..=max).contains(&reg.dac()) {
+            debug!(
+                "{}: DAC {} rejected in octave {}",
+                chip::Ltc6904::NAME,
+                reg.dac(),
+                reg.oct()
+            );
+            return Err(FrequencyError::EdgeCode);
         }
+        Ok(self.apply_frequency(f, reg))
     }
 
-    pub(crate) fn calc_dac(f: u32, oct: u16) -> u16 {
-        let dac = 2048u32 - (2078 * 2u32.pow(10u32 + oct as u32)) / f;
-        dac as u16
+    fn checked_register(&self, f: Frequency) -> Result<Register, FrequencyError> {
+        calibration::checked_reg::<chip::Ltc6904>(f, self.calibration_ppm, self.max_error_ppm)
+            .map(Register::new)
+            .inspect_err(|_e| {
+                debug!(
+                    "{}: {} mHz rejected: {:?}",
+                    chip::Ltc6904::NAME,
+                    f.millihertz(),
+                    _e
+                );
+            })
     }
 
-    pub fn set_frequency(&mut self, f: u32) -> Result<u16, FrequencyError> {
-        let oct = Self::calc_oct(f)?;
-        let dac = Self::calc_dac(f, oct);
+    /// Takes OCT and DAC of `reg` for the frequency `f`, keeping the output mode
+    fn apply_frequency(&mut self, f: Frequency, reg: Register) -> u16 {
+        debug!(
+            "{}: {} mHz -> OCT {} DAC {}",
+            chip::Ltc6904::NAME,
+            f.millihertz(),
+            reg.oct(),
+            reg.dac()
+        );
         self.frequ = f;
-        self.set_oct(oct as u16);
-        self.set_dac(dac);
-        Ok(self.reg)
+        self.reg = self.reg.with_oct(reg.oct()).with_dac(reg.dac());
+        self.reg.bits()
     }
 
-    pub fn get_frequency(&self) -> u32 {
+    /// Sets the frequency and writes the configuration to the device
+    pub fn program_frequency(&mut self, f: u32) -> Result<Programmed, Error<E, PIN::Error>> {
+        let reg = self.set_frequency(f)?;
+        self.write_out().map_err(Error::I2c)?;
+        Ok(Programmed {
+            reg,
+            frequency: self.programmed_frequency(),
+            error_ppm: self.calibrated_error_ppm(reg, f.into()),
+        })
+    }
+
+    /// Sets frequency and output mode and writes both to the device in a single transfer
+    ///
+    /// Nothing is changed if the frequency can't be set.
+    pub fn configure(
+        &mut self,
+        f: u32,
+        mode: OutputMode,
+    ) -> Result<Programmed, Error<E, PIN::Error>> {
+        self.set_frequency(f)?;
+        self.set_output_mode(mode);
+        self.write_out().map_err(Error::I2c)?;
+        Ok(Programmed {
+            reg: self.reg.bits(),
+            frequency: self.programmed_frequency(),
+            error_ppm: self.calibrated_error_ppm(self.reg.bits(), f.into()),
+        })
+    }
+
+    /// Error in ppm [`LTC6904::set_frequency()`] would leave for `target`, without changing the
+    /// configuration
+    pub fn frequency_error_ppm(&self, target: u32) -> Result<i32, FrequencyError> {
+        let target = target.into();
+        Ok(self.calibrated_error_ppm(self.calibrated_reg(target)?, target))
+    }
+
+    /// [`plan_frequency()`] for the register [`LTC6904::set_frequency()`] would program for `f`,
+    /// including the correction set with [`LTC6904::calibrate()`]
+    ///
+    /// Neither the configuration nor the device are changed.
+    pub fn plan_frequency(&self, f: u32) -> Result<Plan, FrequencyError> {
+        let f = f.into();
+        let reg = self.calibrated_reg(f)?;
+        Ok(Plan::new(
+            reg.into(),
+            self.calibrated_freq(reg),
+            self.calibrated_error_ppm(reg, f),
+        ))
+    }
+
+    /// Ramps from the current to the `target` frequency in steps of at most `max_step_hz`
+    ///
+    /// Every step is written to the device, with a pause of `step_us` microseconds before the next
+    /// one. A `max_step_hz` of 0 writes the target directly.
+    pub fn ramp_to<D: DelayUs<u32>>(
+        &mut self,
+        target: u32,
+        max_step_hz: u32,
+        step_us: u32,
+        delay: &mut D,
+    ) -> Result<(), Error<E, PIN::Error>> {
+        freq_to_reg(target)?;
+        let mut f = self.frequ.hz();
+        loop {
+            f = if max_step_hz == 0 {
+                target
+            } else if f < target {
+                target.min(f.saturating_add(max_step_hz))
+            } else {
+                target.max(f.saturating_sub(max_step_hz))
+            };
+            self.set_frequency(f)?;
+            self.write_out().map_err(Error::I2c)?;
+            if f == target {
+                return Ok(());
+            }
+            delay.delay_us(step_us);
+        }
+    }
+
+    /// Plays a tone of frequency `f` for `duration_us` microseconds, e.g. on a piezo buzzer
+    ///
+    /// Both outputs are powered up and enabled for the duration, afterwards the outputs are
+    /// disabled and the oscillator is powered down again.
+    pub fn tone<D: DelayUs<u32>>(
+        &mut self,
+        f: u32,
+        duration_us: u32,
+        delay: &mut D,
+    ) -> Result<(), Error<E, PIN::Error>> {
+        self.configure(f, OutputMode::BothOutputs)?;
+        self.enable_output_for(duration_us, delay)
+            .map_err(Error::Pin)?;
+        self.set_output_mode(OutputMode::PowerDown);
+        self.write_out().map_err(Error::I2c)
+    }
+
+    /// Disables the outputs, then powers the oscillator down
+    ///
+    /// Disabling the outputs first keeps a runt pulse from reaching the clocked circuit. The
+    /// output mode is remembered for [`LTC6904::wake()`].
+    pub fn deep_sleep(&mut self) -> Result<(), Error<E, PIN::Error>> {
+        self.disable_output().map_err(Error::Pin)?;
+        if self.asleep.is_none() {
+            self.asleep = Some(self.output_mode());
+        }
+        self.set_output_mode(OutputMode::PowerDown);
+        self.write_out().map_err(Error::I2c)
+    }
+
+    /// Restores the output mode from before [`LTC6904::deep_sleep()`], waits the
+    /// [`Chip::SETTLING_US`] of the LTC6904 and enables the outputs
+    pub fn wake<D: DelayUs<u32>>(&mut self, delay: &mut D) -> Result<(), Error<E, PIN::Error>> {
+        if let Some(mode) = self.asleep {
+            self.set_output_mode(mode);
+        }
+        self.write_out().map_err(Error::I2c)?;
+        self.asleep = None;
+        delay.delay_us(chip::Ltc6904::SETTLING_US);
+        self.enable_output().map_err(Error::Pin)
+    }
+
+    /// Whether the device has been put to sleep with [`LTC6904::deep_sleep()`] and not woken yet
+    pub fn is_asleep(&self) -> bool {
+        self.asleep.is_some()
+    }
+
+    /// Frequency last requested with [`LTC6904::set_frequency()`] or one of its variants, rounded
+    /// to Hz
+    pub fn requested_frequency(&self) -> u32 {
+        self.frequ.hz()
+    }
+
+    /// Frequency last requested with [`LTC6904::set_frequency()`] or one of its variants
+    pub fn requested_frequency_precise(&self) -> Frequency {
         self.frequ
     }
 
+    /// Frequency generated by the cached register, rounded to Hz
+    ///
+    /// Differs from [`LTC6904::requested_frequency()`] by the quantization error of the DAC. The
+    /// correction set with [`LTC6904::calibrate()`] is included.
+    pub fn programmed_frequency(&self) -> u32 {
+        self.calibrated_freq(self.reg.bits())
+    }
+
+    #[deprecated(note = "use `requested_frequency()`")]
+    pub fn get_frequency(&self) -> u32 {
+        self.requested_frequency()
+    }
+
     pub fn free(mut self) -> Result<(I2C, PIN), <PIN as OutputPin>::Error> {
         self.disable_output()?;
         Ok((self.i2c, self.out_enable))
     }
+
+    /// Disables the outputs, powers the oscillator down and releases bus and pin
+    ///
+    /// Unlike [`LTC6904::free()`], this leaves the device drawing its power-down current. On an
+    /// error the driver is handed back, so the shutdown can be retried.
+    #[allow(clippy::type_complexity, clippy::result_large_err)]
+    pub fn shutdown(mut self) -> Result<(I2C, PIN), (Self, Error<E, PIN::Error>)> {
+        match self.deep_sleep() {
+            Ok(()) => Ok((self.i2c, self.out_enable)),
+            Err(e) => Err((self, e)),
+        }
+    }
+}
+
+// reading back needs a bus supporting reads, everything else only writes
+#[cfg(feature = "readback")]
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E> + Read<Error = E>,
+    PIN: OutputPin,
+{
+    /// Reads the register from the device, the cached configuration isn't changed
+    pub fn read_back(&mut self) -> Result<Register, E> {
+        let mut buffer = [0; 2];
+        self.i2c.read(self.addr.into(), &mut buffer)?;
+        Ok(Register::from_be_bytes(buffer))
+    }
+
+    /// Whether the device register matches the cached one
+    ///
+    /// A match also counts as written for [`LTC6904::has_pending_changes()`].
+    pub fn verify(&mut self) -> Result<bool, E> {
+        let matches = self.read_back()? == self.reg;
+        if matches {
+            self.device_reg = Some(self.reg);
+        }
+        Ok(matches)
+    }
+
+    /// Reads the register from the device and adopts it as the cached configuration, returns
+    /// what differed from the cache
+    ///
+    /// For when the device may have been written behind the driver, e.g. by a bootloader or
+    /// after a brownout reset it to its power-on state. An empty [`ConfigDelta`] means the device
+    /// matched, and the requested frequency is kept. A differing frequency is replaced by the
+    /// one generated by the read register.
+    pub fn sync(&mut self) -> Result<ConfigDelta, E> {
+        let device = self.read_back()?;
+        let frequency_changed = device.with_cnf(0) != self.reg.with_cnf(0);
+        let delta = ConfigDelta {
+            frequency: Some(reg_to_freq(device.bits())).filter(|_| frequency_changed),
+            output_mode: Some(device.output_mode()).filter(|m| *m != self.output_mode()),
+        };
+        if frequency_changed {
+            self.frequ = reg_to_freq(device.bits()).into();
+        }
+        self.reg = device;
+        self.device_reg = Some(device);
+        Ok(delta)
+    }
+}
+
+#[cfg(feature = "driver")]
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: StatefulOutputPin,
+{
+    /// State of the outputs as gated by the OE pin, taking the [`OePolarity`] into account
+    ///
+    /// The clock is only present if the oscillator isn't powered down as well, see
+    /// [`LTC6904::is_powered_down()`].
+    pub fn oe_state(&self) -> Result<OeState, PIN::Error> {
+        let high = self.out_enable.is_set_high()?;
+        let enabled = match self.oe_polarity {
+            OePolarity::ActiveHigh => high,
+            OePolarity::ActiveLow => !high,
+        };
+        Ok(if enabled {
+            OeState::Enabled
+        } else {
+            OeState::Disabled
+        })
+    }
+
+    /// Whether the OE pin currently enables the outputs, see [`LTC6904::oe_state()`]
+    pub fn is_output_enabled(&self) -> Result<bool, PIN::Error> {
+        Ok(self.oe_state()? == OeState::Enabled)
+    }
+
+    /// Enables the outputs unless they already are, returns whether the pin was changed
+    pub fn ensure_output_enabled(&mut self) -> Result<bool, PIN::Error> {
+        if self.is_output_enabled()? {
+            Ok(false)
+        } else {
+            self.enable_output()?;
+            Ok(true)
+        }
+    }
+
+    /// Disables the outputs unless they already are, returns whether the pin was changed
+    pub fn ensure_output_disabled(&mut self) -> Result<bool, PIN::Error> {
+        if self.is_output_enabled()? {
+            self.disable_output()?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
 }
@@ -0,0 +1,227 @@
+//! LTC6903, the SPI sibling of the LTC6904
+//!
+//! Both parts share the register layout and frequency formula, [`LTC6903`] programs the same
+//! [`Register`] words as [`LTC6904`](crate::LTC6904), as one 16 bit SPI transfer, MSB first, in
+//! SPI mode 0. The OE pin is left to the application.
+//!
+//! The chip select is handled by the [`Interface`]:
+//!
+//! - [`CsPin`] drives an explicit CS pin around every transfer on an embedded-hal 0.2 bus
+//! - `Eh1Device` (with the `eh1` feature) writes through an embedded-hal 1.0 `SpiDevice`,
+//!   which asserts CS itself and arbitrates buses shared with other devices
+//! - `AsyncDevice` (with the `async` feature) writes through an embedded-hal-async
+//!   `SpiDevice` with `LTC6903::write_out_async()`, for executors like Embassy
+//!
+//! ```
+//! # use embedded_hal_mock::{pin::{Mock as PinMock, State, Transaction as PinTransaction},
+//! #     spi::{Mock as SpiMock, Transaction}};
+//! use ltc690x::ltc6903::{CsPin, LTC6903};
+//! # let spi = SpiMock::new(&[Transaction::write(vec![0x6c, 0x4c])]);
+//! # let cs = PinMock::new(&[PinTransaction::set(State::High), PinTransaction::set(State::Low),
+//! #     PinTransaction::set(State::High)]);
+//!
+//! let mut ltc = LTC6903::new(CsPin::new(spi, cs).unwrap());
+//! ltc.set_frequency(108_000).unwrap();
+//! ltc.write_out().unwrap();
+//! # let (mut spi, mut cs) = ltc.free().into_inner();
+//! # spi.done();
+//! # cs.done();
+//! ```
+
+use embedded_hal::{blocking::spi::Write, digital::v2::OutputPin};
+
+use crate::{
+    calibration, chip, Config, ErrorCategory, Frequency, FrequencyError, OutputMode, Register,
+};
+
+/// Transfers a register word to an LTC6903, asserting its chip select
+pub trait Interface {
+    type Error;
+
+    /// Writes `bytes` with CS asserted for the whole transfer
+    fn write_word(&mut self, bytes: [u8; 2]) -> Result<(), Self::Error>;
+}
+
+/// Errors of a [`CsPin`] transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+pub enum CsError<E, PE> {
+    /// SPI bus error
+    #[error("SPI error: {0:?}")]
+    Spi(E),
+    /// chip select pin error
+    #[error("CS pin error: {0:?}")]
+    Cs(PE),
+}
+
+impl<E, PE> CsError<E, PE> {
+    /// What went wrong, for error reporting
+    pub const fn category(&self) -> ErrorCategory {
+        match self {
+            CsError::Spi(_) => ErrorCategory::Bus,
+            CsError::Cs(_) => ErrorCategory::Pin,
+        }
+    }
+}
+
+/// Exclusively owned embedded-hal 0.2 SPI bus with an active low CS pin
+#[derive(Debug)]
+pub struct CsPin<SPI, CS> {
+    spi: SPI,
+    cs: CS,
+}
+
+impl<SPI, E, CS> CsPin<SPI, CS>
+where
+    SPI: Write<u8, Error = E>,
+    CS: OutputPin,
+{
+    /// Deasserts `cs` to start out from a known state
+    pub fn new(spi: SPI, mut cs: CS) -> Result<Self, CS::Error> {
+        cs.set_high()?;
+        Ok(Self { spi, cs })
+    }
+
+    /// Returns the bus and the CS pin
+    pub fn into_inner(self) -> (SPI, CS) {
+        (self.spi, self.cs)
+    }
+}
+
+impl<SPI, E, CS> Interface for CsPin<SPI, CS>
+where
+    SPI: Write<u8, Error = E>,
+    CS: OutputPin,
+{
+    type Error = CsError<E, CS::Error>;
+
+    fn write_word(&mut self, bytes: [u8; 2]) -> Result<(), Self::Error> {
+        self.cs.set_low().map_err(CsError::Cs)?;
+        let written = self.spi.write(&bytes).map_err(CsError::Spi);
+        // deassert on bus errors too, a device left selected would take the next transfer
+        let deasserted = self.cs.set_high().map_err(CsError::Cs);
+        written.and(deasserted)
+    }
+}
+
+/// embedded-hal 1.0 `SpiDevice`, managing CS and shared buses itself
+#[cfg(feature = "eh1")]
+#[derive(Debug)]
+pub struct Eh1Device<SPI>(SPI);
+
+#[cfg(feature = "eh1")]
+impl<SPI: embedded_hal_1::spi::SpiDevice> Eh1Device<SPI> {
+    pub fn new(spi: SPI) -> Self {
+        Self(spi)
+    }
+
+    /// Returns the wrapped device
+    pub fn into_inner(self) -> SPI {
+        self.0
+    }
+}
+
+#[cfg(feature = "eh1")]
+impl<SPI: embedded_hal_1::spi::SpiDevice> Interface for Eh1Device<SPI> {
+    type Error = SPI::Error;
+
+    fn write_word(&mut self, bytes: [u8; 2]) -> Result<(), Self::Error> {
+        self.0.write(&bytes)
+    }
+}
+
+/// embedded-hal-async `SpiDevice`, see [`LTC6903::write_out_async()`]
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct AsyncDevice<SPI>(SPI);
+
+#[cfg(feature = "async")]
+impl<SPI: embedded_hal_async::spi::SpiDevice> AsyncDevice<SPI> {
+    pub fn new(spi: SPI) -> Self {
+        Self(spi)
+    }
+
+    /// Returns the wrapped device
+    pub fn into_inner(self) -> SPI {
+        self.0
+    }
+}
+
+/// LTC6903 connected through `IF`
+#[derive(Debug)]
+pub struct LTC6903<IF> {
+    iface: IF,
+    reg: Register,
+    frequ: Frequency,
+}
+
+impl<IF> LTC6903<IF> {
+    pub fn new(iface: IF) -> Self {
+        Self {
+            iface,
+            // OCT = 0, DAC = 0 and both outputs are the power-on state
+            reg: Register::default(),
+            frequ: Config::POWER_ON.frequency.into(),
+        }
+    }
+
+    /// Sets the frequency, see [`LTC6904::set_frequency()`](crate::LTC6904::set_frequency)
+    ///
+    /// [`LTC6903::write_out()`] needs to be called afterwards.
+    pub fn set_frequency(&mut self, f: u32) -> Result<u16, FrequencyError> {
+        let reg = Register::new(calibration::calibrated_reg::<chip::Ltc6903>(f.into(), 0)?);
+        self.frequ = f.into();
+        self.reg = self.reg.with_oct(reg.oct()).with_dac(reg.dac());
+        Ok(self.reg.bits())
+    }
+
+    pub fn set_output_mode(&mut self, mode: OutputMode) {
+        self.reg = self.reg.with_output_mode(mode);
+    }
+
+    pub fn register(&self) -> Register {
+        self.reg
+    }
+
+    /// Frequency last requested with [`LTC6903::set_frequency()`]
+    pub fn requested_frequency(&self) -> Frequency {
+        self.frequ
+    }
+
+    /// Frequency generated by the current configuration, rounded to Hz
+    pub fn frequency(&self) -> u32 {
+        crate::reg_to_freq(self.reg.bits())
+    }
+
+    /// Returns the interface
+    pub fn free(self) -> IF {
+        self.iface
+    }
+}
+
+impl<IF: Interface> LTC6903<IF> {
+    /// Writes the configuration to the device
+    pub fn write_out(&mut self) -> Result<(), IF::Error> {
+        self.iface.write_word(self.reg.to_be_bytes())
+    }
+}
+
+#[cfg(feature = "eh1")]
+impl<SPI: embedded_hal_1::spi::SpiDevice> LTC6903<Eh1Device<SPI>> {
+    /// Creates a driver writing through an embedded-hal 1.0 `SpiDevice`
+    pub fn new_eh1(spi: SPI) -> Self {
+        Self::new(Eh1Device::new(spi))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<SPI: embedded_hal_async::spi::SpiDevice> LTC6903<AsyncDevice<SPI>> {
+    /// Creates a driver writing through an embedded-hal-async `SpiDevice`
+    pub fn new_async(spi: SPI) -> Self {
+        Self::new(AsyncDevice::new(spi))
+    }
+
+    /// [`LTC6903::write_out()`] yielding to the executor while the transfer runs
+    pub async fn write_out_async(&mut self) -> Result<(), SPI::Error> {
+        self.iface.0.write(&self.reg.to_be_bytes()).await
+    }
+}
@@ -0,0 +1,211 @@
+//! LTC6905 fixed frequency oscillator with a pin-selected divider
+//!
+//! The LTC6905 isn't programmed over a bus. A resistor sets its master frequency, which the
+//! three-state DIV pin divides by 1 (tied to V+), 2 (left open) or 4 (tied to GND). The
+//! LTC6905-XXX versions have the master frequency fixed at the factory instead.
+//!
+//! [`LTC6905`] drives the DIV pin through [`DividerPins`], either [`OnePin`] for boards only
+//! switching between ÷1 and ÷4, or [`TwoPins`] with a tri-state buffer for all three settings,
+//! and reports the output frequency as [`Frequency`] like [`LTC6904`](crate::LTC6904) does.
+//!
+//! ```
+//! # use embedded_hal_mock::pin::{Mock as PinMock, State, Transaction};
+//! use ltc690x::ltc6905::{master_frequency, Divider, OnePin, LTC6905};
+//! # let pin = PinMock::new(&[Transaction::set(State::High), Transaction::set(State::Low)]);
+//!
+//! // R_SET = 10 kΩ: 170 MHz master frequency
+//! let mut ltc = LTC6905::new(OnePin::new(pin), master_frequency(10_000), Divider::Div1).unwrap();
+//! assert_eq!(ltc.frequency().hz(), 170_000_000);
+//!
+//! ltc.set_divider(Divider::Div4).unwrap();
+//! assert_eq!(ltc.frequency().hz(), 42_500_000);
+//! # ltc.free().into_inner().done();
+//! ```
+
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{ErrorCategory, Frequency};
+
+/// Ratio between the master and the output frequency, selected by the DIV pin
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Divider {
+    /// DIV tied to V+
+    Div1,
+    /// DIV left open
+    Div2,
+    /// DIV tied to GND
+    Div4,
+}
+
+impl Divider {
+    pub const fn ratio(self) -> u32 {
+        match self {
+            Divider::Div1 => 1,
+            Divider::Div2 => 2,
+            Divider::Div4 => 4,
+        }
+    }
+}
+
+/// Master frequency set by a resistor of `rset_ohms` between SET and V+
+///
+/// Follows the formula of the data sheet, f = 168.5 MHz · 10 kΩ / R_SET + 1.5 MHz, which is
+/// specified for R_SET from 10 kΩ to 25 kΩ.
+pub fn master_frequency(rset_ohms: u32) -> Frequency {
+    let f = 168_500_000_000u64 * 10_000 / rset_ohms.max(1) as u64 + 1_500_000_000;
+    Frequency::from_millihertz(f)
+}
+
+/// Errors selecting the divider
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+pub enum DividerError<PE> {
+    /// error of one of the pins
+    #[error("DIV pin error: {0:?}")]
+    Pin(PE),
+    /// the pins can't put DIV into the state selecting this divider
+    #[error("divider {} not supported by the pins", .0.ratio())]
+    Unsupported(Divider),
+}
+
+impl<PE> DividerError<PE> {
+    /// What went wrong, for error reporting
+    pub const fn category(&self) -> ErrorCategory {
+        match self {
+            DividerError::Pin(_) => ErrorCategory::Pin,
+            DividerError::Unsupported(_) => ErrorCategory::Range,
+        }
+    }
+}
+
+/// Pins driving the DIV input of an [`LTC6905`]
+pub trait DividerPins {
+    type Error;
+
+    /// Puts DIV into the state selecting `divider`
+    fn select(&mut self, divider: Divider) -> Result<(), DividerError<Self::Error>>;
+}
+
+/// DIV driven by one push-pull pin, high for ÷1 and low for ÷4
+///
+/// A push-pull pin can't leave DIV open, [`Divider::Div2`] is
+/// [`Unsupported`](DividerError::Unsupported).
+#[derive(Debug)]
+pub struct OnePin<P> {
+    pin: P,
+}
+
+impl<P: OutputPin> OnePin<P> {
+    pub fn new(pin: P) -> Self {
+        Self { pin }
+    }
+
+    pub fn into_inner(self) -> P {
+        self.pin
+    }
+}
+
+impl<P: OutputPin> DividerPins for OnePin<P> {
+    type Error = P::Error;
+
+    fn select(&mut self, divider: Divider) -> Result<(), DividerError<P::Error>> {
+        match divider {
+            Divider::Div1 => self.pin.set_high(),
+            Divider::Div2 => return Err(DividerError::Unsupported(divider)),
+            Divider::Div4 => self.pin.set_low(),
+        }
+        .map_err(DividerError::Pin)
+    }
+}
+
+/// DIV driven through a tri-state buffer, covering all three dividers
+///
+/// `level` is the buffer input, `drive` its active high enable. With `drive` low, DIV is left
+/// open for [`Divider::Div2`].
+#[derive(Debug)]
+pub struct TwoPins<L, D> {
+    level: L,
+    drive: D,
+}
+
+impl<L, D, PE> TwoPins<L, D>
+where
+    L: OutputPin<Error = PE>,
+    D: OutputPin<Error = PE>,
+{
+    pub fn new(level: L, drive: D) -> Self {
+        Self { level, drive }
+    }
+
+    /// Returns the `level` and `drive` pins
+    pub fn into_inner(self) -> (L, D) {
+        (self.level, self.drive)
+    }
+}
+
+impl<L, D, PE> DividerPins for TwoPins<L, D>
+where
+    L: OutputPin<Error = PE>,
+    D: OutputPin<Error = PE>,
+{
+    type Error = PE;
+
+    fn select(&mut self, divider: Divider) -> Result<(), DividerError<PE>> {
+        // set the level before driving it, so DIV never sees the level of the previous divider
+        match divider {
+            Divider::Div1 => self.level.set_high().and_then(|_| self.drive.set_high()),
+            Divider::Div2 => self.drive.set_low(),
+            Divider::Div4 => self.level.set_low().and_then(|_| self.drive.set_high()),
+        }
+        .map_err(DividerError::Pin)
+    }
+}
+
+/// LTC6905 with its DIV pin driven by `PINS`
+#[derive(Debug)]
+pub struct LTC6905<PINS> {
+    pins: PINS,
+    master: Frequency,
+    divider: Divider,
+}
+
+impl<PINS: DividerPins> LTC6905<PINS> {
+    /// Selects `divider` for a part generating `master`, see [`master_frequency()`]
+    pub fn new(
+        mut pins: PINS,
+        master: Frequency,
+        divider: Divider,
+    ) -> Result<Self, DividerError<PINS::Error>> {
+        pins.select(divider)?;
+        Ok(Self {
+            pins,
+            master,
+            divider,
+        })
+    }
+
+    /// Selects `divider`, keeping the previous one on errors
+    pub fn set_divider(&mut self, divider: Divider) -> Result<(), DividerError<PINS::Error>> {
+        self.pins.select(divider)?;
+        self.divider = divider;
+        Ok(())
+    }
+
+    pub fn divider(&self) -> Divider {
+        self.divider
+    }
+
+    pub fn master_frequency(&self) -> Frequency {
+        self.master
+    }
+
+    /// Output frequency with the selected divider
+    pub fn frequency(&self) -> Frequency {
+        Frequency::from_millihertz(self.master.millihertz() / self.divider.ratio() as u64)
+    }
+
+    /// Returns the pins
+    pub fn free(self) -> PINS {
+        self.pins
+    }
+}
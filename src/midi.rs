@@ -0,0 +1,57 @@
+//! MIDI note to frequency conversion (requires the `midi` feature)
+//!
+//! Equal temperament tuned to A4 = note 69 = 440 Hz. The LTC6904 can't go below 1039 Hz, which
+//! makes note 84 (C6) the lowest note it can generate directly. For clocked filters and
+//! prescaled outputs, use a `multiple` of the note frequency instead.
+
+#[cfg(feature = "driver")]
+use embedded_hal::{blocking::i2c::Write, digital::v2::OutputPin};
+
+#[cfg(feature = "driver")]
+use crate::{Frequency, FrequencyError, LTC6904};
+
+/// Frequencies of the top octave, notes 116 to 127, in millihertz
+#[rustfmt::skip]
+const TOP_OCTAVE: [u32; 12] = [
+    /* 116 */ 6_644_875,
+    /* 117 */ 7_040_000,
+    /* 118 */ 7_458_620,
+    /* 119 */ 7_902_133,
+    /* 120 */ 8_372_018,
+    /* 121 */ 8_869_844,
+    /* 122 */ 9_397_273,
+    /* 123 */ 9_956_063,
+    /* 124 */ 10_548_082,
+    /* 125 */ 11_175_303,
+    /* 126 */ 11_839_822,
+    /* 127 */ 12_543_854,
+];
+
+/// Frequency of MIDI `note` in millihertz, notes above 127 are treated as 127
+pub fn note_to_millihertz(note: u8) -> u32 {
+    let note = note.min(127);
+    let octaves_down = (127 - note as u32) / 12;
+    let f = TOP_OCTAVE[(note as usize + 12 * octaves_down as usize) - 116];
+    (f + (1 << octaves_down >> 1)) >> octaves_down
+}
+
+/// Frequency of MIDI `note` multiplied by `multiple`, rounded to Hz
+pub fn note_to_frequency(note: u8, multiple: u32) -> u32 {
+    let f = note_to_millihertz(note) as u64 * multiple as u64;
+    ((f + 500) / 1000).min(u32::MAX as u64) as u32
+}
+
+#[cfg(feature = "driver")]
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// Sets the frequency to `multiple` times the frequency of MIDI `note`
+    ///
+    /// As with [`LTC6904::set_frequency()`], [`LTC6904::write_out()`] needs to be called afterwards
+    pub fn set_note(&mut self, note: u8, multiple: u32) -> Result<u16, FrequencyError> {
+        let f = note_to_millihertz(note) as u64 * multiple as u64;
+        self.set_frequency_precise(Frequency::from_millihertz(f))
+    }
+}
@@ -0,0 +1,94 @@
+//! Non-blocking writes for buses that can run a transfer in the background
+//!
+//! embedded-hal 0.2 has no non-blocking I2C trait, so the bus needs to implement [`NbWrite`] in
+//! addition to the blocking traits. [`LTC6904::write_out_nb()`] then starts the transfer on the
+//! first call and reports [`nb::Error::WouldBlock`] until it has finished, which lets an RTIC task
+//! poll instead of stalling a high-priority context.
+//!
+//! A blocking write can't be interrupted once the HAL hangs on a stuck bus. Polling one with
+//! [`LTC6904::write_out_timeout()`] gives up after a [`CountDown`] expires instead.
+
+use embedded_hal::{blocking::i2c::Write, digital::v2::OutputPin, timer::CountDown};
+
+use crate::{Error, LTC6904};
+
+/// I2C bus able to send a register word without blocking
+pub trait NbWrite {
+    type Error;
+
+    /// Starts writing `bytes` to `address`, `WouldBlock` while the bus is still busy
+    fn start_write(&mut self, address: u8, bytes: [u8; 2]) -> nb::Result<(), Self::Error>;
+
+    /// Completion of the transfer started last, `WouldBlock` while it's running
+    fn poll_write(&mut self) -> nb::Result<(), Self::Error>;
+}
+
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E> + NbWrite<Error = E>,
+    PIN: OutputPin,
+{
+    /// Non-blocking [`LTC6904::write_out()`]
+    ///
+    /// Call again until it returns `Ok` or an error. The register word is captured when the
+    /// transfer starts, later changes to the configuration need another write.
+    pub fn write_out_nb(&mut self) -> nb::Result<(), E> {
+        let reg = match self.write_pending {
+            Some(reg) => reg,
+            None => {
+                let started =
+                    NbWrite::start_write(&mut self.i2c, self.addr.into(), self.reg.to_be_bytes());
+                if let Err(nb::Error::Other(_)) = started {
+                    // counted like a failed blocking write, a busy bus is just retried
+                    self.note_write(self.reg, false);
+                }
+                started?;
+                self.write_pending = Some(self.reg);
+                self.reg
+            }
+        };
+        let result = NbWrite::poll_write(&mut self.i2c);
+        match result {
+            Err(nb::Error::WouldBlock) => {}
+            Ok(()) => {
+                self.write_pending = None;
+                self.note_write(reg, true);
+                self.record_write(reg);
+            }
+            Err(_) => {
+                self.write_pending = None;
+                self.note_write(reg, false);
+            }
+        }
+        result
+    }
+
+    /// Polls [`LTC6904::write_out_nb()`] until the transfer has completed or `timer` expires
+    /// after `timeout`
+    ///
+    /// After [`Error::Timeout`], the transfer is still pending and the next call to either
+    /// function continues polling it.
+    pub fn write_out_timeout<T: CountDown>(
+        &mut self,
+        timer: &mut T,
+        timeout: T::Time,
+    ) -> Result<(), Error<E, PIN::Error>> {
+        timer.start(timeout);
+        loop {
+            match self.write_out_nb() {
+                Ok(()) => return Ok(()),
+                Err(nb::Error::Other(e)) => return Err(Error::I2c(e)),
+                Err(nb::Error::WouldBlock) => {
+                    if timer.wait().is_ok() {
+                        return Err(Error::Timeout);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether a transfer started by [`LTC6904::write_out_nb()`] hasn't completed yet
+    pub fn is_write_pending(&self) -> bool {
+        self.write_pending.is_some()
+    }
+}
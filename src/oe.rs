@@ -0,0 +1,187 @@
+//! Output enable handle separated from the driver
+//!
+//! [`LTC6904::split_oe()`] moves the OE pin into an [`OutputEnable`], which can gate the clock from
+//! another task or an interrupt handler while the driver keeps configuring the device over the
+//! bus. The driver is left with a [`Detached`] pin, on which its own OE operations do nothing.
+//!
+//! With an OE pin that can't fail, i.e. with `Error = Infallible` like most push-pull GPIOs,
+//! [`InfallibleOe`] switches the outputs without a `Result` to unwrap.
+//!
+//! ```
+//! # #[cfg(feature = "sim")] {
+//! use ltc690x::{sim::Ltc6904Sim, Address, LTC6904};
+//!
+//! let sim = Ltc6904Sim::new(Address::AddressLow);
+//! let ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+//! let (mut ltc, mut oe) = ltc.split_oe();
+//!
+//! ltc.program_frequency(1_000_000).unwrap();
+//! oe.enable().unwrap();
+//! assert!(sim.is_output_enabled());
+//!
+//! let ltc = ltc.join_oe(oe);
+//! # }
+//! ```
+
+use core::convert::Infallible;
+
+use embedded_hal::{
+    blocking::i2c::Write,
+    digital::v2::{OutputPin, StatefulOutputPin},
+};
+
+use crate::{OePolarity, LTC6904};
+
+/// OE pin split off the driver with [`LTC6904::split_oe()`]
+#[derive(Debug)]
+pub struct OutputEnable<PIN> {
+    pin: PIN,
+    polarity: OePolarity,
+}
+
+impl<PIN: OutputPin> OutputEnable<PIN> {
+    pub fn enable(&mut self) -> Result<(), PIN::Error> {
+        match self.polarity {
+            OePolarity::ActiveHigh => self.pin.set_high(),
+            OePolarity::ActiveLow => self.pin.set_low(),
+        }
+    }
+
+    pub fn disable(&mut self) -> Result<(), PIN::Error> {
+        match self.polarity {
+            OePolarity::ActiveHigh => self.pin.set_low(),
+            OePolarity::ActiveLow => self.pin.set_high(),
+        }
+    }
+
+    pub fn polarity(&self) -> OePolarity {
+        self.polarity
+    }
+
+    /// Returns the pin, in the state it was left in
+    pub fn into_inner(self) -> PIN {
+        self.pin
+    }
+}
+
+impl<PIN: StatefulOutputPin> OutputEnable<PIN> {
+    /// Whether the outputs are enabled, taking the [`OePolarity`] into account
+    pub fn is_enabled(&self) -> Result<bool, PIN::Error> {
+        let high = self.pin.is_set_high()?;
+        Ok(high == (self.polarity == OePolarity::ActiveHigh))
+    }
+}
+
+/// Output enable without a `Result`, for OE pins whose error is `Infallible`
+///
+/// ```
+/// # #[cfg(feature = "sim")] {
+/// use ltc690x::{oe::InfallibleOe, sim::Ltc6904Sim, Address, LTC6904};
+///
+/// let sim = Ltc6904Sim::new(Address::AddressLow);
+/// let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+/// ltc.output_on();
+/// assert!(sim.is_output_enabled());
+/// # }
+/// ```
+pub trait InfallibleOe {
+    /// Enables the outputs, taking the [`OePolarity`] into account
+    fn output_on(&mut self);
+
+    /// Disables the outputs, taking the [`OePolarity`] into account
+    fn output_off(&mut self);
+}
+
+impl<I2C, PIN> InfallibleOe for LTC6904<I2C, PIN>
+where
+    I2C: Write,
+    PIN: OutputPin<Error = Infallible>,
+{
+    fn output_on(&mut self) {
+        self.enable_output().unwrap_or_else(|e| match e {})
+    }
+
+    fn output_off(&mut self) {
+        self.disable_output().unwrap_or_else(|e| match e {})
+    }
+}
+
+impl<PIN: OutputPin<Error = Infallible>> InfallibleOe for OutputEnable<PIN> {
+    fn output_on(&mut self) {
+        self.enable().unwrap_or_else(|e| match e {})
+    }
+
+    fn output_off(&mut self) {
+        self.disable().unwrap_or_else(|e| match e {})
+    }
+}
+
+/// Stand-in for the OE pin of a driver after [`LTC6904::split_oe()`], ignoring every change
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Detached;
+
+impl OutputPin for Detached {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// Moves the OE pin into an [`OutputEnable`] handle, keeping its polarity
+    ///
+    /// The pin is left as it is. The returned driver configures the device as before, but
+    /// [`LTC6904::enable_output()`] and the operations built on it, e.g. [`LTC6904::tone()`] or
+    /// [`LTC6904::deep_sleep()`], no longer gate the outputs, the handle has to.
+    pub fn split_oe(self) -> (LTC6904<I2C, Detached>, OutputEnable<PIN>) {
+        let polarity = self.oe_polarity;
+        let (ltc, pin) = self.replace_pin(Detached);
+        (ltc, OutputEnable { pin, polarity })
+    }
+
+    /// Swaps the OE pin, keeping the rest of the state
+    fn replace_pin<P: OutputPin>(self, pin: P) -> (LTC6904<I2C, P>, PIN) {
+        let ltc = LTC6904 {
+            i2c: self.i2c,
+            reg: self.reg,
+            addr: self.addr,
+            frequ: self.frequ,
+            out_enable: pin,
+            oe_polarity: self.oe_polarity,
+            max_error_ppm: self.max_error_ppm,
+            calibration_ppm: self.calibration_ppm,
+            write_pending: self.write_pending,
+            staged: self.staged,
+            device_reg: self.device_reg,
+            refreshes: self.refreshes,
+            asleep: self.asleep,
+            on_write: self.on_write,
+            stats: self.stats,
+            failed_write: self.failed_write,
+            #[cfg(feature = "journal")]
+            journal: self.journal,
+        };
+        (ltc, self.out_enable)
+    }
+}
+
+impl<I2C, E> LTC6904<I2C, Detached>
+where
+    I2C: Write<Error = E>,
+{
+    /// Puts the pin of an [`OutputEnable`] back into the driver
+    pub fn join_oe<PIN: OutputPin>(self, oe: OutputEnable<PIN>) -> LTC6904<I2C, PIN> {
+        let (mut ltc, _) = self.replace_pin(oe.pin);
+        ltc.oe_polarity = oe.polarity;
+        ltc
+    }
+}
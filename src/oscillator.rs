@@ -0,0 +1,97 @@
+//! Object safe interface hiding the bus and pin types
+//!
+//! Structs storing the driver otherwise need to carry its `<I2C, PIN>` parameters. With
+//! [`Oscillator`], they can hold a `&mut dyn Oscillator` instead, bus and pin errors are reduced
+//! to [`OscillatorError`] for that.
+
+#[cfg(feature = "driver")]
+use embedded_hal::{blocking::i2c::Write, digital::v2::OutputPin};
+
+#[cfg(feature = "driver")]
+use crate::LTC6904;
+use crate::{Error, ErrorCategory, FrequencyError, OutputMode, Programmed};
+
+/// Errors of an [`Oscillator`], with the bus and pin errors erased
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+pub enum OscillatorError {
+    /// I2C bus error
+    #[error("I2C error")]
+    I2c,
+    /// output enable pin error
+    #[error("OE pin error")]
+    Pin,
+    /// requested frequency can't be generated
+    #[error(transparent)]
+    Frequency(FrequencyError),
+    /// the bus didn't finish the transfer in time
+    #[error("I2C timeout")]
+    Timeout,
+}
+
+impl OscillatorError {
+    /// What went wrong, for error reporting
+    pub const fn category(&self) -> ErrorCategory {
+        match self {
+            OscillatorError::I2c => ErrorCategory::Bus,
+            OscillatorError::Pin => ErrorCategory::Pin,
+            OscillatorError::Frequency(e) => e.category(),
+            OscillatorError::Timeout => ErrorCategory::Timeout,
+        }
+    }
+}
+
+impl<E, PE> From<Error<E, PE>> for OscillatorError {
+    fn from(e: Error<E, PE>) -> Self {
+        match e {
+            Error::I2c(_) => OscillatorError::I2c,
+            Error::Timeout => OscillatorError::Timeout,
+            Error::Pin(_) => OscillatorError::Pin,
+            Error::Frequency(e) => OscillatorError::Frequency(e),
+        }
+    }
+}
+
+/// Programmable oscillator, applying every change to the device right away
+pub trait Oscillator {
+    /// Sets the frequency and writes it to the device, see [`LTC6904::program_frequency()`]
+    fn program(&mut self, f: u32) -> Result<Programmed, OscillatorError>;
+
+    /// Sets the output mode and writes it to the device
+    fn apply_output_mode(&mut self, mode: OutputMode) -> Result<(), OscillatorError>;
+
+    /// Enables (`true`) or disables the outputs through the OE pin
+    fn set_output_enabled(&mut self, enabled: bool) -> Result<(), OscillatorError>;
+
+    /// Frequency generated by the current configuration, rounded to Hz
+    fn frequency(&self) -> u32;
+}
+
+#[cfg(feature = "driver")]
+impl<I2C, E, PIN> Oscillator for LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    fn program(&mut self, f: u32) -> Result<Programmed, OscillatorError> {
+        Ok(self.program_frequency(f)?)
+    }
+
+    fn apply_output_mode(&mut self, mode: OutputMode) -> Result<(), OscillatorError> {
+        self.set_output_mode(mode);
+        self.write_out().map_err(|_| OscillatorError::I2c)
+    }
+
+    fn set_output_enabled(&mut self, enabled: bool) -> Result<(), OscillatorError> {
+        if enabled {
+            self.enable_output()
+        } else {
+            self.disable_output()
+        }
+        .map_err(|_| OscillatorError::Pin)
+    }
+
+    fn frequency(&self) -> u32 {
+        self.programmed_frequency()
+    }
+}
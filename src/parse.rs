@@ -0,0 +1,164 @@
+//! Parsing driver types from user input (requires the `parse` feature)
+//!
+//! For configuration shells on the device and host tools: [`OutputMode`] parses from the names
+//! "both", "clk", "clkinv" and "off", regardless of case, and [`Frequency`] from a number with
+//! an optional unit, like "12.288MHz", "108 kHz" or "1039". Nothing is allocated.
+//!
+//! ```
+//! use ltc690x::{Frequency, OutputMode};
+//!
+//! let mode: OutputMode = "clkinv".parse().unwrap();
+//! assert_eq!(mode, OutputMode::ClkInvOnly);
+//! assert_eq!(mode.name(), "clkinv");
+//!
+//! let f: Frequency = "12.288MHz".parse().unwrap();
+//! assert_eq!(f, Frequency::from_kilohertz(12_288));
+//! ```
+
+use core::{convert::TryFrom, str::FromStr};
+
+use crate::{ErrorCategory, Frequency, OutputMode};
+
+/// A string naming no output mode
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+#[error("expected one of both, clk, clkinv or off")]
+pub struct ParseOutputModeError;
+
+impl ParseOutputModeError {
+    /// Always [`ErrorCategory::Range`]
+    pub const fn category(&self) -> ErrorCategory {
+        ErrorCategory::Range
+    }
+}
+
+const MODES: [OutputMode; 4] = [
+    OutputMode::BothOutputs,
+    OutputMode::ClkOnly,
+    OutputMode::ClkInvOnly,
+    OutputMode::PowerDown,
+];
+
+impl OutputMode {
+    /// Name the mode parses from
+    pub const fn name(self) -> &'static str {
+        match self {
+            OutputMode::BothOutputs => "both",
+            OutputMode::ClkOnly => "clk",
+            OutputMode::ClkInvOnly => "clkinv",
+            OutputMode::PowerDown => "off",
+        }
+    }
+}
+
+impl FromStr for OutputMode {
+    type Err = ParseOutputModeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        MODES
+            .iter()
+            .copied()
+            .find(|mode| mode.name().eq_ignore_ascii_case(s))
+            .ok_or(ParseOutputModeError)
+    }
+}
+
+impl TryFrom<&str> for OutputMode {
+    type Error = ParseOutputModeError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[allow(deprecated)]
+impl FromStr for crate::OutputSettings {
+    type Err = ParseOutputModeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        OutputMode::from_str(s).map(Self::from)
+    }
+}
+
+/// Why a string isn't a [`Frequency`]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+pub enum ParseFrequencyError {
+    /// no number, or characters other than digits and one decimal point in it
+    #[error("invalid number")]
+    Invalid,
+    /// a unit other than Hz, kHz and MHz
+    #[error("expected Hz, kHz or MHz")]
+    Unit,
+    /// more decimals than millihertz resolve
+    #[error("finer than 1 mHz")]
+    Precision,
+    /// more than `u64::MAX` millihertz
+    #[error("frequency too large")]
+    Overflow,
+}
+
+impl ParseFrequencyError {
+    /// Always [`ErrorCategory::Range`]
+    pub const fn category(&self) -> ErrorCategory {
+        ErrorCategory::Range
+    }
+}
+
+impl FromStr for Frequency {
+    type Err = ParseFrequencyError;
+
+    /// Parses a number in Hz, or in the unit following it
+    ///
+    /// Units are matched regardless of case, so "mhz" is megahertz like "MHz". Decimals down to
+    /// the millihertz are accepted, e.g. "1.5 Hz" or "12.288001 MHz", and the output of
+    /// [`format_frequency()`](crate::frequency::format_frequency) parses back.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(s.len());
+        let (number, unit) = s.split_at(split);
+        if number.is_empty() {
+            return Err(ParseFrequencyError::Invalid);
+        }
+        let decimals = match unit.trim_start() {
+            u if u.is_empty() || u.eq_ignore_ascii_case("hz") => 3,
+            u if u.eq_ignore_ascii_case("khz") => 6,
+            u if u.eq_ignore_ascii_case("mhz") => 9,
+            _ => return Err(ParseFrequencyError::Unit),
+        };
+
+        let (int, frac) = match number.find('.') {
+            Some(dot) => (&number[..dot], &number[dot + 1..]),
+            None => (number, ""),
+        };
+        if number == "." || frac.contains('.') {
+            return Err(ParseFrequencyError::Invalid);
+        }
+        if frac.len() > decimals {
+            return Err(ParseFrequencyError::Precision);
+        }
+        let mut millihertz: u64 = 0;
+        for d in int.bytes().chain(frac.bytes()) {
+            millihertz = millihertz
+                .checked_mul(10)
+                .and_then(|m| m.checked_add((d - b'0') as u64))
+                .ok_or(ParseFrequencyError::Overflow)?;
+        }
+        let scale = 10u64.pow((decimals - frac.len()) as u32);
+        millihertz
+            .checked_mul(scale)
+            .map(Frequency::from_millihertz)
+            .ok_or(ParseFrequencyError::Overflow)
+    }
+}
+
+impl TryFrom<&str> for Frequency {
+    type Error = ParseFrequencyError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
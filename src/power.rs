@@ -0,0 +1,165 @@
+//! Driver switching the supply of the device
+//!
+//! Boards saving power by gating VCC of the oscillator with a regulator or load switch hand its
+//! enable pin to the driver with [`LTC6904::with_power_rail()`]. The returned [`PoweredLtc6904`]
+//! sequences the supply: [`PoweredLtc6904::power_rail_up()`] switches it on, waits for it to ramp
+//! up and writes the cached configuration, which the device lost while unpowered.
+//! [`PoweredLtc6904::power_rail_down()`] disables the outputs before switching it off, so OE
+//! doesn't back-power the device through its input. Everything else derefs to the driver.
+//!
+//! ```
+//! # #[cfg(feature = "sim")] {
+//! use embedded_hal_mock::{
+//!     delay::MockNoop,
+//!     pin::{Mock, State, Transaction},
+//! };
+//! use ltc690x::{sim::Ltc6904Sim, Address, LTC6904};
+//!
+//! let sim = Ltc6904Sim::new(Address::AddressLow);
+//! let rail = Mock::new(&[Transaction::set(State::High)]);
+//! let ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+//! let mut ltc = ltc.with_power_rail(rail);
+//!
+//! ltc.set_frequency(1_000_000).unwrap();
+//! ltc.power_rail_up(&mut MockNoop::new()).unwrap();
+//! assert_eq!(sim.register(), ltc.get_reg());
+//! # let (_, mut rail) = ltc.into_parts();
+//! # rail.done();
+//! # }
+//! ```
+
+use core::ops::{Deref, DerefMut};
+
+use embedded_hal::{
+    blocking::{delay::DelayUs, i2c::Write},
+    digital::v2::OutputPin,
+};
+
+use crate::{consts::POWER_RAIL_SETTLING_US, Error, ErrorCategory, LTC6904};
+
+/// Error of a [`PoweredLtc6904`] operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+pub enum RailError<E, PE, RE> {
+    /// error of the driver, i.e. the bus or the OE pin
+    #[error(transparent)]
+    Driver(#[from] Error<E, PE>),
+    /// error of the power rail enable pin
+    #[error("power rail pin error: {0:?}")]
+    Rail(RE),
+}
+
+impl<E, PE, RE> RailError<E, PE, RE> {
+    /// What went wrong, for error reporting
+    pub const fn category(&self) -> ErrorCategory {
+        match self {
+            RailError::Driver(e) => e.category(),
+            RailError::Rail(_) => ErrorCategory::Pin,
+        }
+    }
+}
+
+/// [`LTC6904`] with the enable pin of its supply, created by [`LTC6904::with_power_rail()`]
+pub struct PoweredLtc6904<I2C, PIN, RAIL>
+where
+    I2C: Write,
+    PIN: OutputPin,
+{
+    ltc: LTC6904<I2C, PIN>,
+    rail: RAIL,
+    settling_us: u32,
+    powered: bool,
+}
+
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// Hands the active high enable pin of the supply of the device to the driver
+    ///
+    /// The pin is left as it is and the supply is assumed to be off until
+    /// [`PoweredLtc6904::power_rail_up()`].
+    pub fn with_power_rail<RAIL: OutputPin>(self, rail: RAIL) -> PoweredLtc6904<I2C, PIN, RAIL> {
+        PoweredLtc6904 {
+            ltc: self,
+            rail,
+            settling_us: POWER_RAIL_SETTLING_US,
+            powered: false,
+        }
+    }
+}
+
+impl<I2C, E, PIN, RAIL> PoweredLtc6904<I2C, PIN, RAIL>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+    RAIL: OutputPin,
+{
+    /// Switches the supply on, waits for it to settle and writes the cached configuration
+    ///
+    /// The outputs are disabled first and left disabled, enable them once the clock is needed.
+    pub fn power_rail_up<D: DelayUs<u32>>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<(), RailError<E, PIN::Error, RAIL::Error>> {
+        self.ltc.disable_output().map_err(Error::Pin)?;
+        self.rail.set_high().map_err(RailError::Rail)?;
+        self.powered = true;
+        // the device came up in its power-on state, whatever was written before
+        self.ltc.device_reg = None;
+        delay.delay_us(self.settling_us);
+        self.ltc.write_out().map_err(Error::I2c)?;
+        Ok(())
+    }
+
+    /// Disables the outputs and switches the supply off
+    ///
+    /// The cached configuration is kept for the next [`PoweredLtc6904::power_rail_up()`].
+    pub fn power_rail_down(&mut self) -> Result<(), RailError<E, PIN::Error, RAIL::Error>> {
+        self.ltc.disable_output().map_err(Error::Pin)?;
+        self.rail.set_low().map_err(RailError::Rail)?;
+        self.powered = false;
+        self.ltc.device_reg = None;
+        Ok(())
+    }
+
+    /// Whether the supply was switched on with [`PoweredLtc6904::power_rail_up()`]
+    pub fn is_rail_up(&self) -> bool {
+        self.powered
+    }
+
+    /// Time [`PoweredLtc6904::power_rail_up()`] waits before writing the configuration, defaults
+    /// to [`POWER_RAIL_SETTLING_US`]
+    ///
+    /// Regulators with a soft start ramping slower than that need a longer time.
+    pub fn set_rail_settling_us(&mut self, settling_us: u32) {
+        self.settling_us = settling_us;
+    }
+
+    /// Returns the driver and the enable pin, in the state it was left in
+    pub fn into_parts(self) -> (LTC6904<I2C, PIN>, RAIL) {
+        (self.ltc, self.rail)
+    }
+}
+
+impl<I2C, PIN, RAIL> Deref for PoweredLtc6904<I2C, PIN, RAIL>
+where
+    I2C: Write,
+    PIN: OutputPin,
+{
+    type Target = LTC6904<I2C, PIN>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.ltc
+    }
+}
+
+impl<I2C, PIN, RAIL> DerefMut for PoweredLtc6904<I2C, PIN, RAIL>
+where
+    I2C: Write,
+    PIN: OutputPin,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.ltc
+    }
+}
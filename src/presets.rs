@@ -0,0 +1,284 @@
+//! Precomputed register words for fast switching between known frequencies
+//!
+//! Besides the user defined [`Presets`], the module has a catalog of frequencies that are
+//! commonly generated with crystals, see [`STANDARD_FREQUENCIES`]. With the `heapless` feature,
+//! `NamedPresets` manages frequencies by name at runtime.
+
+#[cfg(feature = "driver")]
+use embedded_hal::{blocking::i2c::Write, digital::v2::OutputPin};
+
+#[cfg(feature = "driver")]
+use crate::LTC6904;
+use crate::{freq_to_reg, FrequencyError, OutputMode};
+
+/// Bank of `N` frequencies with their register words calculated up front
+///
+/// [`Presets::select()`] only performs the 2 byte write, which makes it cheap enough to switch
+/// frequencies from an interrupt handler.
+#[derive(Debug, Clone, Copy)]
+pub struct Presets<const N: usize> {
+    frequencies: [u32; N],
+    regs: [u16; N],
+}
+
+impl<const N: usize> Presets<N> {
+    /// Calculates the register words for all `frequencies` with the given output configuration
+    pub fn new(frequencies: [u32; N], output: OutputMode) -> Result<Self, FrequencyError> {
+        let cnf = output.bits();
+        let mut regs = [0; N];
+        for (reg, f) in regs.iter_mut().zip(frequencies.iter()) {
+            *reg = freq_to_reg(*f)? | cnf;
+        }
+        Ok(Self { frequencies, regs })
+    }
+
+    pub fn len(&self) -> usize {
+        N
+    }
+
+    pub fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Register word of preset `index`
+    pub fn get(&self, index: usize) -> Option<u16> {
+        self.regs.get(index).copied()
+    }
+
+    /// Requested frequency of preset `index`
+    pub fn frequency(&self, index: usize) -> Option<u32> {
+        self.frequencies.get(index).copied()
+    }
+
+    /// Writes preset `index` to the device, returns `false` without writing if there is none
+    #[cfg(feature = "driver")]
+    pub fn select<I2C, E, PIN>(&self, ltc: &mut LTC6904<I2C, PIN>, index: usize) -> Result<bool, E>
+    where
+        I2C: Write<Error = E>,
+        PIN: OutputPin,
+    {
+        match (self.regs.get(index), self.frequencies.get(index)) {
+            (Some(reg), Some(f)) => ltc.write_precomputed(*reg, *f).map(|_| true),
+            _ => Ok(false),
+        }
+    }
+}
+
+/// Frequency commonly generated by a crystal, with its register word calculated ahead of time
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StandardFrequency {
+    /// nominal frequency in Hz
+    pub frequency: u32,
+    /// register word with CNF = 0
+    pub reg: u16,
+    /// error of the generated frequency in ppm
+    pub error_ppm: i32,
+}
+
+impl StandardFrequency {
+    /// Writes the frequency to the device, keeping its output mode
+    ///
+    /// The word is calculated for an uncalibrated part, use [`LTC6904::set_frequency()`] with
+    /// [`StandardFrequency::frequency`] to apply a calibration.
+    #[cfg(feature = "driver")]
+    pub fn select<I2C, E, PIN>(&self, ltc: &mut LTC6904<I2C, PIN>) -> Result<(), E>
+    where
+        I2C: Write<Error = E>,
+        PIN: OutputPin,
+    {
+        let reg = self.reg | ltc.get_cnf();
+        ltc.write_precomputed(reg, self.frequency)
+    }
+}
+
+const fn standard(frequency: u32, reg: u16, error_ppm: i32) -> StandardFrequency {
+    StandardFrequency {
+        frequency,
+        reg,
+        error_ppm,
+    }
+}
+
+/// 32.768 kHz, watch crystal, RTCs
+pub const KHZ_32_768: StandardFrequency = standard(32_768, 0x4fc4, 0);
+/// 1 MHz
+pub const MHZ_1: StandardFrequency = standard(1_000_000, 0x9efc, 432);
+/// 1.8432 MHz, 16 x 115200 baud
+pub const MHZ_1_8432: StandardFrequency = standard(1_843_200, 0xad88, 128);
+/// 3.6864 MHz, 32 x 115200 baud
+pub const MHZ_3_6864: StandardFrequency = standard(3_686_400, 0xbd88, 128);
+/// 4 MHz
+pub const MHZ_4: StandardFrequency = standard(4_000_000, 0xbefc, 432);
+/// 4.096 MHz
+pub const MHZ_4_096: StandardFrequency = standard(4_096_000, 0xbf60, -60);
+/// 8 MHz
+pub const MHZ_8: StandardFrequency = standard(8_000_000, 0xcefc, 432);
+/// 11.0592 MHz, 96 x 115200 baud
+pub const MHZ_11_0592: StandardFrequency = standard(11_059_200, 0xd760, 128);
+/// 12 MHz, USB PHYs
+pub const MHZ_12: StandardFrequency = standard(12_000_000, 0xd94c, -257);
+/// 12.288 MHz, 256 x 48 kHz audio
+pub const MHZ_12_288: StandardFrequency = standard(12_288_000, 0xd9d4, -295);
+/// 16 MHz
+pub const MHZ_16: StandardFrequency = standard(16_000_000, 0xdefc, 432);
+/// 24 MHz
+pub const MHZ_24: StandardFrequency = standard(24_000_000, 0xe94c, -257);
+/// 24.576 MHz, 512 x 48 kHz audio
+pub const MHZ_24_576: StandardFrequency = standard(24_576_000, 0xe9d4, -295);
+/// 25 MHz, Ethernet PHYs
+pub const MHZ_25: StandardFrequency = standard(25_000_000, 0xea34, -343);
+/// 27 MHz, video
+pub const MHZ_27: StandardFrequency = standard(27_000_000, 0xebd4, 174);
+/// 48 MHz, USB
+pub const MHZ_48: StandardFrequency = standard(48_000_000, 0xf94c, -257);
+
+/// All standard frequencies, ascending
+pub const STANDARD_FREQUENCIES: [StandardFrequency; 16] = [
+    KHZ_32_768,
+    MHZ_1,
+    MHZ_1_8432,
+    MHZ_3_6864,
+    MHZ_4,
+    MHZ_4_096,
+    MHZ_8,
+    MHZ_11_0592,
+    MHZ_12,
+    MHZ_12_288,
+    MHZ_16,
+    MHZ_24,
+    MHZ_24_576,
+    MHZ_25,
+    MHZ_27,
+    MHZ_48,
+];
+
+/// Longest name of a [`NamedPresets`] entry in bytes
+#[cfg(feature = "heapless")]
+pub const PRESET_NAME_LEN: usize = 16;
+
+/// Errors of [`NamedPresets::add_preset()`]
+#[cfg(feature = "heapless")]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+pub enum PresetError {
+    /// the frequency can't be generated
+    #[error(transparent)]
+    Frequency(FrequencyError),
+    /// all `N` entries are in use
+    #[error("no room for another preset")]
+    Full,
+    /// the name is longer than [`PRESET_NAME_LEN`]
+    #[error("preset name longer than {} bytes", PRESET_NAME_LEN)]
+    NameTooLong,
+}
+
+#[cfg(feature = "heapless")]
+impl PresetError {
+    /// What went wrong, for error reporting
+    pub const fn category(&self) -> crate::ErrorCategory {
+        match self {
+            PresetError::Frequency(e) => e.category(),
+            PresetError::Full | PresetError::NameTooLong => crate::ErrorCategory::Range,
+        }
+    }
+}
+
+#[cfg(feature = "heapless")]
+#[derive(Debug, Clone)]
+struct NamedPreset {
+    name: heapless::String<PRESET_NAME_LEN>,
+    frequency: u32,
+    reg: u16,
+}
+
+/// Up to `N` frequencies managed by name at runtime, e.g. from a shell (requires the `heapless`
+/// feature)
+///
+/// Like [`StandardFrequency`], the register words are calculated when the preset is added, for
+/// an uncalibrated part, and selecting one keeps the output mode of the driver.
+#[cfg(feature = "heapless")]
+#[derive(Debug, Clone, Default)]
+pub struct NamedPresets<const N: usize> {
+    presets: heapless::Vec<NamedPreset, N>,
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> NamedPresets<N> {
+    pub const fn new() -> Self {
+        Self {
+            presets: heapless::Vec::new(),
+        }
+    }
+
+    /// Adds the preset `name` for `f`, replacing an existing one of the same name
+    pub fn add_preset(&mut self, name: &str, f: u32) -> Result<(), PresetError> {
+        let reg = freq_to_reg(f).map_err(PresetError::Frequency)?;
+        if let Some(preset) = self.presets.iter_mut().find(|p| p.name == name) {
+            preset.frequency = f;
+            preset.reg = reg;
+            return Ok(());
+        }
+        let name = core::convert::TryFrom::try_from(name).map_err(|_| PresetError::NameTooLong)?;
+        self.presets
+            .push(NamedPreset {
+                name,
+                frequency: f,
+                reg,
+            })
+            .map_err(|_| PresetError::Full)
+    }
+
+    /// Removes the preset `name`, returns whether there was one
+    pub fn remove_preset(&mut self, name: &str) -> bool {
+        match self.presets.iter().position(|p| p.name == name) {
+            Some(i) => {
+                self.presets.remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Frequency of the preset `name`
+    pub fn frequency(&self, name: &str) -> Option<u32> {
+        self.find(name).map(|p| p.frequency)
+    }
+
+    /// Names and frequencies of all presets, in the order they were added
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.presets.iter().map(|p| (p.name.as_str(), p.frequency))
+    }
+
+    pub fn len(&self) -> usize {
+        self.presets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.presets.is_empty()
+    }
+
+    /// Writes the preset `name` to the device, returns `false` without writing if there is none
+    #[cfg(feature = "driver")]
+    pub fn select_preset<I2C, E, PIN>(
+        &self,
+        ltc: &mut LTC6904<I2C, PIN>,
+        name: &str,
+    ) -> Result<bool, E>
+    where
+        I2C: Write<Error = E>,
+        PIN: OutputPin,
+    {
+        match self.find(name) {
+            Some(p) => {
+                let reg = p.reg | ltc.get_cnf();
+                ltc.write_precomputed(reg, p.frequency).map(|_| true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn find(&self, name: &str) -> Option<&NamedPreset> {
+        self.presets.iter().find(|p| p.name == name)
+    }
+}
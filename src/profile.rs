@@ -0,0 +1,127 @@
+//! Timed sequences of frequencies declared as data
+//!
+//! A [`Profile`] lists the steps of a stimulus, e.g. for a test fixture, each with its frequency,
+//! output mode and duration. [`LTC6904::play()`] programs them one after the other, waiting on a
+//! blocking delay. With the `async` feature, `LTC6904::play_async()` waits on an
+//! `embedded-hal-async` delay instead.
+//!
+//! ```
+//! use ltc690x::{
+//!     profile::{Profile, Step},
+//!     OutputMode,
+//! };
+//!
+//! static STIMULUS: Profile = Profile::new(&[
+//!     Step::new(10_000, 1_000_000, OutputMode::BothOutputs),
+//!     Step::new(5_000, 2_000_000, OutputMode::ClkOnly),
+//!     Step::new(1_000, 1_039, OutputMode::PowerDown),
+//! ]);
+//! assert_eq!(STIMULUS.duration_us(), 16_000);
+//! ```
+
+#[cfg(feature = "driver")]
+use embedded_hal::{
+    blocking::{delay::DelayUs, i2c::Write},
+    digital::v2::OutputPin,
+};
+
+use crate::OutputMode;
+#[cfg(feature = "driver")]
+use crate::{calibration, chip, Error, FrequencyError, LTC6904};
+
+/// One step of a [`Profile`]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Step {
+    /// time until the next step in microseconds
+    pub duration_us: u32,
+    /// frequency in Hz
+    pub frequency: u32,
+    pub output_mode: OutputMode,
+}
+
+impl Step {
+    pub const fn new(duration_us: u32, frequency: u32, output_mode: OutputMode) -> Self {
+        Self {
+            duration_us,
+            frequency,
+            output_mode,
+        }
+    }
+}
+
+/// Sequence of [`Step`]s, played with [`LTC6904::play()`]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Profile<'a> {
+    steps: &'a [Step],
+}
+
+impl<'a> Profile<'a> {
+    pub const fn new(steps: &'a [Step]) -> Self {
+        Self { steps }
+    }
+
+    pub fn steps(&self) -> &'a [Step] {
+        self.steps
+    }
+
+    /// Total duration of all steps in microseconds
+    pub fn duration_us(&self) -> u64 {
+        self.steps.iter().map(|s| s.duration_us as u64).sum()
+    }
+}
+
+#[cfg(feature = "driver")]
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// Checks every step of `profile` before anything is written, rejecting it if
+    /// [`LTC6904::configure()`] would reject one of its steps
+    fn check_profile(&self, profile: &Profile) -> Result<(), FrequencyError> {
+        profile.steps.iter().try_for_each(|s| {
+            calibration::checked_reg::<chip::Ltc6904>(
+                s.frequency.into(),
+                self.calibration_ppm,
+                self.max_error_ppm,
+            )
+            .map(|_| ())
+        })
+    }
+
+    /// Programs the steps of `profile` one after the other, each for its duration
+    ///
+    /// The frequencies are checked up front, the profile isn't started if one of them can't be
+    /// set. The OE pin isn't touched, the device stays on the last step afterwards.
+    pub fn play<D: DelayUs<u32>>(
+        &mut self,
+        profile: &Profile,
+        delay: &mut D,
+    ) -> Result<(), Error<E, PIN::Error>> {
+        self.check_profile(profile)?;
+        for step in profile.steps {
+            self.configure(step.frequency, step.output_mode)?;
+            delay.delay_us(step.duration_us);
+        }
+        Ok(())
+    }
+
+    /// [`LTC6904::play()`] waiting on an async delay (requires the `async` feature)
+    ///
+    /// The register writes still block, only the waits between them yield to the executor.
+    #[cfg(feature = "async")]
+    pub async fn play_async<D: embedded_hal_async::delay::DelayNs>(
+        &mut self,
+        profile: &Profile<'_>,
+        delay: &mut D,
+    ) -> Result<(), Error<E, PIN::Error>> {
+        self.check_profile(profile)?;
+        for step in profile.steps {
+            self.configure(step.frequency, step.output_mode)?;
+            delay.delay_us(step.duration_us).await;
+        }
+        Ok(())
+    }
+}
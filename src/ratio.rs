@@ -0,0 +1,51 @@
+//! Frequencies as a ratio of a reference clock
+//!
+//! Peripherals sharing data with one clocked from the system clock often need a multiple of it,
+//! e.g. 3/2 × 8 MHz. [`multiple_of()`] calculates the target with millihertz resolution and
+//! [`LTC6904::set_multiple_of()`] programs it, reporting the error of the achieved ratio in ppm.
+
+use core::convert::TryFrom;
+
+#[cfg(feature = "driver")]
+use embedded_hal::{blocking::i2c::Write, digital::v2::OutputPin};
+
+#[cfg(feature = "driver")]
+use crate::LTC6904;
+use crate::{Frequency, FrequencyError};
+
+/// `n / d` × `reference_hz`, rounded to millihertz
+///
+/// A `d` of 0 is [`FrequencyError::TooHigh`], as is a result beyond [`Frequency`].
+pub fn multiple_of(reference_hz: u32, n: u32, d: u32) -> Result<Frequency, FrequencyError> {
+    if d == 0 {
+        return Err(FrequencyError::TooHigh);
+    }
+    let num = reference_hz as u128 * n as u128 * 1_000;
+    let d = d as u128;
+    let millihertz = (num + d / 2) / d;
+    u64::try_from(millihertz)
+        .map(Frequency::from_millihertz)
+        .map_err(|_| FrequencyError::TooHigh)
+}
+
+#[cfg(feature = "driver")]
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// Sets the frequency to `n / d` × `reference_hz`
+    ///
+    /// Returns the error of the achieved ratio in ppm, including the calibration. As with
+    /// [`LTC6904::set_frequency()`], [`LTC6904::write_out()`] needs to be called afterwards.
+    pub fn set_multiple_of(
+        &mut self,
+        reference_hz: u32,
+        n: u32,
+        d: u32,
+    ) -> Result<i32, FrequencyError> {
+        let f = multiple_of(reference_hz, n, d)?;
+        let reg = self.set_frequency_precise(f)?;
+        Ok(self.calibrated_error_ppm(reg, f))
+    }
+}
@@ -0,0 +1,142 @@
+//! The 16 bit register of the device
+//!
+//! | bits   | 15..12 | 11..2 | 1..0 |
+//! |--------|--------|-------|------|
+//! | field  | OCT    | DAC   | CNF  |
+//!
+//! The layout is declared once in the `fields!` table below, which generates the masks, the
+//! accessors and the defmt output, and checks at compile time that the fields cover all 16 bits
+//! without overlapping. The module doesn't depend on embedded-hal, so the packing can be tested on
+//! the host without a bus or pin.
+
+use core::fmt;
+
+use crate::OutputMode;
+
+/// Register word with accessors for its OCT, DAC and CNF fields
+///
+/// Setters take the register by value and return the changed copy, values too wide for their
+/// field are truncated to it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Register(u16);
+
+/// Mask of the bits `msb` down to `lsb`
+const fn mask(msb: u16, lsb: u16) -> u16 {
+    (u16::MAX >> (15 - msb)) & (u16::MAX << lsb)
+}
+
+/// Generates a getter and a `with_` setter for every field, given by its most and least
+/// significant bit
+macro_rules! fields {
+    ($($get:ident, $set:ident: $msb:literal..=$lsb:literal;)*) => {
+        impl Register {
+            $(
+                pub const fn $get(self) -> u16 {
+                    (self.0 & mask($msb, $lsb)) >> $lsb
+                }
+
+                pub const fn $set(self, value: u16) -> Self {
+                    Self((self.0 & !mask($msb, $lsb)) | ((value << $lsb) & mask($msb, $lsb)))
+                }
+            )*
+        }
+
+        const _: () = assert!((0 $(| mask($msb, $lsb))*) == u16::MAX, "fields leave bits out");
+        const _: () = assert!((0 $(+ ($msb - $lsb + 1))*) == 16, "fields overlap");
+
+        #[cfg(feature = "defmt")]
+        impl defmt::Format for Register {
+            fn format(&self, f: defmt::Formatter) {
+                defmt::write!(f, "Register {{ bits: {=u16:#06x}", self.0);
+                $(defmt::write!(f, ", {=str}: {=u16}", stringify!($get), self.$get());)*
+                defmt::write!(f, " }}");
+            }
+        }
+    };
+}
+
+fields! {
+    oct, with_oct: 15..=12;
+    dac, with_dac: 11..=2;
+    cnf, with_cnf: 1..=0;
+}
+
+impl Register {
+    pub const fn new(bits: u16) -> Self {
+        Self(bits)
+    }
+
+    /// Packs the fields, values too wide for their field are truncated
+    pub const fn from_fields(oct: u16, dac: u16, cnf: u16) -> Self {
+        Self(0).with_oct(oct).with_dac(dac).with_cnf(cnf)
+    }
+
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    pub fn output_mode(self) -> OutputMode {
+        OutputMode::from_cnf(self.cnf())
+    }
+
+    pub fn with_output_mode(self, mode: OutputMode) -> Self {
+        self.with_cnf(mode.into())
+    }
+
+    /// OCT and DAC as a single 14 bit code, ascending with the frequency
+    pub(crate) const fn code(self) -> u16 {
+        (self.oct() << 10) | self.dac()
+    }
+
+    /// Register with CNF = 0 from a [`Register::code()`]
+    pub(crate) const fn from_code(code: u16) -> Self {
+        Self::from_fields(code >> 10, code, 0)
+    }
+
+    /// Register in the byte order it's sent on the bus
+    pub const fn to_be_bytes(self) -> [u8; 2] {
+        self.0.to_be_bytes()
+    }
+
+    pub const fn from_be_bytes(bytes: [u8; 2]) -> Self {
+        Self(u16::from_be_bytes(bytes))
+    }
+}
+
+impl From<u16> for Register {
+    fn from(bits: u16) -> Self {
+        Self(bits)
+    }
+}
+
+impl From<Register> for u16 {
+    fn from(reg: Register) -> Self {
+        reg.0
+    }
+}
+
+impl From<[u8; 2]> for Register {
+    /// Register from the bytes in bus order, see [`Register::from_be_bytes()`]
+    fn from(bytes: [u8; 2]) -> Self {
+        Self::from_be_bytes(bytes)
+    }
+}
+
+impl From<Register> for [u8; 2] {
+    /// Bytes in bus order, see [`Register::to_be_bytes()`]
+    fn from(reg: Register) -> Self {
+        reg.to_be_bytes()
+    }
+}
+
+impl fmt::LowerHex for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+
+impl fmt::UpperHex for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.0, f)
+    }
+}
@@ -0,0 +1,72 @@
+//! Bus sanity check for production test firmware
+//!
+//! [`LTC6904::self_test()`] writes the cached register and reports whether the device
+//! acknowledged it. No test pattern is written, the output only changes if the cached register
+//! differs from the configuration of the device: changes of the setters not written yet take
+//! effect, as does the configuration of a driver which hasn't written the device before. With the
+//! `readback` feature and a bus implementing `Read`, `LTC6904::self_test_read_back()` also
+//! compares the register read back from the device.
+
+#[cfg(feature = "readback")]
+use embedded_hal::blocking::i2c::Read;
+use embedded_hal::{blocking::i2c::Write, digital::v2::OutputPin};
+
+use crate::{Register, LTC6904};
+
+/// Outcome of [`LTC6904::self_test()`] and `LTC6904::self_test_read_back()`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfTestReport<E> {
+    /// register word written to the device
+    pub written: Register,
+    /// error of the write, `None` if the device acknowledged it
+    pub write_error: Option<E>,
+    /// register read back from the device, `None` if it wasn't read
+    pub read_back: Option<Result<Register, E>>,
+}
+
+impl<E> SelfTestReport<E> {
+    /// Whether the write succeeded and a register read back matches it
+    pub fn passed(&self) -> bool {
+        self.write_error.is_none()
+            && match &self.read_back {
+                Some(Ok(reg)) => *reg == self.written,
+                Some(Err(_)) => false,
+                None => true,
+            }
+    }
+}
+
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// Writes the cached register and reports whether the device acknowledged it
+    ///
+    /// Meant to be called once at power-on, after the driver has been configured. Pending changes
+    /// are written as well, see [`LTC6904::has_pending_changes()`].
+    pub fn self_test(&mut self) -> SelfTestReport<E> {
+        SelfTestReport {
+            written: self.reg,
+            write_error: self.write_out().err(),
+            read_back: None,
+        }
+    }
+}
+
+#[cfg(feature = "readback")]
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E> + Read<Error = E>,
+    PIN: OutputPin,
+{
+    /// Like [`LTC6904::self_test()`], additionally reading the register back if the write
+    /// succeeded
+    pub fn self_test_read_back(&mut self) -> SelfTestReport<E> {
+        let mut report = self.self_test();
+        if report.write_error.is_none() {
+            report.read_back = Some(self.read_back());
+        }
+        report
+    }
+}
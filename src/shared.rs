@@ -0,0 +1,61 @@
+//! Driver shared between tasks or interrupt handlers (requires the `critical-section` feature)
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use embedded_hal::{blocking::i2c::Write, digital::v2::OutputPin};
+
+use crate::LTC6904;
+
+/// [`LTC6904`] behind a critical section mutex
+///
+/// Can be placed in a `static` and used from any context, e.g. one task retuning the frequency
+/// while another one gates the output. Every access runs inside a critical section, which blocks
+/// interrupts for the duration of the bus transfer on single core targets.
+pub struct SharedLtc6904<I2C, PIN>
+where
+    I2C: Write,
+    PIN: OutputPin,
+{
+    inner: Mutex<RefCell<Option<LTC6904<I2C, PIN>>>>,
+}
+
+impl<I2C, E, PIN> SharedLtc6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// Creates an empty wrapper, to be filled with [`SharedLtc6904::init()`]
+    pub const fn new() -> Self {
+        Self {
+            inner: Mutex::new(RefCell::new(None)),
+        }
+    }
+
+    /// Moves the driver into the wrapper, returns the previous one
+    pub fn init(&self, ltc: LTC6904<I2C, PIN>) -> Option<LTC6904<I2C, PIN>> {
+        critical_section::with(|cs| self.inner.borrow(cs).replace(Some(ltc)))
+    }
+
+    /// Runs `f` on the driver inside a critical section
+    ///
+    /// Returns `None` if the wrapper hasn't been initialized yet.
+    pub fn lock<R>(&self, f: impl FnOnce(&mut LTC6904<I2C, PIN>) -> R) -> Option<R> {
+        critical_section::with(|cs| self.inner.borrow(cs).borrow_mut().as_mut().map(f))
+    }
+
+    /// Takes the driver back out of the wrapper
+    pub fn take(&self) -> Option<LTC6904<I2C, PIN>> {
+        critical_section::with(|cs| self.inner.borrow(cs).take())
+    }
+}
+
+impl<I2C, E, PIN> Default for SharedLtc6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,214 @@
+//! Host-side model of the LTC6904 (requires the `sim` feature)
+//!
+//! [`Ltc6904Sim`] behaves like the chip on the bus: it acknowledges two-byte writes to its
+//! strapped address, decodes the OCT, DAC and CNF fields and reports the resulting output
+//! frequency. The bus and OE handles returned by [`Ltc6904Sim::i2c()`] and
+//! [`Ltc6904Sim::oe_pin()`] share their state with the simulator, so they can be moved into
+//! the driver while the test keeps the simulator to inspect the device.
+//!
+//! ```
+//! use ltc690x::{sim::Ltc6904Sim, Address, OutputMode, LTC6904};
+//!
+//! let sim = Ltc6904Sim::new(Address::AddressLow);
+//! let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+//!
+//! ltc.set_frequency(108_000).unwrap();
+//! ltc.set_output_mode(OutputMode::BothOutputs);
+//! ltc.write_out().unwrap();
+//! ltc.enable_output().unwrap();
+//!
+//! assert_eq!(sim.register(), ltc.get_reg());
+//! assert!(sim.output_frequency().is_some());
+//! ```
+
+use std::{cell::RefCell, convert::Infallible, rc::Rc, vec::Vec};
+
+use crate::hal::{
+    blocking::i2c::{Read, Write, WriteRead},
+    digital::v2::{OutputPin, StatefulOutputPin},
+};
+use crate::{consts::BASE_FREQUENCY, Address, OutputMode, Register};
+
+/// Bus errors reported by the simulated device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SimError {
+    /// the transaction was addressed to a different device
+    Nack,
+    /// the device only accepts two-byte transfers
+    InvalidLength(usize),
+}
+
+#[derive(Debug, Default)]
+struct State {
+    reg: u16,
+    writes: Vec<u16>,
+    oe: bool,
+}
+
+/// Simulated LTC6904 strapped to one of its two addresses
+#[derive(Debug, Clone)]
+pub struct Ltc6904Sim {
+    addr: u8,
+    state: Rc<RefCell<State>>,
+}
+
+/// I2C handle to a [`Ltc6904Sim`], to be passed to the driver
+#[derive(Debug, Clone)]
+pub struct SimI2c {
+    sim: Ltc6904Sim,
+}
+
+/// OE pin handle to a [`Ltc6904Sim`], to be passed to the driver
+#[derive(Debug, Clone)]
+pub struct SimOutputEnable {
+    sim: Ltc6904Sim,
+}
+
+impl Ltc6904Sim {
+    /// Creates a device in its power-on state with the ADR pin strapped as `address`
+    pub fn new(address: Address) -> Self {
+        Self {
+            addr: address.into(),
+            state: Rc::new(RefCell::new(State::default())),
+        }
+    }
+
+    pub fn i2c(&self) -> SimI2c {
+        SimI2c { sim: self.clone() }
+    }
+
+    pub fn oe_pin(&self) -> SimOutputEnable {
+        SimOutputEnable { sim: self.clone() }
+    }
+
+    /// Current content of the device register
+    pub fn register(&self) -> u16 {
+        self.state.borrow().reg
+    }
+
+    /// Every register word written to the device, oldest first
+    pub fn writes(&self) -> Vec<u16> {
+        self.state.borrow().writes.clone()
+    }
+
+    pub fn oct(&self) -> u16 {
+        Register::new(self.register()).oct()
+    }
+
+    pub fn dac(&self) -> u16 {
+        Register::new(self.register()).dac()
+    }
+
+    pub fn cnf(&self) -> u16 {
+        Register::new(self.register()).cnf()
+    }
+
+    pub fn output_mode(&self) -> OutputMode {
+        Register::new(self.register()).output_mode()
+    }
+
+    pub fn is_output_enabled(&self) -> bool {
+        self.state.borrow().oe
+    }
+
+    pub fn is_powered_down(&self) -> bool {
+        self.cnf() == OutputMode::PowerDown as u16
+    }
+
+    /// Frequency the oscillator core runs at, rounded to the nearest Hz
+    ///
+    /// f = 2^OCT * 2078 Hz / (2 - DAC / 1024)
+    pub fn frequency(&self) -> u32 {
+        let num = (BASE_FREQUENCY as u64) << (10 + self.oct());
+        let den = 2048 - self.dac() as u64;
+        ((num + den / 2) / den) as u32
+    }
+
+    /// Frequency visible on the outputs, `None` if they are gated by OE or powered down
+    pub fn output_frequency(&self) -> Option<u32> {
+        if self.is_output_enabled() && !self.is_powered_down() {
+            Some(self.frequency())
+        } else {
+            None
+        }
+    }
+
+    fn write(&self, address: u8, bytes: &[u8]) -> Result<(), SimError> {
+        if address != self.addr {
+            return Err(SimError::Nack);
+        }
+        if bytes.len() != 2 {
+            return Err(SimError::InvalidLength(bytes.len()));
+        }
+        let reg = u16::from_be_bytes([bytes[0], bytes[1]]);
+        let mut state = self.state.borrow_mut();
+        state.reg = reg;
+        state.writes.push(reg);
+        Ok(())
+    }
+
+    fn read(&self, address: u8, buffer: &mut [u8]) -> Result<(), SimError> {
+        if address != self.addr {
+            return Err(SimError::Nack);
+        }
+        if buffer.len() != 2 {
+            return Err(SimError::InvalidLength(buffer.len()));
+        }
+        buffer.copy_from_slice(&self.register().to_be_bytes());
+        Ok(())
+    }
+}
+
+impl Write for SimI2c {
+    type Error = SimError;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.sim.write(address, bytes)
+    }
+}
+
+impl Read for SimI2c {
+    type Error = SimError;
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.sim.read(address, buffer)
+    }
+}
+
+impl WriteRead for SimI2c {
+    type Error = SimError;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.sim.write(address, bytes)?;
+        self.sim.read(address, buffer)
+    }
+}
+
+impl OutputPin for SimOutputEnable {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.sim.state.borrow_mut().oe = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.sim.state.borrow_mut().oe = true;
+        Ok(())
+    }
+}
+
+impl StatefulOutputPin for SimOutputEnable {
+    fn is_set_high(&self) -> Result<bool, Self::Error> {
+        Ok(self.sim.is_output_enabled())
+    }
+
+    fn is_set_low(&self) -> Result<bool, Self::Error> {
+        Ok(!self.sim.is_output_enabled())
+    }
+}
@@ -0,0 +1,165 @@
+//! Finding one frequency for several consumers of the same clock
+//!
+//! A clock shared by several peripherals, each dividing it down by an integer within its own
+//! limits, has to satisfy all of their frequency ranges at once. [`solve()`] searches the achievable
+//! frequencies on both sides of a preferred one and returns the closest that works for every
+//! [`Consumer`], or the first consumer that can't be satisfied together with the ones before it.
+//!
+//! ```
+//! use ltc690x::solver::{solve, Consumer};
+//!
+//! // a UART dividing by 1 to 16 for 3.6864 MHz +- 2 % and a codec taking 11.2 to 12.4 MHz
+//! let consumers = [
+//!     Consumer::range(3_612_672, 3_760_128).with_dividers(1, 16),
+//!     Consumer::range(11_200_000, 12_400_000),
+//! ];
+//! let solution = solve(&consumers, 11_059_200).unwrap();
+//! assert_eq!(solution.dividers, [3, 1]);
+//! ```
+
+use crate::{
+    achievable::{code_to_reg, reg_to_code, CODES},
+    calibration::exact,
+    freq_to_reg, reg_to_freq, ErrorCategory, FREQUENCY_MAX, FREQUENCY_MIN,
+};
+
+/// Frequency range a consumer accepts after its divider
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Consumer {
+    /// lowest accepted frequency in Hz after the divider
+    pub min_hz: u32,
+    /// highest accepted frequency in Hz after the divider
+    pub max_hz: u32,
+    /// smallest divider the consumer supports, at least 1
+    pub min_divider: u32,
+    /// largest divider the consumer supports
+    pub max_divider: u32,
+}
+
+impl Consumer {
+    /// Consumer taking the clock undivided within `min_hz` to `max_hz`
+    pub const fn range(min_hz: u32, max_hz: u32) -> Self {
+        Self {
+            min_hz,
+            max_hz,
+            min_divider: 1,
+            max_divider: 1,
+        }
+    }
+
+    /// Allows the consumer to divide the clock by `min` to `max`
+    pub const fn with_dividers(mut self, min: u32, max: u32) -> Self {
+        self.min_divider = min;
+        self.max_divider = max;
+        self
+    }
+
+    /// Smallest divider bringing the exact frequency `num / den` into range
+    fn divider(&self, num: i128, den: i128) -> Option<u32> {
+        if self.min_hz == 0 || self.min_hz > self.max_hz {
+            return None;
+        }
+        // max_hz * d >= f and min_hz * d <= f
+        let max = self.max_hz as i128 * den;
+        let lowest = (num + max - 1) / max;
+        let highest = num / (self.min_hz as i128 * den);
+        let lowest = lowest.max(self.min_divider.max(1) as i128);
+        let highest = highest.min(self.max_divider as i128);
+        if lowest <= highest {
+            Some(lowest as u32)
+        } else {
+            None
+        }
+    }
+}
+
+/// Frequency satisfying all consumers passed to [`solve()`]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Solution<const N: usize> {
+    /// register word with CNF = 0
+    pub reg: u16,
+    /// output frequency in Hz, rounded
+    pub frequency: u32,
+    /// divider for every consumer, in the order they were passed
+    pub dividers: [u32; N],
+}
+
+/// Reported by [`solve()`] if no achievable frequency satisfies all consumers
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+#[error("consumer {consumer} conflicts with the ones before it")]
+pub struct Conflict {
+    /// index of the first consumer which can't be satisfied together with the ones before it
+    pub consumer: usize,
+}
+
+impl Conflict {
+    /// Always [`ErrorCategory::Range`]
+    pub const fn category(&self) -> ErrorCategory {
+        ErrorCategory::Range
+    }
+}
+
+fn dividers<const N: usize>(consumers: &[Consumer; N], code: u16) -> Option<[u32; N]> {
+    let (num, den) = exact(code_to_reg(code));
+    let mut dividers = [0; N];
+    for (d, c) in dividers.iter_mut().zip(consumers) {
+        *d = c.divider(num, den)?;
+    }
+    Some(dividers)
+}
+
+/// Achievable frequency closest to `preferred` satisfying all `consumers`
+///
+/// Every code of the device is checked in the worst case, which takes a while on small cores.
+/// `preferred` outside of the range of the device starts the search from the closer end.
+pub fn solve<const N: usize>(
+    consumers: &[Consumer; N],
+    preferred: u32,
+) -> Result<Solution<N>, Conflict> {
+    let start =
+        reg_to_code(freq_to_reg(preferred.clamp(FREQUENCY_MIN, FREQUENCY_MAX)).unwrap_or(0));
+    let solution = |code: u16| dividers(consumers, code).map(|d| (code, d));
+    // frequencies ascend with the code, the first solutions found in either direction are the
+    // closest below and above
+    let below = (0..=start).rev().find_map(solution);
+    let above = (start + 1..CODES).find_map(solution);
+    let distance = |code: u16| {
+        let f = reg_to_freq(code_to_reg(code));
+        f.max(preferred) - f.min(preferred)
+    };
+    let best = match (below, above) {
+        (Some(b), Some(a)) if distance(a.0) < distance(b.0) => Some(a),
+        (Some(b), _) => Some(b),
+        (None, a) => a,
+    };
+    match best {
+        Some((code, dividers)) => {
+            let reg = code_to_reg(code);
+            Ok(Solution {
+                reg,
+                frequency: reg_to_freq(reg),
+                dividers,
+            })
+        }
+        None => Err(Conflict {
+            consumer: first_conflict(consumers),
+        }),
+    }
+}
+
+/// Index of the first consumer without a code satisfying it and all before it
+fn first_conflict(consumers: &[Consumer]) -> usize {
+    (0..consumers.len())
+        .find(|&n| {
+            !(0..CODES).any(|code| {
+                let (num, den) = exact(code_to_reg(code));
+                consumers[..=n]
+                    .iter()
+                    .all(|c| c.divider(num, den).is_some())
+            })
+        })
+        .unwrap_or(0)
+}
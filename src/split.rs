@@ -0,0 +1,169 @@
+//! Separating the frequency calculation from the bus access
+//!
+//! [`LTC6904::split()`] hands out a [`FrequencyPlanner`], which only does the math and can run in
+//! a low priority task, and a [`RegisterWriter`] owning the bus and the OE pin for a higher
+//! priority one. Both share a [`Staged`] register word, usually placed in a `static`.
+//!
+//! ```
+//! # #[cfg(feature = "sim")] {
+//! use ltc690x::{sim::Ltc6904Sim, split::Staged, Address, LTC6904};
+//!
+//! static STAGED: Staged = Staged::new();
+//!
+//! let sim = Ltc6904Sim::new(Address::AddressLow);
+//! let ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+//! let (mut planner, mut writer) = ltc.split(&STAGED);
+//!
+//! planner.set_frequency(108_000).unwrap();
+//! assert!(writer.commit().unwrap());
+//! assert_eq!(sim.register(), planner.reg());
+//! # }
+//! ```
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use embedded_hal::{blocking::i2c::Write, digital::v2::OutputPin};
+
+use crate::{calibration, chip, Frequency, FrequencyError, OutputMode, Register, LTC6904};
+
+/// Register word handed from a [`FrequencyPlanner`] to a [`RegisterWriter`]
+#[derive(Debug, Default)]
+pub struct Staged {
+    // register word in the lower 16 bits, PENDING if it hasn't been written yet
+    word: AtomicU32,
+}
+
+impl Staged {
+    const PENDING: u32 = 1 << 16;
+
+    pub const fn new() -> Self {
+        Self {
+            word: AtomicU32::new(0),
+        }
+    }
+
+    /// Whether a word is waiting for [`RegisterWriter::commit()`]
+    pub fn is_pending(&self) -> bool {
+        self.word.load(Ordering::Acquire) & Self::PENDING != 0
+    }
+
+    fn stage(&self, reg: u16) {
+        self.word
+            .store(Self::PENDING | reg as u32, Ordering::Release);
+    }
+
+    fn take(&self) -> Option<u16> {
+        let word = self.word.fetch_and(!Self::PENDING, Ordering::AcqRel);
+        if word & Self::PENDING != 0 {
+            Some(word as u16)
+        } else {
+            None
+        }
+    }
+}
+
+/// Calculating half of a split driver, see [`LTC6904::split()`]
+///
+/// Every change is staged for the [`RegisterWriter`] right away, a newer one replaces a word that
+/// hasn't been written yet.
+#[derive(Debug)]
+pub struct FrequencyPlanner<'a> {
+    staged: &'a Staged,
+    reg: u16,
+    frequ: Frequency,
+    calibration_ppm: i32,
+    max_error_ppm: Option<u32>,
+}
+
+impl<'a> FrequencyPlanner<'a> {
+    /// Stages the frequency, taking calibration and error limit of the driver into account
+    pub fn set_frequency(&mut self, f: u32) -> Result<u16, FrequencyError> {
+        let f = f.into();
+        let reg =
+            calibration::checked_reg::<chip::Ltc6904>(f, self.calibration_ppm, self.max_error_ppm)?;
+        self.frequ = f;
+        self.reg = Register::new(reg)
+            .with_cnf(Register::new(self.reg).cnf())
+            .bits();
+        self.staged.stage(self.reg);
+        Ok(self.reg)
+    }
+
+    /// Stages the output mode
+    pub fn set_output_mode(&mut self, mode: OutputMode) {
+        self.reg = Register::new(self.reg).with_output_mode(mode).bits();
+        self.staged.stage(self.reg);
+    }
+
+    /// Last staged register word
+    pub fn reg(&self) -> u16 {
+        self.reg
+    }
+
+    /// Frequency last requested with [`FrequencyPlanner::set_frequency()`]
+    pub fn requested_frequency(&self) -> u32 {
+        self.frequ.hz()
+    }
+}
+
+/// Bus half of a split driver, see [`LTC6904::split()`]
+pub struct RegisterWriter<'a, I2C, PIN>
+where
+    I2C: Write,
+    PIN: OutputPin,
+{
+    ltc: LTC6904<I2C, PIN>,
+    staged: &'a Staged,
+}
+
+impl<'a, I2C, E, PIN> RegisterWriter<'a, I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// Writes the staged word if there is one, returns whether the device was written
+    pub fn commit(&mut self) -> Result<bool, E> {
+        match self.staged.take() {
+            Some(reg) => self.ltc.write_reg(reg).map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    pub fn enable_output(&mut self) -> Result<(), PIN::Error> {
+        self.ltc.enable_output()
+    }
+
+    pub fn disable_output(&mut self) -> Result<(), PIN::Error> {
+        self.ltc.disable_output()
+    }
+
+    /// Joins both halves again
+    ///
+    /// A word still staged is dropped, call [`RegisterWriter::commit()`] first to keep it.
+    pub fn join(self, planner: FrequencyPlanner<'a>) -> LTC6904<I2C, PIN> {
+        let mut ltc = self.ltc;
+        ltc.reg = planner.reg.into();
+        ltc.frequ = planner.frequ;
+        ltc
+    }
+}
+
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// Splits the driver into a [`FrequencyPlanner`] and a [`RegisterWriter`] communicating
+    /// through `staged`
+    pub fn split(self, staged: &Staged) -> (FrequencyPlanner<'_>, RegisterWriter<'_, I2C, PIN>) {
+        staged.take();
+        let planner = FrequencyPlanner {
+            staged,
+            reg: self.reg.bits(),
+            frequ: self.frequ,
+            calibration_ppm: self.calibration_ppm,
+            max_error_ppm: self.max_error_ppm,
+        };
+        (planner, RegisterWriter { ltc: self, staged })
+    }
+}
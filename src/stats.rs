@@ -0,0 +1,58 @@
+//! Counters for reporting the health of the oscillator bus
+
+use embedded_hal::{blocking::i2c::Write, digital::v2::OutputPin};
+
+use crate::{Register, LTC6904};
+
+/// Counts of the writes issued by the driver, returned by [`LTC6904::stats()`]
+///
+/// All counters wrap on overflow.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Stats {
+    /// writes the device acknowledged
+    pub writes: u32,
+    /// writes the bus reported an error for
+    pub bus_errors: u32,
+    /// writes repeating a register word whose previous write failed
+    pub retries: u32,
+    /// successful writes changing OCT or DAC on the device
+    pub frequency_changes: u32,
+}
+
+impl Stats {
+    pub(crate) fn count(
+        &mut self,
+        reg: Register,
+        ok: bool,
+        failed: Option<Register>,
+        device: Option<Register>,
+    ) {
+        if failed == Some(reg) {
+            self.retries = self.retries.wrapping_add(1);
+        }
+        if !ok {
+            self.bus_errors = self.bus_errors.wrapping_add(1);
+            return;
+        }
+        self.writes = self.writes.wrapping_add(1);
+        if device.map(|d| (d.oct(), d.dac())) != Some((reg.oct(), reg.dac())) {
+            self.frequency_changes = self.frequency_changes.wrapping_add(1);
+        }
+    }
+}
+
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// Counters since the driver was created or [`LTC6904::reset_stats()`]
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = Stats::default();
+    }
+}
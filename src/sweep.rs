@@ -0,0 +1,191 @@
+//! Frequency sweeps as iterators of ready-to-write register words
+//!
+//! [`sweep()`] steps linearly, [`log_sweep()`] with a fixed number of points per octave or decade.
+//! Both only use integer math, so they are cheap on cores without an FPU.
+//!
+//! The words can be collected into a buffer up front and written with
+//! [`LTC6904::write_reg()`](crate::LTC6904::write_reg), which keeps the frequency math out of
+//! timing-critical loops.
+
+use crate::{freq_to_reg, FrequencyError, OutputMode};
+
+/// Linear sweep created by [`sweep()`]
+#[derive(Debug, Clone)]
+pub struct Sweep {
+    next: Option<u32>,
+    stop: u32,
+    step: u32,
+    cnf: u16,
+}
+
+/// Sweeps from `start` to `stop` (inclusive, in either direction) in `step` Hz increments
+///
+/// Yields the register word for every point, with the output configuration set to
+/// [`OutputMode::BothOutputs`] unless changed with [`Sweep::with_output()`].
+/// If `stop` isn't reached exactly by the step size, the sweep ends at the last point before it.
+/// A step of 0 yields `start` only.
+pub fn sweep(start: u32, stop: u32, step: u32) -> Result<Sweep, FrequencyError> {
+    freq_to_reg(start)?;
+    freq_to_reg(stop)?;
+    Ok(Sweep {
+        next: Some(start),
+        stop,
+        step,
+        cnf: OutputMode::BothOutputs.into(),
+    })
+}
+
+impl Sweep {
+    /// Sets the output configuration included in every yielded register word
+    pub fn with_output(mut self, output: OutputMode) -> Self {
+        self.cnf = output.into();
+        self
+    }
+
+    fn advance(&self, f: u32) -> Option<u32> {
+        if self.step == 0 {
+            None
+        } else if f < self.stop {
+            f.checked_add(self.step).filter(|n| *n <= self.stop)
+        } else {
+            f.checked_sub(self.step).filter(|n| *n >= self.stop)
+        }
+    }
+}
+
+impl Iterator for Sweep {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        let f = self.next?;
+        self.next = self.advance(f);
+        // every point lies between the validated end points
+        freq_to_reg(f).ok().map(|reg| reg | self.cnf)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match self.next {
+            None => 0,
+            Some(_) if self.step == 0 => 1,
+            Some(f) => (f.max(self.stop) - f.min(self.stop)) as usize / self.step as usize + 1,
+        };
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Sweep {}
+
+/// Point spacing of a [`log_sweep()`]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogSpacing {
+    PerOctave(u32),
+    PerDecade(u32),
+}
+
+/// Logarithmic sweep created by [`log_sweep()`]
+#[derive(Debug, Clone)]
+pub struct LogSweep {
+    /// next frequency in Q32.32 fixed point
+    next: Option<u64>,
+    stop: u32,
+    /// ratio between consecutive points in Q32.32 fixed point
+    ratio: u64,
+    ascending: bool,
+    cnf: u16,
+}
+
+const FRAC: u32 = 32;
+
+/// Sweeps from `start` to `stop` (inclusive, in either direction) with logarithmic spacing
+///
+/// The ratio between points is calculated once up front, every further point costs a single
+/// fixed point multiplication. A spacing of 0 points yields `start` only.
+pub fn log_sweep(start: u32, stop: u32, spacing: LogSpacing) -> Result<LogSweep, FrequencyError> {
+    freq_to_reg(start)?;
+    freq_to_reg(stop)?;
+    let ratio = match spacing {
+        LogSpacing::PerOctave(n) => root(2, n),
+        LogSpacing::PerDecade(n) => root(10, n),
+    };
+    let ascending = stop >= start;
+    Ok(LogSweep {
+        next: Some((start as u64) << FRAC),
+        stop,
+        ratio: if ascending {
+            ratio
+        } else {
+            ((1u128 << (2 * FRAC)) / ratio as u128) as u64
+        },
+        ascending,
+        cnf: OutputMode::BothOutputs.into(),
+    })
+}
+
+/// `x^n` for Q32.32 fixed point `x`, saturating
+fn pow(x: u64, n: u32) -> u64 {
+    let mut acc: u128 = 1 << FRAC;
+    for _ in 0..n {
+        acc = (acc * x as u128) >> FRAC;
+        if acc > u64::MAX as u128 {
+            return u64::MAX;
+        }
+    }
+    acc as u64
+}
+
+/// `base^(1/n)` in Q32.32 fixed point, found by bisection, 1.0 for n = 0
+fn root(base: u64, n: u32) -> u64 {
+    let target = base << FRAC;
+    let (mut lo, mut hi) = (1u64 << FRAC, target);
+    if n == 0 {
+        return lo;
+    }
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if pow(mid, n) <= target {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+fn round(q: u64) -> u32 {
+    ((q + (1 << (FRAC - 1))) >> FRAC) as u32
+}
+
+impl LogSweep {
+    /// Sets the output configuration included in every yielded register word
+    pub fn with_output(mut self, output: OutputMode) -> Self {
+        self.cnf = output.into();
+        self
+    }
+
+    fn advance(&self, q: u64) -> Option<u64> {
+        let next = ((q as u128 * self.ratio as u128) >> FRAC) as u64;
+        let f = round(next);
+        let done = if self.ascending {
+            next <= q || f > self.stop
+        } else {
+            next >= q || f < self.stop
+        };
+        if done {
+            None
+        } else {
+            Some(next)
+        }
+    }
+}
+
+impl Iterator for LogSweep {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        let q = self.next?;
+        self.next = self.advance(q);
+        // every point lies between the validated end points
+        freq_to_reg(round(q)).ok().map(|reg| reg | self.cnf)
+    }
+}
@@ -0,0 +1,115 @@
+//! Fault injection for robustness tests (requires the `test-hooks` feature)
+//!
+//! [`FaultyI2c`] wraps a bus, e.g. a [`sim::SimI2c`](crate::sim::SimI2c) or a mock, and fails
+//! chosen transactions with an error instead of passing them on. Counting starts at 1 with the
+//! first transaction, reads and writes both count.
+//!
+//! ```
+//! # #[cfg(feature = "sim")] {
+//! use ltc690x::{
+//!     sim::{Ltc6904Sim, SimError},
+//!     test_hooks::FaultyI2c,
+//!     Address, LTC6904,
+//! };
+//!
+//! let sim = Ltc6904Sim::new(Address::AddressLow);
+//! let i2c = FaultyI2c::new(sim.i2c()).fail_at(2, SimError::Nack);
+//! let mut ltc = LTC6904::new(i2c, Address::AddressLow, sim.oe_pin());
+//!
+//! ltc.set_frequency(1_000_000).unwrap();
+//! assert_eq!(ltc.write_out(), Ok(()));
+//! assert_eq!(ltc.write_out(), Err(SimError::Nack));
+//! assert_eq!(sim.writes().len(), 1);
+//! # }
+//! ```
+
+use embedded_hal::blocking::i2c::{Read, Write, WriteRead};
+
+/// Number of single faults a [`FaultyI2c`] can hold
+pub const MAX_FAULTS: usize = 8;
+
+/// Bus failing chosen transactions with the error `E`
+#[derive(Debug, Clone)]
+pub struct FaultyI2c<I2C, E> {
+    i2c: I2C,
+    calls: u32,
+    faults: [Option<(u32, E)>; MAX_FAULTS],
+    every: Option<(u32, E)>,
+}
+
+impl<I2C, E: Clone> FaultyI2c<I2C, E> {
+    pub fn new(i2c: I2C) -> Self {
+        Self {
+            i2c,
+            calls: 0,
+            faults: Default::default(),
+            every: None,
+        }
+    }
+
+    /// Fails transaction number `call` with `error`
+    ///
+    /// Faults beyond [`MAX_FAULTS`] are ignored.
+    pub fn fail_at(mut self, call: u32, error: E) -> Self {
+        if let Some(slot) = self.faults.iter_mut().find(|f| f.is_none()) {
+            *slot = Some((call, error));
+        }
+        self
+    }
+
+    /// Fails every `n`-th transaction with `error`, 0 disables it
+    pub fn fail_every(mut self, n: u32, error: E) -> Self {
+        self.every = if n == 0 { None } else { Some((n, error)) };
+        self
+    }
+
+    /// Number of transactions so far, including the failed ones
+    pub fn calls(&self) -> u32 {
+        self.calls
+    }
+
+    /// Returns the wrapped bus
+    pub fn into_inner(self) -> I2C {
+        self.i2c
+    }
+
+    /// Counts a transaction, returns the error to inject for it
+    fn next_call(&mut self) -> Result<(), E> {
+        self.calls = self.calls.wrapping_add(1);
+        let calls = self.calls;
+        if let Some((_, e)) = self.faults.iter().flatten().find(|(c, _)| *c == calls) {
+            return Err(e.clone());
+        }
+        match &self.every {
+            Some((n, e)) if calls.is_multiple_of(*n) => Err(e.clone()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<I2C: Write<Error = E>, E: Clone> Write for FaultyI2c<I2C, E> {
+    type Error = E;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), E> {
+        self.next_call()?;
+        self.i2c.write(address, bytes)
+    }
+}
+
+impl<I2C: Read<Error = E>, E: Clone> Read for FaultyI2c<I2C, E> {
+    type Error = E;
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), E> {
+        self.next_call()?;
+        self.i2c.read(address, buffer)
+    }
+}
+
+impl<I2C: WriteRead<Error = E>, E: Clone> WriteRead for FaultyI2c<I2C, E> {
+    type Error = E;
+
+    fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), E> {
+        self.next_call()?;
+        self.i2c.write_read(address, bytes, buffer)
+    }
+}
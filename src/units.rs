@@ -0,0 +1,72 @@
+//! `uom` quantities for the frequency APIs (requires the `uom` feature)
+//!
+//! [`Frequency`] converts from and to `uom::si::f64::Frequency`, and the driver takes and
+//! reports frequencies as quantities with [`LTC6904::set_frequency_uom()`] and friends, for
+//! projects using `uom` throughout.
+//!
+//! ```
+//! # #[cfg(feature = "sim")] {
+//! use ltc690x::{sim::Ltc6904Sim, Address, LTC6904};
+//! use uom::si::{f64::Frequency, frequency::kilohertz};
+//!
+//! let sim = Ltc6904Sim::new(Address::AddressLow);
+//! let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+//! ltc.set_frequency_uom(Frequency::new::<kilohertz>(108.0)).unwrap();
+//! assert_eq!(ltc.requested_frequency(), 108_000);
+//! # }
+//! ```
+
+use core::convert::TryFrom;
+
+use uom::si::{f64, frequency::hertz};
+
+#[cfg(feature = "driver")]
+use embedded_hal::{blocking::i2c::Write, digital::v2::OutputPin};
+
+#[cfg(feature = "driver")]
+use crate::LTC6904;
+use crate::{Frequency, FrequencyError};
+
+impl From<Frequency> for f64::Frequency {
+    fn from(f: Frequency) -> Self {
+        f64::Frequency::new::<hertz>(f.millihertz() as f64 / 1_000.0)
+    }
+}
+
+impl TryFrom<f64::Frequency> for Frequency {
+    type Error = FrequencyError;
+
+    /// Rounds to millihertz, negative and NaN quantities are [`FrequencyError::TooLow`]
+    fn try_from(f: f64::Frequency) -> Result<Self, FrequencyError> {
+        let millihertz = f.get::<hertz>() * 1_000.0;
+        if millihertz.is_nan() || millihertz < 0.0 {
+            Err(FrequencyError::TooLow)
+        } else if millihertz >= u64::MAX as f64 {
+            Err(FrequencyError::TooHigh)
+        } else {
+            Ok(Frequency::from_millihertz((millihertz + 0.5) as u64))
+        }
+    }
+}
+
+#[cfg(feature = "driver")]
+impl<I2C, E, PIN> LTC6904<I2C, PIN>
+where
+    I2C: Write<Error = E>,
+    PIN: OutputPin,
+{
+    /// [`LTC6904::set_frequency_precise()`] for a `uom` quantity
+    pub fn set_frequency_uom(&mut self, f: f64::Frequency) -> Result<u16, FrequencyError> {
+        self.set_frequency_precise(Frequency::try_from(f)?)
+    }
+
+    /// [`LTC6904::requested_frequency_precise()`] as a `uom` quantity
+    pub fn requested_frequency_uom(&self) -> f64::Frequency {
+        self.requested_frequency_precise().into()
+    }
+
+    /// [`LTC6904::programmed_frequency()`] as a `uom` quantity
+    pub fn programmed_frequency_uom(&self) -> f64::Frequency {
+        Frequency::from_hertz(self.programmed_frequency()).into()
+    }
+}
@@ -0,0 +1,50 @@
+use ltc690x::{accuracy::accuracy_bounds, error_ppm, freq_to_reg, reg_to_freq, FrequencyError};
+
+#[test]
+fn bounds_combine_quantization_and_tolerance() {
+    let bounds = accuracy_bounds(1_000_000).unwrap();
+    assert_eq!(bounds.nominal, reg_to_freq(freq_to_reg(1_000_000).unwrap()));
+    assert_eq!(bounds.quantization_ppm, error_ppm(1_000_000).unwrap());
+    assert_eq!(bounds.tolerance_ppm, 11_000);
+    let spread = (bounds.nominal as u64 * 11_000).div_ceil(1_000_000) as u32;
+    assert_eq!(bounds.min, bounds.nominal - spread);
+    assert_eq!(bounds.max, bounds.nominal + spread);
+    assert_eq!(
+        bounds.worst_error_ppm(),
+        11_000 + bounds.quantization_ppm.unsigned_abs()
+    );
+    assert_eq!(bounds.best_error_ppm(), 0);
+    assert!(bounds.within(20_000));
+    assert!(!bounds.within(10_000));
+
+    // the top octaves are specified looser
+    assert_eq!(accuracy_bounds(50_000_000).unwrap().tolerance_ppm, 15_000);
+    assert_eq!(accuracy_bounds(100), Err(FrequencyError::TooLow));
+}
+
+#[test]
+fn bounds_contain_the_nominal_frequency_everywhere() {
+    for f in (1_039..68_000_000).step_by(99_991) {
+        let bounds = accuracy_bounds(f).unwrap();
+        assert!(bounds.min < bounds.nominal && bounds.nominal < bounds.max);
+        let spread = bounds.nominal as u64 * bounds.tolerance_ppm as u64 / 1_000_000;
+        assert!((bounds.max - bounds.nominal) as u64 >= spread);
+        assert!((bounds.nominal - bounds.min) as u64 >= spread);
+    }
+}
+
+#[cfg(feature = "sim")]
+#[test]
+fn driver_bounds_follow_the_calibration() {
+    use ltc690x::{sim::Ltc6904Sim, Address, LTC6904};
+
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    ltc.set_frequency(1_000_000).unwrap();
+    assert_eq!(ltc.output_bounds(), accuracy_bounds(1_000_000).unwrap());
+
+    ltc.set_calibration_ppm(1_000).unwrap();
+    let bounds = ltc.output_bounds();
+    assert_eq!(bounds.nominal, ltc.programmed_frequency());
+    assert_eq!(bounds.tolerance_ppm, 11_000);
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "sim")]
+
+use ltc690x::{
+    baud::{baud_clock, baud_error_ppm},
+    freq_to_reg, reg_to_freq,
+    sim::Ltc6904Sim,
+    Address, FrequencyError, OutputMode, LTC6904,
+};
+
+#[test]
+fn clock_is_baud_times_oversampling() {
+    assert_eq!(baud_clock(115_200, 16), 1_843_200);
+    assert_eq!(baud_clock(9_600, 8), 76_800);
+    assert_eq!(baud_clock(u32::MAX, 16), u32::MAX);
+}
+
+#[test]
+fn error_of_an_inexact_clock() {
+    let f = baud_clock(115_200, 16);
+    let achieved = reg_to_freq(freq_to_reg(f).unwrap());
+    assert_ne!(achieved, f);
+
+    let expected = ((achieved as f64 - f as f64) * 1e6 / f as f64).round() as i32;
+    let error = baud_error_ppm(115_200, 16).unwrap();
+    assert_ne!(error, 0);
+    assert_eq!(error, expected);
+    // well within what a UART tolerates
+    assert!(error.abs() < 20_000);
+}
+
+#[test]
+fn set_baud_clock_writes_the_register() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+
+    let error = ltc.set_baud_clock(115_200, 16).unwrap();
+    assert_eq!(error, baud_error_ppm(115_200, 16).unwrap());
+    ltc.write_out().unwrap();
+    assert_eq!(
+        sim.writes(),
+        [freq_to_reg(1_843_200).unwrap() | OutputMode::BothOutputs as u16]
+    );
+}
+
+#[test]
+fn out_of_range_clocks_are_rejected() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+
+    assert_eq!(baud_error_ppm(50, 16), Err(FrequencyError::TooLow));
+    assert_eq!(baud_error_ppm(115_200, 1_000), Err(FrequencyError::TooHigh));
+    assert_eq!(ltc.set_baud_clock(115_200, 0), Err(FrequencyError::TooLow));
+    assert_eq!(
+        ltc.set_baud_clock(5_000_000, 16),
+        Err(FrequencyError::TooHigh)
+    );
+    assert!(sim.writes().is_empty());
+}
@@ -0,0 +1,37 @@
+#![cfg(all(feature = "std", feature = "parse"))]
+
+use std::process::Command;
+
+use ltc690x::{freq_to_reg, plan_frequency};
+
+fn calc(args: &[&str]) -> (bool, String) {
+    let out = Command::new(env!("CARGO_BIN_EXE_ltc690x-calc"))
+        .args(args)
+        .output()
+        .unwrap();
+    (out.status.success(), String::from_utf8(out.stdout).unwrap())
+}
+
+#[test]
+fn calculator_prints_the_driver_plan() {
+    let (ok, out) = calc(&["--mode", "clk", "108kHz"]);
+    assert!(ok);
+    let plan = plan_frequency(108_000).unwrap();
+    let reg = freq_to_reg(108_000).unwrap() | 0b10;
+    assert!(out.contains(&format!("OCT       {}\n", plan.oct)));
+    assert!(out.contains(&format!("DAC       {}\n", plan.dac)));
+    assert!(out.contains(&format!("register  {:#06x} (CNF 10, clk)\n", reg)));
+    assert!(out.contains(&format!("({} Hz)\n", plan.achieved)));
+    assert!(out.contains(&format!("error     {:+} ppm\n", plan.error_ppm)));
+}
+
+#[test]
+fn calculator_fails_on_bad_input() {
+    assert!(!calc(&[]).0);
+    assert!(!calc(&["70MHz"]).0);
+    assert!(!calc(&["--mode", "loud", "1MHz"]).0);
+    // the valid frequencies are printed nonetheless
+    let (ok, out) = calc(&["1MHz", "fast"]);
+    assert!(!ok);
+    assert!(out.contains("target    1.000 MHz"));
+}
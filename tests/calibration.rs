@@ -0,0 +1,167 @@
+#![cfg(feature = "sim")]
+
+use ltc690x::{sim::Ltc6904Sim, Address, FrequencyError, LTC6904};
+
+#[test]
+fn calibration_corrects_fast_part() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+
+    let uncalibrated = ltc.set_frequency(1_000_000).unwrap();
+    ltc.write_out().unwrap();
+    // the part runs 1000 ppm fast
+    let measured = (sim.frequency() as u64 * 1_001 / 1_000) as u32;
+    let ppm = ltc.calibrate(1_000_000, measured).unwrap();
+    assert!((ppm - 1_000).abs() <= 1, "{}", ppm);
+    assert_eq!(ltc.calibration_ppm(), ppm);
+
+    let calibrated = ltc.set_frequency(1_000_000).unwrap();
+    assert!(calibrated < uncalibrated);
+    let error = ltc.frequency_error_ppm(1_000_000).unwrap();
+    assert!(error.abs() < 600, "{}", error);
+    let programmed = ltc.programmed_frequency() as i64;
+    assert!((programmed - 1_000_000).abs() < 600);
+}
+
+#[test]
+fn calibrating_twice_replaces_the_correction() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+
+    ltc.set_frequency(5_000).unwrap();
+    ltc.write_out().unwrap();
+    let measured = (sim.frequency() as u64 * 998 / 1_000) as u32;
+    let first = ltc.calibrate(5_000, measured).unwrap();
+    assert!(first < 0);
+
+    // measuring the already corrected output again keeps the correction
+    ltc.set_frequency(20_000_000).unwrap();
+    let corrected = ltc.programmed_frequency();
+    let second = ltc.calibrate(20_000_000, corrected).unwrap();
+    assert!((second - first).abs() <= 1, "{} {}", first, second);
+}
+
+#[test]
+fn implausible_calibration_is_rejected() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+
+    assert!(matches!(
+        ltc.calibrate(1_000_000, 2_000_000),
+        Err(FrequencyError::CalibrationOutOfRange)
+    ));
+    assert!(matches!(
+        ltc.calibrate(1_000_000, 0),
+        Err(FrequencyError::CalibrationOutOfRange)
+    ));
+    assert!(ltc.set_calibration_ppm(-60_000).is_err());
+    assert_eq!(ltc.calibration_ppm(), 0);
+    ltc.set_calibration_ppm(-1_200).unwrap();
+    assert_eq!(ltc.calibration_ppm(), -1_200);
+}
+
+#[test]
+fn trim_loop_converges() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    ltc.set_frequency(12_288_000).unwrap();
+    ltc.write_out().unwrap();
+
+    // the part runs 0.4 % slow
+    let measure = |sim: &Ltc6904Sim| (sim.frequency() as u64 * 996 / 1_000) as u32;
+    let first = ltc.trim_step(measure(&sim)).unwrap();
+    assert!(first > 0);
+    assert_eq!(sim.register(), ltc.get_reg());
+    assert_eq!(ltc.trim_step(measure(&sim)).unwrap(), 0);
+    let error = (measure(&sim) as i64 - 12_288_000).abs();
+    assert!(error <= ltc690x::resolution_at(12_288_000).unwrap() as i64);
+    assert_eq!(sim.writes().len(), 2);
+}
+
+#[test]
+fn plan_includes_the_calibration() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    ltc.set_calibration_ppm(-2_000).unwrap();
+
+    let plan = ltc.plan_frequency(1_000_000).unwrap();
+    assert_ne!(plan.reg(), ltc690x::freq_to_reg(1_000_000).unwrap());
+    assert_eq!(plan.error_ppm, ltc.frequency_error_ppm(1_000_000).unwrap());
+    // planning leaves the configuration alone
+    assert_eq!(ltc.requested_frequency(), 1_039);
+
+    assert_eq!(ltc.set_frequency(1_000_000).unwrap(), plan.reg());
+    assert_eq!(ltc.programmed_frequency(), plan.achieved);
+}
+
+#[test]
+fn calibrated_math_never_panics() {
+    use ltc690x::calibration::MAX_CALIBRATION_PPM;
+
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    let edges = [
+        0,
+        1,
+        1_038,
+        1_039,
+        68_030_000,
+        68_030_001,
+        u32::MAX / 2,
+        u32::MAX,
+    ];
+    for ppm in [-MAX_CALIBRATION_PPM, 0, MAX_CALIBRATION_PPM] {
+        ltc.set_calibration_ppm(ppm).unwrap();
+        for f in edges {
+            let _ = ltc.frequency_error_ppm(f);
+            let _ = ltc.plan_frequency(f);
+            let _ = ltc.dither(f);
+            let _ = ltc.set_frequency(f);
+            let _ = ltc.calibrate(f, f);
+            ltc.set_calibration_ppm(ppm).unwrap();
+            let _ = ltc.calibrate(1_000_000, f);
+            ltc.set_calibration_ppm(ppm).unwrap();
+        }
+    }
+}
+
+#[test]
+fn compensation_biases_against_the_drift() {
+    use ltc690x::calibration::drift_ppm;
+
+    assert_eq!(drift_ppm(25), 0);
+    assert_eq!(drift_ppm(85), 600);
+    assert_eq!(drift_ppm(-40), -650);
+
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    let nominal = ltc.set_frequency(1_000_000).unwrap();
+    assert_eq!(ltc.set_frequency_compensated(1_000_000, 25), Ok(nominal));
+
+    // a hot part runs fast, so a lower frequency is programmed, as for a fast calibration
+    let hot = ltc.set_frequency_compensated(1_000_000, 85).unwrap();
+    assert!(hot < nominal);
+    ltc.set_calibration_ppm(600).unwrap();
+    assert_eq!(ltc.set_frequency(1_000_000), Ok(hot));
+    assert_eq!(ltc.requested_frequency(), 1_000_000);
+
+    assert_eq!(
+        ltc.set_frequency_compensated(1_000_000, i16::MAX),
+        Err(FrequencyError::CalibrationOutOfRange)
+    );
+    assert_eq!(ltc.get_reg(), hot);
+}
+
+#[test]
+fn calibrated_part_reaches_beyond_the_nominal_range() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+
+    // a part running 1000 ppm fast is programmed below 68.05 MHz for it
+    ltc.set_calibration_ppm(1_000).unwrap();
+    assert!(ltc.set_frequency(68_050_000).is_ok());
+    // within half a DAC step at the top of the range
+    let error = ltc.frequency_error_ppm(68_050_000).unwrap();
+    assert!(error.abs() < 500, "{}", error);
+    assert_eq!(ltc.set_frequency(68_200_000), Err(FrequencyError::TooHigh));
+}
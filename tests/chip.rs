@@ -0,0 +1,43 @@
+use ltc690x::{
+    chip::{AddressScheme, Chip, Ltc6903, Ltc6904},
+    freq_to_reg, Frequency, FrequencyError, FREQUENCY_MAX, FREQUENCY_MIN,
+};
+
+/// hypothetical member of the family specified for the lower octaves only
+struct LowBand;
+
+impl Chip for LowBand {
+    const NAME: &'static str = "low band";
+    const FREQUENCY_MIN: u32 = FREQUENCY_MIN;
+    const FREQUENCY_MAX: u32 = 1_000_000;
+    const SETTLING_US: u32 = 100;
+    const ADDRESS_SCHEME: AddressScheme = AddressScheme::ChipSelect;
+}
+
+#[test]
+fn family_members_share_the_range() {
+    assert_eq!(Ltc6903::NAME, "LTC6903");
+    assert_eq!(Ltc6904::NAME, "LTC6904");
+    for f in [FREQUENCY_MIN, 12_288_000, FREQUENCY_MAX] {
+        assert_eq!(Ltc6903::freq_to_reg(f), freq_to_reg(f));
+        assert_eq!(Ltc6904::freq_to_reg(f), freq_to_reg(f));
+    }
+    assert_eq!(
+        Ltc6904::check_frequency(Frequency::from_millihertz(FREQUENCY_MIN as u64 * 1_000 - 1)),
+        Err(FrequencyError::TooLow)
+    );
+    assert_eq!(
+        Ltc6903::freq_to_reg(FREQUENCY_MAX + 1),
+        Err(FrequencyError::TooHigh)
+    );
+}
+
+#[test]
+fn narrower_parts_only_need_their_constants() {
+    assert!(LowBand::freq_to_reg(1_000_000).is_ok());
+    assert_eq!(
+        LowBand::freq_to_reg(1_000_001),
+        Err(FrequencyError::TooHigh)
+    );
+    assert!(freq_to_reg(1_000_001).is_ok());
+}
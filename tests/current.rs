@@ -0,0 +1,34 @@
+use ltc690x::{
+    current::{load_current, supply_current, MicroAmps},
+    freq_to_reg, OutputMode, Register,
+};
+
+fn reg(f: u32, mode: OutputMode) -> Register {
+    Register::new(freq_to_reg(f).unwrap()).with_output_mode(mode)
+}
+
+#[test]
+fn current_grows_with_frequency_and_outputs() {
+    let slow = supply_current(reg(1_039, OutputMode::BothOutputs));
+    let fast = supply_current(reg(68_000_000, OutputMode::BothOutputs));
+    let one = supply_current(reg(68_000_000, OutputMode::ClkOnly));
+    assert!(slow < one && one < fast);
+    assert_eq!(one, supply_current(reg(68_000_000, OutputMode::ClkInvOnly)));
+    assert_eq!(fast.0, 1_700 + 2 * 17 * 68);
+}
+
+#[test]
+fn power_down_draws_almost_nothing() {
+    let down = supply_current(reg(68_000_000, OutputMode::PowerDown));
+    assert!(down < MicroAmps(10));
+}
+
+#[test]
+fn load_current_is_c_v_f() {
+    // 10 pF at 3.3 V and 10 MHz
+    assert_eq!(load_current(10_000_000, 10, 3_300), MicroAmps(330));
+    assert_eq!(
+        supply_current(reg(1_039, OutputMode::ClkOnly)) + MicroAmps(u32::MAX),
+        MicroAmps(u32::MAX)
+    );
+}
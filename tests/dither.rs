@@ -0,0 +1,45 @@
+//! Dithering between adjacent DAC codes
+
+use ltc690x::{dither::DUTY_SCALE, error_ppm, freq_to_reg, reg_to_freq, Dither};
+
+/// exact output frequency of `reg`
+fn exact(reg: u16) -> f64 {
+    let oct = reg >> 12;
+    let dac = ((reg >> 2) & 0x3ff) as f64;
+    2078.0 * (1u32 << oct) as f64 / (2.0 - dac / 1024.0)
+}
+
+#[test]
+fn average_frequency_beats_the_resolution() {
+    for f in [1_500, 108_003, 1_000_100, 12_288_000, 34_030_000] {
+        let dither = Dither::new(f).unwrap();
+        let (lo, hi) = dither.codes();
+        assert!(exact(lo) <= f as f64 && f as f64 <= exact(hi), "f={}", f);
+
+        let n = DUTY_SCALE as usize;
+        let regs: Vec<_> = dither.clone().take(n).collect();
+        let upper = regs.iter().filter(|r| **r == hi && lo != hi).count();
+        assert_eq!(upper as u32, dither.duty());
+
+        let average = regs.iter().map(|r| exact(*r)).sum::<f64>() / n as f64;
+        let ppm = (average / f as f64 - 1.0) * 1e6;
+        assert!(ppm.abs() < 0.1, "f={} {} ppm", f, ppm);
+        assert!(ppm.abs() <= error_ppm(f).unwrap().abs() as f64);
+    }
+}
+
+#[test]
+fn exact_codes_dont_dither() {
+    let reg = freq_to_reg(1_039).unwrap();
+    let mut dither = Dither::new(1_039).unwrap();
+    assert_eq!(dither.codes(), (reg, reg));
+    assert_eq!(dither.duty(), 0);
+    assert!(dither.by_ref().take(100).all(|r| r == reg));
+    assert_eq!(reg_to_freq(reg), 1_039);
+}
+
+#[test]
+fn out_of_range_is_rejected() {
+    assert!(Dither::new(1_000).is_err());
+    assert!(Dither::new(70_000_000).is_err());
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "eh1")]
+
+use std::convert::Infallible;
+
+use embedded_hal_1::{
+    digital::{ErrorType as PinErrorType, OutputPin, StatefulOutputPin},
+    i2c::{ErrorType, I2c, Operation, SevenBitAddress},
+};
+use ltc690x::{Address, OutputMode, LTC6904};
+
+/// Bus recording the written bytes
+#[derive(Default)]
+struct Bus {
+    written: Vec<(u8, Vec<u8>)>,
+}
+
+impl ErrorType for Bus {
+    type Error = Infallible;
+}
+
+impl I2c<SevenBitAddress> for Bus {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        for op in operations {
+            match op {
+                Operation::Write(bytes) => self.written.push((address, bytes.to_vec())),
+                Operation::Read(buffer) => buffer.fill(0),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct Pin {
+    high: bool,
+}
+
+impl PinErrorType for Pin {
+    type Error = Infallible;
+}
+
+impl OutputPin for Pin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.high = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.high = true;
+        Ok(())
+    }
+}
+
+impl StatefulOutputPin for Pin {
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.high)
+    }
+
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.high)
+    }
+}
+
+#[test]
+fn driver_works_on_eh1_bus_and_pin() {
+    let mut ltc = LTC6904::new_eh1(Bus::default(), Address::AddressLow, Pin::default());
+
+    ltc.configure(108_000, OutputMode::BothOutputs).unwrap();
+    ltc.enable_output().unwrap();
+    assert!(ltc.is_output_enabled().unwrap());
+
+    let (i2c, pin) = ltc.free().unwrap();
+    assert_eq!(i2c.into_inner().written, vec![(0x17, vec![0x6c, 0x4c])]);
+    assert!(!pin.into_inner().high);
+}
+
+#[test]
+fn bus_errors_keep_their_kind() {
+    use embedded_hal_1::i2c::{ErrorKind, NoAcknowledgeSource};
+    use ltc690x::{Error, FrequencyError};
+
+    #[derive(Debug)]
+    struct BusError(ErrorKind);
+
+    impl embedded_hal_1::i2c::Error for BusError {
+        fn kind(&self) -> ErrorKind {
+            self.0
+        }
+    }
+
+    let nack = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address);
+    let absent: Error<BusError, Infallible> = Error::I2c(BusError(nack));
+    assert_eq!(absent.i2c_error_kind(), Some(nack));
+    assert!(absent.is_device_absent());
+    assert!(!absent.is_bus_contention());
+
+    let contended: Error<BusError, Infallible> = Error::I2c(BusError(ErrorKind::ArbitrationLoss));
+    assert!(contended.is_bus_contention());
+    assert!(!contended.is_device_absent());
+
+    let data_nack = BusError(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data));
+    assert!(!Error::<_, Infallible>::I2c(data_nack).is_device_absent());
+
+    let frequency: Error<BusError, Infallible> = Error::Frequency(FrequencyError::TooLow);
+    assert_eq!(frequency.i2c_error_kind(), None);
+}
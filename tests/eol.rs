@@ -0,0 +1,57 @@
+use ltc690x::{
+    accuracy::accuracy_bounds,
+    eol::{eol_pattern, OctavePosition},
+    freq_to_reg, octave_range, reg_to_freq, resolution_at, Register,
+};
+
+#[test]
+fn pattern_covers_every_octave() {
+    let points: Vec<_> = eol_pattern().collect();
+    assert_eq!(points.len(), 48);
+    assert_eq!(points[0].bounds.nominal, ltc690x::FREQUENCY_MIN);
+    for (oct, chunk) in points.chunks(3).enumerate() {
+        let (min, max) = octave_range(oct as u16).unwrap();
+        assert_eq!(
+            chunk.iter().map(|p| p.position).collect::<Vec<_>>(),
+            [
+                OctavePosition::Bottom,
+                OctavePosition::Middle,
+                OctavePosition::Top
+            ]
+        );
+        let [bottom, middle, top] = [0, 1, 2].map(|i| chunk[i].bounds.nominal);
+        // the datasheet ranges are rounded to four digits
+        assert!(
+            bottom.abs_diff(min) as u64 * 1_000 <= min as u64,
+            "{} {}",
+            bottom,
+            min
+        );
+        assert!(
+            top.abs_diff(max) as u64 * 1_000 <= max as u64,
+            "{} {}",
+            top,
+            max
+        );
+        // closest to the middle of the octave
+        let step = resolution_at(middle).unwrap();
+        assert!(middle.abs_diff((bottom + top) / 2) <= step);
+        for p in chunk {
+            assert_eq!(p.oct, oct as u16);
+            assert_eq!(Register::new(p.reg).oct(), p.oct);
+            assert_eq!(p.reg & 0b11, 0);
+            assert_eq!(p.bounds.nominal, reg_to_freq(p.reg));
+            assert_eq!(freq_to_reg(p.bounds.nominal), Ok(p.reg));
+            assert_eq!(accuracy_bounds(p.bounds.nominal), Ok(p.bounds));
+        }
+    }
+}
+
+#[test]
+fn points_accept_the_guaranteed_range() {
+    for p in eol_pattern() {
+        assert!(p.accepts(p.bounds.nominal));
+        assert!(p.accepts(p.bounds.min) && p.accepts(p.bounds.max));
+        assert!(!p.accepts(p.bounds.min - 1) && !p.accepts(p.bounds.max + 1));
+    }
+}
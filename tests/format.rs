@@ -0,0 +1,74 @@
+use ltc690x::{
+    frequency::{format_frequency, FORMATTED_LEN},
+    Frequency,
+};
+
+fn format(f: Frequency) -> String {
+    format_frequency(f, &mut [0; FORMATTED_LEN]).to_string()
+}
+
+#[test]
+fn picks_the_largest_unit() {
+    assert_eq!(format(Frequency::from_hertz(1_006_000)), "1.006 MHz");
+    assert_eq!(format(Frequency::from_hertz(68_030_000)), "68.030 MHz");
+    assert_eq!(format(Frequency::from_hertz(1_039)), "1.039 kHz");
+    assert_eq!(format(Frequency::from_millihertz(440_500)), "440.500 Hz");
+    assert_eq!(format(Frequency::from_millihertz(5)), "0.005 Hz");
+    assert_eq!(format(Frequency::default()), "0.000 Hz");
+}
+
+#[test]
+fn rounds_the_last_decimal() {
+    assert_eq!(format(Frequency::from_hertz(12_288_499)), "12.288 MHz");
+    assert_eq!(format(Frequency::from_hertz(12_288_500)), "12.289 MHz");
+    // rounding up into the next unit
+    assert_eq!(format(Frequency::from_millihertz(999_999_600)), "1.000 MHz");
+    assert_eq!(format(Frequency::from_millihertz(999_499)), "999.499 Hz");
+    assert_eq!(format(Frequency::from_millihertz(999_500)), "1.000 kHz");
+}
+
+#[test]
+fn longest_frequency_fits() {
+    let s = format(Frequency::from_millihertz(u64::MAX));
+    assert_eq!(s, "18446744073.710 MHz");
+    assert_eq!(s.len(), FORMATTED_LEN);
+}
+
+#[cfg(feature = "parse")]
+#[test]
+fn frequencies_parse_with_and_without_unit() {
+    use ltc690x::parse::ParseFrequencyError;
+
+    let parse = |s: &str| s.parse::<Frequency>();
+    assert_eq!(parse("12.288MHz"), Ok(Frequency::from_kilohertz(12_288)));
+    assert_eq!(parse("108kHz"), Ok(Frequency::from_kilohertz(108)));
+    assert_eq!(parse("1039"), Ok(Frequency::from_hertz(1_039)));
+    assert_eq!(parse(" 440.5 hz "), Ok(Frequency::from_millihertz(440_500)));
+    assert_eq!(parse("1."), Ok(Frequency::from_hertz(1)));
+    assert_eq!(parse(".5KHZ"), Ok(Frequency::from_hertz(500)));
+
+    assert_eq!(parse(""), Err(ParseFrequencyError::Invalid));
+    assert_eq!(parse("MHz"), Err(ParseFrequencyError::Invalid));
+    assert_eq!(parse("-5"), Err(ParseFrequencyError::Invalid));
+    assert_eq!(parse("1.2.3"), Err(ParseFrequencyError::Invalid));
+    assert_eq!(parse(". Hz"), Err(ParseFrequencyError::Invalid));
+    assert_eq!(parse("5 GHz"), Err(ParseFrequencyError::Unit));
+    assert_eq!(parse("1.0005"), Err(ParseFrequencyError::Precision));
+    assert_eq!(
+        parse("18446744073709552 Hz"),
+        Err(ParseFrequencyError::Overflow)
+    );
+}
+
+#[cfg(feature = "parse")]
+#[test]
+fn formatted_frequencies_parse_back() {
+    for f in [
+        Frequency::from_hertz(1_006_000),
+        Frequency::from_hertz(1_039),
+        Frequency::from_millihertz(440_500),
+        Frequency::default(),
+    ] {
+        assert_eq!(format(f).parse(), Ok(f));
+    }
+}
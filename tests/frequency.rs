@@ -0,0 +1,337 @@
+//! Property tests for the frequency <-> register conversion
+
+use ltc690x::{
+    achievable_near, consts, error_ppm, freq_to_reg, frequency_range, iter_octaves, octave_range,
+    plan_frequency, reg_to_freq, resolution_at, Address, FrequencyError, Register, FREQUENCY_MAX,
+    FREQUENCY_MIN,
+};
+use proptest::prelude::*;
+
+const FREQU_MIN: u32 = FREQUENCY_MIN;
+const FREQU_MAX: u32 = FREQUENCY_MAX;
+
+fn fields(reg: u16) -> (u16, u16, u16) {
+    (reg >> 12, (reg >> 2) & 0x3ff, reg & 0b11)
+}
+
+/// codes adjacent to `reg`, including the edges of the neighbouring octaves
+fn neighbours(reg: u16) -> Vec<u16> {
+    let (oct, dac, _) = fields(reg);
+    let mut n = Vec::new();
+    match dac {
+        0 if oct > 0 => n.push((oct - 1) << 12 | 1023 << 2),
+        0 => {}
+        _ => n.push(reg - 4),
+    }
+    match dac {
+        1023 if oct < 15 => n.push((oct + 1) << 12),
+        1023 => {}
+        _ => n.push(reg + 4),
+    }
+    n
+}
+
+fn distance(a: u32, b: u32) -> u32 {
+    a.max(b) - a.min(b)
+}
+
+proptest! {
+    #[test]
+    fn no_neighbouring_code_is_closer(f in FREQU_MIN..=FREQU_MAX) {
+        let reg = freq_to_reg(f).unwrap();
+        let error = distance(reg_to_freq(reg), f);
+        for n in neighbours(reg) {
+            // 1 Hz slack for rounding the achieved frequencies
+            prop_assert!(
+                error <= distance(reg_to_freq(n), f) + 1,
+                "f={} reg={:#06x} error={} neighbour={:#06x}", f, reg, error, n
+            );
+        }
+    }
+
+    #[test]
+    fn fields_stay_in_range(f in FREQU_MIN..=FREQU_MAX) {
+        let reg = freq_to_reg(f).unwrap();
+        let (oct, dac, cnf) = fields(reg);
+        prop_assert!(oct <= 15);
+        prop_assert!(dac <= 1023);
+        prop_assert_eq!(cnf, 0);
+        prop_assert_eq!(oct << 12 | dac << 2, reg);
+    }
+
+    #[test]
+    fn frequency_is_monotonic(f in FREQU_MIN..FREQU_MAX) {
+        let lo = reg_to_freq(freq_to_reg(f).unwrap());
+        let hi = reg_to_freq(freq_to_reg(f + 1).unwrap());
+        prop_assert!(lo <= hi, "f={} -> {}, f+1 -> {}", f, lo, hi);
+    }
+
+    #[test]
+    fn octave_matches_the_datasheet_table(f in FREQU_MIN..=FREQU_MAX) {
+        let table = (0..16).find(|oct| {
+            let (min, max) = octave_range(*oct).unwrap();
+            (min..=max).contains(&f)
+        });
+        // frequencies in the gaps between the table's octaves may go either way
+        if let Some(oct) = table {
+            prop_assert_eq!(freq_to_reg(f).unwrap() >> 12, oct, "f={}", f);
+        }
+    }
+
+    #[test]
+    fn below_range_is_rejected(f in 0..FREQU_MIN) {
+        prop_assert!(matches!(freq_to_reg(f), Err(FrequencyError::TooLow)));
+    }
+
+    #[test]
+    fn above_range_is_rejected(f in FREQU_MAX + 1..) {
+        prop_assert!(matches!(freq_to_reg(f), Err(FrequencyError::TooHigh)));
+    }
+}
+
+#[test]
+fn range_limits_are_accepted() {
+    assert_eq!(reg_to_freq(freq_to_reg(FREQU_MIN).unwrap()), FREQU_MIN);
+    assert!(freq_to_reg(FREQU_MAX).is_ok());
+}
+
+#[test]
+fn error_ppm_of_exact_codes_is_zero() {
+    // OCT = 0, DAC = 0 and OCT = 15, DAC = 0 generate exactly 1039 Hz and 34.045952 MHz
+    assert_eq!(error_ppm(1_039).unwrap(), 0);
+    assert_eq!(error_ppm(34_045_952).unwrap(), 0);
+    assert!(error_ppm(12_288_000).unwrap().abs() < 500);
+}
+
+#[test]
+fn resolution_matches_neighbouring_codes() {
+    for f in [1_039, 5_000, 108_000, 1_000_000, 12_288_000, 68_030_000] {
+        let reg = freq_to_reg(f).unwrap();
+        let next = if (reg >> 2) & 0x3ff < 1023 {
+            reg + 4
+        } else {
+            reg - 4
+        };
+        let step = distance(reg_to_freq(next), reg_to_freq(reg));
+        let resolution = resolution_at(f).unwrap();
+        assert!(
+            distance(step, resolution) <= 1,
+            "f={} {} != {}",
+            f,
+            step,
+            resolution
+        );
+    }
+    assert!(resolution_at(1_000).is_err());
+}
+
+#[test]
+fn achievable_near_is_sorted_by_error() {
+    let candidates: Vec<_> = achievable_near(34_030_000, 6).unwrap().collect();
+    assert_eq!(candidates.len(), 6);
+    assert_eq!(candidates[0].reg, freq_to_reg(34_030_000).unwrap());
+    assert!(candidates
+        .windows(2)
+        .all(|w| w[0].error_ppm.abs() <= w[1].error_ppm.abs()));
+    // the target sits between octave 14 and 15, both have to show up
+    assert!(candidates.iter().any(|c| c.reg >> 12 == 14));
+    assert!(candidates.iter().any(|c| c.reg >> 12 == 15));
+    for c in candidates {
+        assert_eq!(c.frequency, reg_to_freq(c.reg));
+    }
+}
+
+#[test]
+fn achievable_near_stops_at_the_range_limits() {
+    let candidates: Vec<_> = achievable_near(1_039, 3).unwrap().collect();
+    assert_eq!(candidates[0].error_ppm, 0);
+    assert!(candidates.iter().all(|c| c.frequency >= 1_039));
+}
+
+#[test]
+fn public_range_matches_octaves() {
+    assert_eq!(frequency_range(), (1_039, 68_030_000));
+    assert_eq!(octave_range(0).unwrap().0, FREQUENCY_MIN);
+    assert_eq!(octave_range(15).unwrap().1, FREQUENCY_MAX);
+    assert_eq!(octave_range(16), None);
+    for oct in 0..16 {
+        let (min, max) = octave_range(oct).unwrap();
+        assert_eq!(freq_to_reg(min).unwrap() >> 12, oct);
+        assert_eq!(freq_to_reg(max).unwrap() >> 12, oct);
+    }
+}
+
+#[test]
+fn constants_match_the_conversion() {
+    for (oct, range) in consts::OCTAVE_RANGES.iter().enumerate() {
+        assert_eq!(octave_range(oct as u16), Some(*range));
+    }
+    let top = Register::from_fields(consts::OCT_MAX, consts::DAC_MAX, 0).bits();
+    assert_eq!(freq_to_reg(FREQUENCY_MAX).unwrap(), top);
+    assert_eq!(reg_to_freq(0), consts::BASE_FREQUENCY / 2);
+    assert_eq!(u8::from(Address::AddressLow), consts::ADDRESS_LOW);
+    assert_eq!(u8::from(Address::AddressHigh), consts::ADDRESS_HIGH);
+}
+
+#[test]
+fn octave_plan_covers_the_range() {
+    let octaves: Vec<_> = iter_octaves().collect();
+    assert_eq!(octaves.len(), 16);
+    assert_eq!((octaves[0].1, octaves[15].2), frequency_range());
+    for (oct, min, max, step) in octaves {
+        assert_eq!(octave_range(oct), Some((min, max)));
+        let top = Register::from_fields(oct, consts::DAC_MAX, 0).bits();
+        let below = Register::from_fields(oct, consts::DAC_MAX - 1, 0).bits();
+        // both ends are rounded to Hz
+        let diff = reg_to_freq(top) - reg_to_freq(below);
+        assert!(
+            step.abs_diff(diff) <= 1,
+            "oct={}: {} vs {}",
+            oct,
+            step,
+            diff
+        );
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn enumeration_lists_every_code_in_range() {
+    use ltc690x::enumerate_achievable;
+
+    let all = enumerate_achievable(..);
+    assert_eq!(all.len(), 16 * 1024);
+    assert!(all.windows(2).all(|w| w[0].1 <= w[1].1));
+    assert!(all.iter().all(|(reg, f)| reg_to_freq(*reg) == *f));
+
+    let some = enumerate_achievable(1_000_000..=1_010_000);
+    assert!(!some.is_empty());
+    assert!(some
+        .iter()
+        .all(|(_, f)| (1_000_000..=1_010_000).contains(f)));
+    let first = all.iter().position(|e| *e == some[0]).unwrap();
+    assert_eq!(&all[first..first + some.len()], &some[..]);
+    assert!(all[first - 1].1 < 1_000_000);
+    assert!(all[first + some.len()].1 > 1_010_000);
+}
+
+#[test]
+fn errors_are_displayable() {
+    assert_eq!(
+        FrequencyError::TooLow.to_string(),
+        "frequency below 1039 Hz"
+    );
+    let e: ltc690x::Error<(), ()> = FrequencyError::TooHigh.into();
+    assert_eq!(e.to_string(), "frequency above 68030000 Hz");
+}
+
+#[test]
+fn errors_are_categorized() {
+    use ltc690x::{Error, ErrorCategory, OscillatorError};
+
+    assert_eq!(FrequencyError::TooLow.category(), ErrorCategory::Range);
+    assert_eq!(
+        FrequencyError::ToleranceExceeded.category(),
+        ErrorCategory::Tolerance
+    );
+    assert_eq!(Error::<u8, ()>::I2c(3).category(), ErrorCategory::Bus);
+    assert_eq!(Error::<(), u8>::Pin(3).category(), ErrorCategory::Pin);
+    assert_eq!(Error::<(), ()>::Timeout.category(), ErrorCategory::Timeout);
+    let e: Error<(), ()> = FrequencyError::EdgeCode.into();
+    assert_eq!(e.category(), ErrorCategory::Tolerance);
+
+    // erasing the bus and pin errors keeps the category
+    for e in [
+        Error::<u8, u8>::I2c(3),
+        Error::Pin(3),
+        Error::Timeout,
+        FrequencyError::TooHigh.into(),
+    ] {
+        assert_eq!(OscillatorError::from(e).category(), e.category());
+    }
+    assert_eq!(
+        OscillatorError::from(Error::<(), ()>::Timeout),
+        OscillatorError::Timeout
+    );
+
+    // usable as `dyn Error`, even with bus and pin errors which aren't errors themselves
+    let e: Box<dyn std::error::Error> = Box::new(Error::<u8, ()>::I2c(3));
+    assert_eq!(e.to_string(), "I2C error: 3");
+    assert!(e.source().is_none());
+}
+
+#[test]
+fn plan_matches_the_conversion() {
+    for f in [1_039, 108_000, 12_288_000, 34_030_000, 68_030_000] {
+        let plan = plan_frequency(f).unwrap();
+        let reg = freq_to_reg(f).unwrap();
+        assert_eq!(plan.reg(), reg);
+        assert_eq!((plan.oct, plan.dac), (fields(reg).0, fields(reg).1));
+        assert_eq!(plan.achieved, reg_to_freq(reg));
+        assert_eq!(plan.error_ppm, error_ppm(f).unwrap());
+    }
+    assert_eq!(plan_frequency(1_000), Err(FrequencyError::TooLow));
+}
+
+#[test]
+fn narrow_hop_pattern_uses_every_code() {
+    use ltc690x::hopping::hop_pattern;
+
+    // 1100 Hz +- 1 Hz spans fewer codes than the maximal number of hops
+    let lo = freq_to_reg(1_099).unwrap();
+    let hi = freq_to_reg(1_101).unwrap();
+    let codes = ((hi - lo) >> 2) as usize;
+    assert!(codes < 8);
+    let pattern: Vec<_> = hop_pattern(1_100, 1_000).unwrap().collect();
+    assert_eq!(pattern.len(), 2 * codes);
+    assert_eq!(pattern[0], lo);
+    assert_eq!(pattern[codes], hi);
+    assert!(pattern
+        .windows(2)
+        .all(|w| w[0].max(w[1]) - w[0].min(w[1]) == 4));
+    assert_eq!(hop_pattern(1_000_000, 0).unwrap().len(), 1);
+}
+
+proptest! {
+    #[test]
+    fn math_never_panics(f in any::<u32>(), ppm in any::<u32>()) {
+        let _ = freq_to_reg(f);
+        let _ = error_ppm(f);
+        let _ = resolution_at(f);
+        let _ = plan_frequency(f);
+        let _ = ltc690x::Dither::new(f);
+        let _ = ltc690x::hopping::hop_pattern(f, ppm);
+        let _ = ltc690x::audio::mclk_error_ppm(f, ppm);
+        let _ = ltc690x::baud::baud_error_ppm(f, ppm);
+        let _ = ltc690x::ratio::multiple_of(f, ppm, f >> 16);
+        ltc690x::eol::eol_pattern().for_each(drop);
+    }
+}
+
+#[test]
+fn multiples_round_to_millihertz() {
+    use ltc690x::{ratio::multiple_of, Frequency};
+
+    assert_eq!(
+        multiple_of(8_000_000, 3, 2),
+        Ok(Frequency::from_megahertz(12))
+    );
+    assert_eq!(
+        multiple_of(32_768, 1, 3),
+        Ok(Frequency::from_millihertz(10_922_667))
+    );
+    assert_eq!(multiple_of(1_000, 1, 0), Err(FrequencyError::TooHigh));
+    assert_eq!(
+        multiple_of(u32::MAX, u32::MAX, 1),
+        Err(FrequencyError::TooHigh)
+    );
+}
+
+#[test]
+fn every_register_decodes() {
+    for reg in 0..=u16::MAX {
+        let f = reg_to_freq(reg);
+        assert!((FREQU_MIN..=FREQU_MAX + 1).contains(&f), "{:#06x}", reg);
+        let _ = ltc690x::RegisterSnapshot::from(reg);
+    }
+}
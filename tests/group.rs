@@ -0,0 +1,93 @@
+#![cfg(feature = "sim")]
+
+use ltc690x::{
+    group::{write_all, write_all_spaced},
+    sim::Ltc6904Sim,
+    Address, Error, FrequencyError, LTC6904,
+};
+
+#[test]
+fn all_devices_are_programmed() {
+    let (sim_a, sim_b) = (
+        Ltc6904Sim::new(Address::AddressLow),
+        Ltc6904Sim::new(Address::AddressHigh),
+    );
+    let mut a = LTC6904::new(sim_a.i2c(), Address::AddressLow, sim_a.oe_pin());
+    let mut b = LTC6904::new(sim_b.i2c(), Address::AddressHigh, sim_b.oe_pin());
+    b.set_calibration_ppm(1_000).unwrap();
+
+    write_all(&mut [&mut a, &mut b], [1_000_000, 2_000_000]).unwrap();
+    assert_eq!(sim_a.register(), a.get_reg());
+    assert_eq!(sim_b.register(), b.get_reg());
+    assert_eq!(a.requested_frequency(), 1_000_000);
+    assert_eq!(b.requested_frequency(), 2_000_000);
+    assert!(b.programmed_frequency().abs_diff(2_000_000) < 1_000);
+
+    let mut delay = embedded_hal_mock::delay::MockNoop::new();
+    write_all_spaced(
+        &mut [&mut a, &mut b],
+        [3_000_000, 4_000_000],
+        10,
+        &mut delay,
+    )
+    .unwrap();
+    assert_eq!(sim_a.writes().len(), 2);
+    assert_eq!(sim_b.register(), b.get_reg());
+}
+
+#[test]
+fn nothing_is_changed_on_a_rejected_frequency() {
+    let (sim_a, sim_b) = (
+        Ltc6904Sim::new(Address::AddressLow),
+        Ltc6904Sim::new(Address::AddressHigh),
+    );
+    let mut a = LTC6904::new(sim_a.i2c(), Address::AddressLow, sim_a.oe_pin());
+    let mut b = LTC6904::new(sim_b.i2c(), Address::AddressHigh, sim_b.oe_pin());
+    let before = a.get_reg();
+
+    assert_eq!(
+        write_all(&mut [&mut a, &mut b], [1_000_000, 100]),
+        Err(Error::Frequency(FrequencyError::TooLow))
+    );
+    assert_eq!(a.get_reg(), before);
+    assert!(sim_a.writes().is_empty() && sim_b.writes().is_empty());
+}
+
+#[test]
+fn linked_oscillators_keep_their_ratio() {
+    use ltc690x::group::{LinkedOscillators, WriteOrder};
+
+    let (sim_a, sim_b) = (
+        Ltc6904Sim::new(Address::AddressLow),
+        Ltc6904Sim::new(Address::AddressHigh),
+    );
+    let a = LTC6904::new(sim_a.i2c(), Address::AddressLow, sim_a.oe_pin());
+    let b = LTC6904::new(sim_b.i2c(), Address::AddressHigh, sim_b.oe_pin());
+    let mut linked = LinkedOscillators::new(a, b, 2, 3);
+
+    let ppm = linked.set_primary(3_000_000).unwrap();
+    assert_eq!(linked.secondary().requested_frequency(), 2_000_000);
+    assert_eq!(sim_a.register(), linked.primary().get_reg());
+    assert_eq!(sim_b.register(), linked.secondary().get_reg());
+    let actual = sim_b.frequency() as f64 / sim_a.frequency() as f64 * 1.5 - 1.0;
+    assert!(
+        (actual * 1e6 - ppm as f64).abs() < 2.0,
+        "{} {}",
+        actual,
+        ppm
+    );
+
+    linked.set_write_order(WriteOrder::SecondaryFirst);
+    linked.set_secondary(1_000_000).unwrap();
+    assert_eq!(linked.primary().requested_frequency(), 1_500_000);
+
+    // 1.5 kHz is fine for the primary, but 2/3 of it is too low for the secondary
+    let before = linked.primary().get_reg();
+    assert_eq!(
+        linked.set_primary(1_500),
+        Err(Error::Frequency(FrequencyError::TooLow))
+    );
+    assert_eq!(linked.primary().get_reg(), before);
+    assert_eq!((sim_a.writes().len(), sim_b.writes().len()), (2, 2));
+    assert_eq!(linked.ratio(), (2, 3));
+}
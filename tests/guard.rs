@@ -0,0 +1,50 @@
+#![cfg(feature = "sim")]
+
+use ltc690x::{sim::Ltc6904Sim, Address, FrequencyError, OutputMode, LTC6904};
+
+fn measure(
+    ltc: &mut LTC6904<ltc690x::sim::SimI2c, ltc690x::sim::SimOutputEnable>,
+    f: u32,
+) -> Result<(), FrequencyError> {
+    let mut guard = ltc.enable_scoped().unwrap();
+    guard.set_frequency(f)?;
+    guard.write_out().unwrap();
+    Ok(())
+}
+
+#[test]
+fn guard_disables_output_on_early_return() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+
+    assert!(measure(&mut ltc, 100).is_err());
+    assert!(!sim.is_output_enabled());
+    assert!(measure(&mut ltc, 100_000).is_ok());
+    assert!(!sim.is_output_enabled());
+}
+
+#[test]
+fn guard_keeps_output_enabled_while_alive() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+
+    let guard = ltc.enable_scoped().unwrap();
+    assert!(sim.is_output_enabled());
+    guard.release().unwrap();
+    assert!(!sim.is_output_enabled());
+}
+
+#[test]
+fn retune_programs_the_running_clock() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    ltc.set_output_mode(OutputMode::ClkOnly);
+
+    let mut guard = ltc.enable_scoped().unwrap();
+    let programmed = guard.retune(2_000_000).unwrap();
+    assert_eq!(sim.register(), programmed.reg);
+    assert_eq!(sim.output_mode(), OutputMode::ClkOnly);
+    assert_eq!(sim.output_frequency(), Some(programmed.frequency));
+    drop(guard);
+    assert_eq!(sim.output_frequency(), None);
+}
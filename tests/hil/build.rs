@@ -0,0 +1,12 @@
+//! Puts `memory.x` in the linker search path and links the tests with the `embedded-test`
+//! linker script
+
+use std::{env, fs, path::PathBuf};
+
+fn main() {
+    let out = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    fs::write(out.join("memory.x"), include_bytes!("memory.x")).unwrap();
+    println!("cargo:rustc-link-search={}", out.display());
+    println!("cargo:rustc-link-arg-tests=-Tembedded-test.x");
+    println!("cargo:rerun-if-changed=memory.x");
+}
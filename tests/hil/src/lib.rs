@@ -0,0 +1,92 @@
+//! Board setup and clock measurement for the hardware-in-the-loop tests
+//!
+//! nRF52840 DK with an LTC6904 on SCL P0.28, SDA P0.29 and OE P1.01, ADR strapped low, as in
+//! `examples/nrf52840.rs`. The CLK output is wired back to P0.03.
+//!
+//! [`Board::measure_hz()`] counts the rising edges on P0.03 for [`GATE_US`]: GPIOTE turns every
+//! edge into an event, which PPI routes to the count task of TIMER1 running as a counter, while
+//! TIMER0 times the gate. No CPU is involved per edge, which keeps the count exact up to a few
+//! MHz.
+
+#![no_std]
+
+use nrf52840_hal::{
+    gpio::{self, Floating, Input, Output, Pin, PushPull},
+    gpiote::Gpiote,
+    pac,
+    ppi::{self, ConfigurablePpi, Ppi},
+    timer::Timer,
+    twim::{self, Twim},
+};
+
+use ltc690x::{Address, LTC6904};
+
+pub type Ltc = LTC6904<Twim<pac::TWIM0>, Pin<Output<PushPull>>>;
+
+/// Time the edges are counted for, resolving 10 Hz
+pub const GATE_US: u32 = 100_000;
+
+/// Driver and frequency counter
+pub struct Board {
+    pub ltc: Ltc,
+    counter: pac::TIMER1,
+    gate: Timer<pac::TIMER0>,
+    // kept configured as the GPIOTE input for the lifetime of the board
+    _clk: Pin<Input<Floating>>,
+    _gpiote: Gpiote,
+}
+
+impl Board {
+    /// Takes the peripherals, creates the driver with the outputs disabled and sets up the counter
+    pub fn take() -> Self {
+        let p = pac::Peripherals::take().unwrap();
+        let p0 = gpio::p0::Parts::new(p.P0);
+        let p1 = gpio::p1::Parts::new(p.P1);
+
+        let scl = p0.p0_28.into_floating_input().degrade();
+        let sda = p0.p0_29.into_floating_input().degrade();
+        let i2c = Twim::new(p.TWIM0, twim::Pins { scl, sda }, twim::Frequency::K100);
+        let ltc = LTC6904::new(
+            i2c,
+            Address::AddressLow,
+            p1.p1_01.into_push_pull_output(gpio::Level::Low).degrade(),
+        );
+
+        let counter = p.TIMER1;
+        counter.mode.write(|w| w.mode().counter());
+        counter.bitmode.write(|w| w.bitmode()._32bit());
+
+        let clk = p0.p0_03.into_floating_input().degrade();
+        let gpiote = Gpiote::new(p.GPIOTE);
+        gpiote.channel0().input_pin(&clk).lo_to_hi();
+        let mut ppi = ppi::Parts::new(p.PPI);
+        ppi.ppi0.set_event_endpoint(gpiote.channel0().event());
+        ppi.ppi0.set_task_endpoint(&counter.tasks_count);
+        ppi.ppi0.enable();
+
+        Self {
+            ltc,
+            counter,
+            gate: Timer::new(p.TIMER0),
+            _clk: clk,
+            _gpiote: gpiote,
+        }
+    }
+
+    /// Frequency on P0.03 in Hz, counted over [`GATE_US`]
+    pub fn measure_hz(&mut self) -> u32 {
+        let timer = &self.counter;
+        timer.tasks_clear.write(|w| unsafe { w.bits(1) });
+        timer.tasks_start.write(|w| unsafe { w.bits(1) });
+        self.gate.delay(GATE_US);
+        timer.tasks_capture[0].write(|w| unsafe { w.bits(1) });
+        timer.tasks_stop.write(|w| unsafe { w.bits(1) });
+        let edges = timer.cc[0].read().bits();
+        (edges as u64 * 1_000_000 / GATE_US as u64) as u32
+    }
+
+    /// Waits `us` microseconds, e.g. for the oscillator to settle
+    pub fn wait_us(&mut self, us: u32) {
+        self.gate.delay(us);
+    }
+}
@@ -0,0 +1,74 @@
+//! Measures the generated clock and compares it to the programmed frequency
+//!
+//! The tolerance covers the initial accuracy of the device, the counter resolves 10 Hz. Every
+//! test runs on a freshly reset board.
+
+#![no_std]
+#![no_main]
+
+use defmt_rtt as _;
+
+/// Largest deviation from the programmed frequency accepted, in parts per million
+const TOLERANCE_PPM: u64 = 15_000;
+
+fn assert_within_tolerance(measured: u32, expected: u32) {
+    let deviation = (measured as i64 - expected as i64).unsigned_abs();
+    defmt::info!("expected {} Hz, measured {} Hz", expected, measured);
+    defmt::assert!(
+        deviation * 1_000_000 <= expected as u64 * TOLERANCE_PPM,
+        "{} Hz is off by more than {} ppm",
+        measured,
+        TOLERANCE_PPM
+    );
+}
+
+#[embedded_test::tests]
+mod tests {
+    use ltc690x::{OutputMode, WAKE_SETTLING_US};
+    use ltc690x_hil::Board;
+
+    use super::assert_within_tolerance;
+
+    #[init]
+    fn init() -> Board {
+        Board::take()
+    }
+
+    #[test]
+    fn programmed_frequencies_are_generated(mut board: Board) {
+        board.ltc.enable_output().unwrap();
+        for f in [10_000, 108_000, 1_000_000, 2_000_000] {
+            let programmed = board.ltc.program_frequency(f).unwrap();
+            board.wait_us(WAKE_SETTLING_US);
+            assert_within_tolerance(board.measure_hz(), programmed.frequency);
+        }
+    }
+
+    #[test]
+    fn calibration_moves_the_clock_closer(mut board: Board) {
+        board.ltc.enable_output().unwrap();
+        board.ltc.program_frequency(500_000).unwrap();
+        board.wait_us(WAKE_SETTLING_US);
+        let uncalibrated = board.measure_hz();
+        board.ltc.calibrate(500_000, uncalibrated).unwrap();
+
+        board.ltc.program_frequency(500_000).unwrap();
+        board.wait_us(WAKE_SETTLING_US);
+        let calibrated = board.measure_hz();
+        assert_within_tolerance(calibrated, 500_000);
+        // within one DAC step and the counter resolution of the target
+        let step = ltc690x::resolution_at(500_000).unwrap() + 10;
+        defmt::assert!(calibrated.abs_diff(500_000) <= uncalibrated.abs_diff(500_000).max(step));
+    }
+
+    #[test]
+    fn no_clock_when_disabled_or_powered_down(mut board: Board) {
+        board.ltc.program_frequency(1_000_000).unwrap();
+        defmt::assert_eq!(board.measure_hz(), 0);
+
+        board.ltc.enable_output().unwrap();
+        board.ltc.set_output_mode(OutputMode::PowerDown);
+        board.ltc.write_out().unwrap();
+        defmt::assert_eq!(board.measure_hz(), 0);
+    }
+}
@@ -0,0 +1,36 @@
+#![cfg(all(feature = "journal", feature = "sim"))]
+
+use ltc690x::{journal::JOURNAL_LEN, sim::Ltc6904Sim, Address, LTC6904};
+
+#[test]
+fn journal_keeps_the_last_writes() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    assert!(ltc.journal().is_empty());
+
+    ltc.set_frequency(1_000_000).unwrap();
+    ltc.write_out().unwrap();
+    ltc.set_address(Address::AddressHigh);
+    assert!(ltc.write_out().is_err());
+    let last = ltc.journal().last().unwrap();
+    assert_eq!(last.reg, ltc.register());
+    assert!(!last.ok);
+    assert_eq!(ltc.journal().len(), 2);
+
+    ltc.set_address(Address::AddressLow);
+    for i in 0..JOURNAL_LEN as u32 {
+        ltc.set_frequency(2_000_000 + 100_000 * i).unwrap();
+        ltc.write_out().unwrap();
+    }
+    let entries: Vec<_> = ltc.journal().iter().collect();
+    assert_eq!(entries.len(), JOURNAL_LEN);
+    assert!(entries.iter().all(|e| e.ok));
+    assert_eq!(
+        entries.iter().map(|e| e.reg.bits()).collect::<Vec<_>>(),
+        sim.writes()[1..]
+    );
+
+    ltc.clear_journal();
+    assert!(ltc.journal().is_empty());
+    assert_eq!(ltc.journal().iter().count(), 0);
+}
@@ -0,0 +1,151 @@
+use embedded_hal::blocking::spi::Write;
+use embedded_hal_mock::{
+    pin::{Mock as PinMock, State, Transaction as PinTransaction},
+    spi::{Mock as SpiMock, Transaction},
+    MockError,
+};
+use ltc690x::{
+    ltc6903::{CsError, CsPin, LTC6903},
+    OutputMode,
+};
+
+fn cs_pulses(n: usize) -> PinMock {
+    let mut expected = vec![PinTransaction::set(State::High)];
+    for _ in 0..n {
+        expected.push(PinTransaction::set(State::Low));
+        expected.push(PinTransaction::set(State::High));
+    }
+    PinMock::new(&expected)
+}
+
+fn done(ltc: LTC6903<CsPin<SpiMock, PinMock>>) {
+    let (mut spi, mut cs) = ltc.free().into_inner();
+    spi.done();
+    cs.done();
+}
+
+#[test]
+fn write_out_sends_register_with_cs_asserted() {
+    // 108 kHz: OCT = 6, DAC = 787, CLK̅ off
+    let spi = SpiMock::new(&[Transaction::write(vec![0x6c, 0x4e])]);
+    let mut ltc = LTC6903::new(CsPin::new(spi, cs_pulses(1)).unwrap());
+
+    ltc.set_frequency(108_000).unwrap();
+    ltc.set_output_mode(OutputMode::ClkOnly);
+    ltc.write_out().unwrap();
+    assert_eq!(ltc.frequency(), ltc690x::reg_to_freq(0x6c4c));
+
+    done(ltc);
+}
+
+/// Bus failing every transfer
+struct Broken;
+
+impl Write<u8> for Broken {
+    type Error = MockError;
+
+    fn write(&mut self, _: &[u8]) -> Result<(), MockError> {
+        Err(MockError::Io(std::io::ErrorKind::Other))
+    }
+}
+
+#[test]
+fn cs_is_deasserted_after_bus_errors() {
+    let mut ltc = LTC6903::new(CsPin::new(Broken, cs_pulses(1)).unwrap());
+
+    assert!(matches!(ltc.write_out(), Err(CsError::Spi(_))));
+
+    ltc.free().into_inner().1.done();
+}
+
+#[cfg(feature = "eh1")]
+mod eh1 {
+    use std::convert::Infallible;
+
+    use embedded_hal_1::spi::{ErrorType, Operation, SpiDevice};
+    use ltc690x::ltc6903::LTC6903;
+
+    /// Device recording the written transfers
+    #[derive(Default)]
+    struct Device {
+        written: Vec<Vec<u8>>,
+    }
+
+    impl ErrorType for Device {
+        type Error = Infallible;
+    }
+
+    impl SpiDevice for Device {
+        fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Infallible> {
+            for op in operations {
+                if let Operation::Write(bytes) = op {
+                    self.written.push(bytes.to_vec());
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn write_out_through_spi_device() {
+        let mut ltc = LTC6903::new_eh1(Device::default());
+        ltc.set_frequency(108_000).unwrap();
+        ltc.write_out().unwrap();
+        assert_eq!(ltc.free().into_inner().written, vec![vec![0x6c, 0x4c]]);
+    }
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn write_out_async_through_spi_device() {
+    use std::{
+        convert::Infallible,
+        future::Future,
+        pin::pin,
+        sync::Arc,
+        task::{Context, Poll, Wake},
+    };
+
+    use embedded_hal_async::spi::{ErrorType, Operation, SpiDevice};
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    #[derive(Default)]
+    struct Device {
+        written: Vec<Vec<u8>>,
+    }
+
+    impl ErrorType for Device {
+        type Error = Infallible;
+    }
+
+    impl SpiDevice for Device {
+        async fn transaction(
+            &mut self,
+            operations: &mut [Operation<'_, u8>],
+        ) -> Result<(), Infallible> {
+            for op in operations {
+                if let Operation::Write(bytes) = op {
+                    self.written.push(bytes.to_vec());
+                }
+            }
+            Ok(())
+        }
+    }
+
+    let mut ltc = LTC6903::new_async(Device::default());
+    ltc.set_frequency(108_000).unwrap();
+    let waker = Arc::new(NoopWaker).into();
+    {
+        let mut write = pin!(ltc.write_out_async());
+        assert!(matches!(
+            write.as_mut().poll(&mut Context::from_waker(&waker)),
+            Poll::Ready(Ok(()))
+        ));
+    }
+    assert_eq!(ltc.free().into_inner().written, vec![vec![0x6c, 0x4c]]);
+}
@@ -0,0 +1,70 @@
+use embedded_hal_mock::{
+    pin::{Mock as PinMock, State, Transaction},
+    MockError,
+};
+use ltc690x::{
+    ltc6905::{master_frequency, Divider, DividerError, OnePin, TwoPins, LTC6905},
+    Frequency,
+};
+
+#[test]
+fn master_frequency_follows_the_data_sheet() {
+    assert_eq!(master_frequency(10_000).hz(), 170_000_000);
+    assert_eq!(master_frequency(25_000).hz(), 68_900_000);
+}
+
+#[test]
+fn one_pin_switches_between_div1_and_div4() {
+    let pin = PinMock::new(&[Transaction::set(State::Low), Transaction::set(State::High)]);
+    let mut ltc = LTC6905::new(
+        OnePin::new(pin),
+        Frequency::from_megahertz(100),
+        Divider::Div4,
+    )
+    .unwrap();
+    assert_eq!(ltc.frequency(), Frequency::from_megahertz(25));
+
+    ltc.set_divider(Divider::Div1).unwrap();
+    assert_eq!(ltc.frequency(), Frequency::from_megahertz(100));
+
+    assert_eq!(
+        ltc.set_divider(Divider::Div2),
+        Err(DividerError::Unsupported(Divider::Div2))
+    );
+    assert_eq!(ltc.divider(), Divider::Div1);
+    ltc.free().into_inner().done();
+}
+
+#[test]
+fn two_pins_leave_div_open_for_div2() {
+    let level = PinMock::new(&[Transaction::set(State::High)]);
+    let drive = PinMock::new(&[Transaction::set(State::High), Transaction::set(State::Low)]);
+    let pins = TwoPins::new(level, drive);
+    let mut ltc = LTC6905::new(pins, Frequency::from_megahertz(100), Divider::Div1).unwrap();
+
+    ltc.set_divider(Divider::Div2).unwrap();
+    assert_eq!(ltc.frequency(), Frequency::from_megahertz(50));
+
+    let (mut level, mut drive) = ltc.free().into_inner();
+    level.done();
+    drive.done();
+}
+
+#[test]
+fn pin_errors_keep_the_divider() {
+    let err = MockError::Io(std::io::ErrorKind::NotConnected);
+    let pin = PinMock::new(&[
+        Transaction::set(State::High),
+        Transaction::set(State::Low).with_error(err.clone()),
+    ]);
+    let mut ltc = LTC6905::new(
+        OnePin::new(pin),
+        Frequency::from_megahertz(100),
+        Divider::Div1,
+    )
+    .unwrap();
+
+    assert_eq!(ltc.set_divider(Divider::Div4), Err(DividerError::Pin(err)));
+    assert_eq!(ltc.divider(), Divider::Div1);
+    ltc.free().into_inner().done();
+}
@@ -0,0 +1,13 @@
+#![cfg(feature = "midi")]
+
+use ltc690x::midi::{note_to_frequency, note_to_millihertz};
+
+#[test]
+fn reference_pitches() {
+    assert_eq!(note_to_millihertz(69), 440_000);
+    assert_eq!(note_to_millihertz(57), 220_000);
+    assert_eq!(note_to_millihertz(0), 8_176);
+    assert_eq!(note_to_millihertz(60), 261_626);
+    assert_eq!(note_to_frequency(84, 1), 1_047);
+    assert_eq!(note_to_frequency(69, 100), 44_000);
+}
@@ -0,0 +1,792 @@
+//! Bus-level tests asserting the exact transactions the driver issues
+
+use embedded_hal::digital::v2::{OutputPin, StatefulOutputPin};
+use embedded_hal_mock::{
+    delay::MockNoop,
+    i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+    pin::{Mock as PinMock, State as PinState, Transaction as PinTransaction},
+    MockError,
+};
+use ltc690x::{Address, Config, Error, FrequencyError, OePolarity, OutputMode, Presets, LTC6904};
+
+const ADDR_LOW: u8 = 0x17;
+const ADDR_HIGH: u8 = 0x16;
+
+fn done(ltc: LTC6904<I2cMock, PinMock>) {
+    let (mut i2c, mut pin) = ltc.free().unwrap();
+    i2c.done();
+    pin.done();
+}
+
+fn pin_freed() -> PinMock {
+    PinMock::new(&[PinTransaction::set(PinState::Low)])
+}
+
+/// [`PinMock`] remembering the level it was set to, for the operations reading the OE pin back
+struct StatefulPin {
+    mock: PinMock,
+    high: bool,
+}
+
+impl StatefulPin {
+    fn new(transactions: &[PinTransaction]) -> Self {
+        Self {
+            mock: PinMock::new(transactions),
+            high: false,
+        }
+    }
+}
+
+impl OutputPin for StatefulPin {
+    type Error = MockError;
+
+    fn set_low(&mut self) -> Result<(), MockError> {
+        self.mock.set_low()?;
+        self.high = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), MockError> {
+        self.mock.set_high()?;
+        self.high = true;
+        Ok(())
+    }
+}
+
+impl StatefulOutputPin for StatefulPin {
+    fn is_set_high(&self) -> Result<bool, MockError> {
+        Ok(self.high)
+    }
+
+    fn is_set_low(&self) -> Result<bool, MockError> {
+        Ok(!self.high)
+    }
+}
+
+fn done_stateful(ltc: LTC6904<I2cMock, StatefulPin>) {
+    let (mut i2c, mut pin) = ltc.free().unwrap();
+    i2c.done();
+    pin.mock.done();
+}
+
+#[test]
+fn set_frequency_is_staged_until_write_out() {
+    let i2c = I2cMock::new(&[]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+
+    ltc.set_frequency(108_000).unwrap();
+    ltc.set_output_mode(OutputMode::ClkOnly);
+
+    done(ltc);
+}
+
+#[test]
+fn write_out_sends_register_big_endian() {
+    // 108 kHz: OCT = 6, DAC = 787
+    let i2c = I2cMock::new(&[I2cTransaction::write(ADDR_LOW, vec![0x6c, 0x4c])]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+
+    assert_eq!(ltc.set_frequency(108_000).unwrap(), 0x6c4c);
+    ltc.write_out().unwrap();
+
+    done(ltc);
+}
+
+#[test]
+fn write_out_includes_output_configuration() {
+    // 1 MHz: OCT = 9, DAC = 959
+    let i2c = I2cMock::new(&[
+        I2cTransaction::write(ADDR_LOW, vec![0x9e, 0xfc]),
+        I2cTransaction::write(ADDR_LOW, vec![0x9e, 0xfd]),
+        I2cTransaction::write(ADDR_LOW, vec![0x9e, 0xfe]),
+    ]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+
+    ltc.set_frequency(1_000_000).unwrap();
+    for conf in [
+        OutputMode::BothOutputs,
+        OutputMode::ClkInvOnly,
+        OutputMode::ClkOnly,
+    ] {
+        ltc.set_output_mode(conf);
+        ltc.write_out().unwrap();
+    }
+
+    done(ltc);
+}
+
+#[test]
+fn retuning_keeps_output_configuration() {
+    let i2c = I2cMock::new(&[
+        I2cTransaction::write(ADDR_LOW, vec![0x6c, 0x4e]),
+        I2cTransaction::write(ADDR_LOW, vec![0x9e, 0xfe]),
+    ]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+
+    ltc.set_output_mode(OutputMode::ClkOnly);
+    ltc.set_frequency(108_000).unwrap();
+    ltc.write_out().unwrap();
+    ltc.set_frequency(1_000_000).unwrap();
+    ltc.write_out().unwrap();
+
+    done(ltc);
+}
+
+#[test]
+fn power_down_sequence() {
+    let i2c = I2cMock::new(&[
+        I2cTransaction::write(ADDR_LOW, vec![0x6c, 0x4d]),
+        I2cTransaction::write(ADDR_LOW, vec![0x6c, 0x4f]),
+    ]);
+    let pin = PinMock::new(&[
+        PinTransaction::set(PinState::High),
+        PinTransaction::set(PinState::Low),
+        PinTransaction::set(PinState::Low),
+    ]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin);
+
+    ltc.set_frequency(108_000).unwrap();
+    ltc.set_output_mode(OutputMode::ClkInvOnly);
+    ltc.write_out().unwrap();
+    ltc.enable_output().unwrap();
+
+    ltc.set_output_mode(OutputMode::PowerDown);
+    ltc.write_out().unwrap();
+    ltc.disable_output().unwrap();
+
+    done(ltc);
+}
+
+#[test]
+fn address_low() {
+    let i2c = I2cMock::new(&[I2cTransaction::write(ADDR_LOW, vec![0x6c, 0x4c])]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+
+    ltc.set_frequency(108_000).unwrap();
+    ltc.write_out().unwrap();
+
+    done(ltc);
+}
+
+#[test]
+fn address_high() {
+    let i2c = I2cMock::new(&[I2cTransaction::write(ADDR_HIGH, vec![0x6c, 0x4c])]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressHigh, pin_freed());
+
+    ltc.set_frequency(108_000).unwrap();
+    ltc.write_out().unwrap();
+
+    done(ltc);
+}
+
+#[test]
+fn bus_error_is_returned() {
+    let i2c = I2cMock::new(&[I2cTransaction::write(ADDR_LOW, vec![0x6c, 0x4c])
+        .with_error(MockError::Io(std::io::ErrorKind::Other))]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+
+    ltc.set_frequency(108_000).unwrap();
+    assert!(ltc.write_out().is_err());
+
+    done(ltc);
+}
+
+#[test]
+fn free_disables_output() {
+    let pin = PinMock::new(&[
+        PinTransaction::set(PinState::High),
+        PinTransaction::set(PinState::Low),
+    ]);
+    let mut ltc = LTC6904::new(I2cMock::new(&[]), Address::AddressLow, pin);
+
+    ltc.enable_output().unwrap();
+
+    done(ltc);
+}
+
+#[test]
+fn ramp_writes_bounded_steps() {
+    let steps = [103_000u32, 106_000, 108_000];
+    let expected: Vec<I2cTransaction> = steps
+        .iter()
+        .map(|f| {
+            let reg = ltc690x::freq_to_reg(*f).unwrap();
+            I2cTransaction::write(ADDR_LOW, reg.to_be_bytes().to_vec())
+        })
+        .collect();
+    let i2c = I2cMock::new(&expected);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+    ltc.set_frequency(100_000).unwrap();
+
+    ltc.ramp_to(108_000, 3_000, 10, &mut MockNoop::new())
+        .unwrap();
+    assert_eq!(ltc.requested_frequency(), 108_000);
+
+    done(ltc);
+}
+
+#[test]
+fn preset_select_writes_precomputed_word() {
+    let presets = Presets::new([108_000, 1_000_000], OutputMode::ClkOnly).unwrap();
+    let i2c = I2cMock::new(&[
+        I2cTransaction::write(ADDR_LOW, vec![0x9e, 0xfe]),
+        I2cTransaction::write(ADDR_LOW, vec![0x6c, 0x4e]),
+    ]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+
+    assert!(presets.select(&mut ltc, 1).unwrap());
+    assert_eq!(ltc.requested_frequency(), 1_000_000);
+    assert!(presets.select(&mut ltc, 0).unwrap());
+    assert_eq!(ltc.output_mode(), OutputMode::ClkOnly);
+    // an unknown index writes nothing
+    assert!(!presets.select(&mut ltc, 2).unwrap());
+
+    done(ltc);
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_output_settings_keep_their_bits() {
+    use ltc690x::OutputSettings;
+
+    let i2c = I2cMock::new(&[
+        I2cTransaction::write(ADDR_LOW, vec![0x9e, 0xfc]),
+        I2cTransaction::write(ADDR_LOW, vec![0x9e, 0xfd]),
+        I2cTransaction::write(ADDR_LOW, vec![0x9e, 0xfe]),
+        I2cTransaction::write(ADDR_LOW, vec![0x9e, 0xff]),
+    ]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+
+    ltc.set_frequency(1_000_000).unwrap();
+    for (old, new) in [
+        (OutputSettings::ClkNeg, OutputMode::BothOutputs),
+        (OutputSettings::ClkBoth, OutputMode::ClkInvOnly),
+        (OutputSettings::ClkPos, OutputMode::ClkOnly),
+        (OutputSettings::PowerDown, OutputMode::PowerDown),
+    ] {
+        ltc.set_output_conf(old);
+        assert_eq!(ltc.output_mode(), new);
+        assert_eq!(OutputMode::from(old), new);
+        ltc.write_out().unwrap();
+    }
+
+    done(ltc);
+}
+
+#[test]
+fn active_low_oe_is_inverted() {
+    let pin = PinMock::new(&[
+        PinTransaction::set(PinState::Low),
+        PinTransaction::set(PinState::High),
+        PinTransaction::set(PinState::High),
+    ]);
+    let mut ltc = LTC6904::new_with_polarity(
+        I2cMock::new(&[]),
+        Address::AddressLow,
+        pin,
+        OePolarity::ActiveLow,
+    );
+
+    ltc.enable_output().unwrap();
+    ltc.disable_output().unwrap();
+
+    done(ltc);
+}
+
+#[test]
+fn frequency_error_estimate_leaves_configuration() {
+    let i2c = I2cMock::new(&[]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+    ltc.set_frequency(108_000).unwrap();
+    let reg = ltc.get_reg();
+
+    let estimate = ltc.frequency_error_ppm(1_000_000).unwrap();
+    assert_eq!(ltc.get_reg(), reg);
+    assert_eq!(ltc.requested_frequency(), 108_000);
+    assert_eq!(estimate, ltc690x::error_ppm(1_000_000).unwrap());
+    assert!(ltc.frequency_error_ppm(1_000).is_err());
+
+    done(ltc);
+}
+
+#[test]
+fn tolerance_rejects_inaccurate_frequency() {
+    // the codes around 68 MHz are roughly 1000 ppm apart
+    let worst = ltc690x::error_ppm(68_000_000).unwrap().unsigned_abs();
+    assert!(worst > 200);
+
+    let i2c = I2cMock::new(&[]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed()).with_max_error_ppm(200);
+    assert_eq!(ltc.max_error_ppm(), Some(200));
+
+    let reg = ltc.set_frequency(108_000).unwrap();
+    assert!(matches!(
+        ltc.set_frequency(68_000_000),
+        Err(FrequencyError::ToleranceExceeded)
+    ));
+    assert_eq!(ltc.get_reg(), reg);
+    assert_eq!(ltc.requested_frequency(), 108_000);
+
+    done(ltc);
+}
+
+#[test]
+fn program_frequency_writes_immediately() {
+    let i2c = I2cMock::new(&[I2cTransaction::write(ADDR_LOW, vec![0x6c, 0x4e])]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+
+    ltc.set_output_mode(OutputMode::ClkOnly);
+    let programmed = ltc.program_frequency(108_000).unwrap();
+    assert_eq!(programmed.reg, 0x6c4e);
+    assert_eq!(programmed.frequency, ltc.programmed_frequency());
+    assert!(programmed.error_ppm.abs() < 1_000);
+    assert!(matches!(
+        ltc.program_frequency(100),
+        Err(Error::Frequency(FrequencyError::TooLow))
+    ));
+
+    done(ltc);
+}
+
+#[test]
+fn configure_writes_frequency_and_mode_once() {
+    let i2c = I2cMock::new(&[I2cTransaction::write(ADDR_LOW, vec![0x9e, 0xfd])]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+
+    let programmed = ltc.configure(1_000_000, OutputMode::ClkInvOnly).unwrap();
+    assert_eq!(programmed.reg, 0x9efd);
+    // a rejected frequency leaves the output mode alone as well
+    assert!(ltc.configure(70_000_000, OutputMode::PowerDown).is_err());
+    assert_eq!(ltc.output_mode(), OutputMode::ClkInvOnly);
+
+    done(ltc);
+}
+
+#[test]
+fn register_bytes_for_external_transfers() {
+    let i2c = I2cMock::new(&[]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+
+    ltc.set_frequency(108_000).unwrap();
+    assert_eq!(ltc.register_bytes(), [0x6c, 0x4c]);
+    assert!(!ltc.is_written());
+    ltc.mark_written();
+    assert!(ltc.is_written());
+    ltc.set_output_mode(OutputMode::ClkOnly);
+    assert!(!ltc.is_written());
+
+    done(ltc);
+}
+
+#[test]
+fn dump_decodes_all_fields() {
+    let i2c = I2cMock::new(&[]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+
+    ltc.set_frequency(108_000).unwrap();
+    ltc.set_output_mode(OutputMode::ClkOnly);
+    let dump = ltc.dump();
+    assert_eq!(
+        (dump.oct, dump.dac, dump.cnf, dump.reg),
+        (6, 787, 2, 0x6c4e)
+    );
+
+    done(ltc);
+}
+
+#[test]
+fn getters_decode_each_field() {
+    let i2c = I2cMock::new(&[]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+
+    ltc.set_frequency(1_000_000).unwrap();
+    ltc.set_output_mode(OutputMode::ClkInvOnly);
+    assert_eq!(ltc.get_oct(), 9);
+    assert_eq!(ltc.get_dac(), 959);
+    assert_eq!(ltc.get_cnf(), 1);
+    assert_eq!(ltc.register().bits(), ltc.get_reg());
+
+    done(ltc);
+}
+
+#[test]
+fn standard_frequency_keeps_output_mode() {
+    let i2c = I2cMock::new(&[I2cTransaction::write(ADDR_LOW, vec![0xd9, 0xd6])]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+
+    ltc.set_output_mode(OutputMode::ClkOnly);
+    ltc690x::presets::MHZ_12_288.select(&mut ltc).unwrap();
+    assert_eq!(ltc.requested_frequency(), 12_288_000);
+
+    done(ltc);
+}
+
+#[test]
+fn setters_leave_pending_changes_until_written() {
+    let i2c = I2cMock::new(&[
+        I2cTransaction::write(ADDR_LOW, vec![0x6c, 0x4c]),
+        I2cTransaction::write(ADDR_LOW, vec![0x6c, 0x4e]),
+    ]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+
+    assert!(ltc.has_pending_changes());
+    ltc.set_frequency(108_000).unwrap();
+    ltc.write_out().unwrap();
+    assert!(!ltc.has_pending_changes());
+
+    ltc.set_output_mode(OutputMode::ClkInvOnly);
+    assert!(ltc.has_pending_changes());
+    // restoring the written configuration leaves nothing to write
+    ltc.set_output_mode(OutputMode::BothOutputs);
+    assert!(!ltc.has_pending_changes());
+    ltc.set_output_mode(OutputMode::ClkOnly);
+    ltc.write_out().unwrap();
+    assert!(!ltc.has_pending_changes());
+
+    done(ltc);
+}
+
+#[test]
+fn address_can_be_changed_at_runtime() {
+    let i2c = I2cMock::new(&[
+        I2cTransaction::write(ADDR_LOW, vec![0x6c, 0x4c]),
+        I2cTransaction::write(ADDR_HIGH, vec![0x6c, 0x4c]),
+    ]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+    assert_eq!(ltc.address(), Address::AddressLow);
+
+    ltc.set_frequency(108_000).unwrap();
+    ltc.write_out().unwrap();
+    ltc.set_address(Address::AddressHigh);
+    assert_eq!(ltc.address(), Address::AddressHigh);
+    // the device at the other address hasn't seen the configuration yet
+    assert!(ltc.has_pending_changes());
+    ltc.write_out().unwrap();
+    assert!(!ltc.has_pending_changes());
+
+    done(ltc);
+}
+
+#[test]
+fn address_forms() {
+    assert_eq!(Address::AddressLow.as_7bit(), ADDR_LOW);
+    assert_eq!(Address::AddressHigh.as_7bit(), ADDR_HIGH);
+    assert_eq!(u8::from(Address::AddressLow), ADDR_LOW);
+    assert_eq!(Address::AddressLow.as_8bit_write(), 0x2e);
+    assert_eq!(Address::AddressLow.as_8bit_read(), 0x2f);
+    assert_eq!(Address::AddressHigh.as_8bit_write(), 0x2c);
+    assert_eq!(Address::AddressHigh.as_8bit_read(), 0x2d);
+}
+
+#[test]
+fn enable_output_for_strobes_the_pin() {
+    let i2c = I2cMock::new(&[]);
+    let pin = PinMock::new(&[
+        PinTransaction::set(PinState::High),
+        PinTransaction::set(PinState::Low),
+        PinTransaction::set(PinState::Low),
+    ]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin);
+
+    ltc.enable_output_for(1_000, &mut MockNoop::new()).unwrap();
+
+    done(ltc);
+}
+
+#[test]
+fn burst_enables_the_outputs_for_the_cycles() {
+    struct Recorded(Vec<u32>);
+
+    impl embedded_hal::blocking::delay::DelayUs<u32> for Recorded {
+        fn delay_us(&mut self, us: u32) {
+            self.0.push(us);
+        }
+    }
+
+    let i2c = I2cMock::new(&[I2cTransaction::write(ADDR_LOW, vec![0x9e, 0xfc])]);
+    let pin = PinMock::new(&[
+        PinTransaction::set(PinState::High),
+        PinTransaction::set(PinState::Low),
+        PinTransaction::set(PinState::Low),
+    ]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin);
+    let mut delay = Recorded(Vec::new());
+    ltc.configure(1_000_000, OutputMode::BothOutputs).unwrap();
+
+    // 1 MHz nominal generates slightly off, which rounds back to the same microseconds
+    assert_eq!(ltc.burst(250, &mut delay), Ok(250));
+    assert_eq!(delay.0, [250]);
+
+    done(ltc);
+}
+
+#[test]
+fn gated_writes_disable_the_outputs_around_the_transaction() {
+    let i2c = I2cMock::new(&[
+        I2cTransaction::write(ADDR_LOW, vec![0x9e, 0xfc]),
+        I2cTransaction::write(ADDR_LOW, vec![0x9e, 0xfe])
+            .with_error(MockError::Io(std::io::ErrorKind::Other)),
+        I2cTransaction::write(ADDR_LOW, vec![0x9e, 0xfd]),
+    ]);
+    let pin = StatefulPin::new(&[
+        PinTransaction::set(PinState::High),
+        PinTransaction::set(PinState::Low),
+        PinTransaction::set(PinState::High),
+        // a failed write leaves the old frequency, which is enabled again
+        PinTransaction::set(PinState::Low),
+        PinTransaction::set(PinState::High),
+        PinTransaction::set(PinState::Low),
+    ]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin).with_delay(MockNoop::new());
+    ltc.gate_writes(Some(ltc690x::WAKE_SETTLING_US));
+    ltc.enable_output().unwrap();
+
+    ltc.set_frequency(1_000_000).unwrap();
+    ltc.write_out().unwrap();
+    ltc.set_output_mode(OutputMode::ClkOnly);
+    assert!(matches!(ltc.write_out(), Err(Error::I2c(_))));
+
+    ltc.gate_writes(None);
+    ltc.set_output_mode(OutputMode::ClkInvOnly);
+    ltc.write_out().unwrap();
+
+    let (ltc, _) = ltc.into_parts();
+    done_stateful(ltc);
+}
+
+#[test]
+fn gated_write_leaves_disabled_outputs_disabled() {
+    let i2c = I2cMock::new(&[I2cTransaction::write(ADDR_LOW, vec![0x9e, 0xfc])]);
+    // only the pin going low when freed
+    let pin = StatefulPin::new(&[PinTransaction::set(PinState::Low)]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin).with_delay(MockNoop::new());
+    ltc.gate_writes(Some(ltc690x::WAKE_SETTLING_US));
+
+    ltc.set_frequency(1_000_000).unwrap();
+    ltc.write_out().unwrap();
+    assert_eq!(ltc.is_output_enabled(), Ok(false));
+
+    let (ltc, _) = ltc.into_parts();
+    done_stateful(ltc);
+}
+
+#[test]
+fn refresh_rewrites_the_register() {
+    let i2c = I2cMock::new(&[
+        I2cTransaction::write(ADDR_LOW, vec![0x6c, 0x4c]),
+        I2cTransaction::write(ADDR_LOW, vec![0x6c, 0x4c]),
+        I2cTransaction::write(ADDR_LOW, vec![0x6c, 0x4c]),
+    ]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+
+    ltc.set_frequency(108_000).unwrap();
+    ltc.write_out().unwrap();
+    assert_eq!(ltc.refresh_count(), 0);
+    // nothing changed, the register is written anyway
+    ltc.refresh().unwrap();
+    ltc.refresh().unwrap();
+    assert_eq!(ltc.refresh_count(), 2);
+
+    done(ltc);
+}
+
+#[test]
+fn write_only_bus_is_enough() {
+    /// bus without read support, as some bridges provide
+    struct WriteOnly(Vec<Vec<u8>>);
+
+    impl embedded_hal::blocking::i2c::Write for WriteOnly {
+        type Error = ();
+
+        fn write(&mut self, _address: u8, bytes: &[u8]) -> Result<(), ()> {
+            self.0.push(bytes.to_vec());
+            Ok(())
+        }
+    }
+
+    let mut ltc = LTC6904::new(WriteOnly(Vec::new()), Address::AddressLow, pin_freed());
+    ltc.program_frequency(108_000).unwrap();
+    let (i2c, mut pin) = ltc.free().unwrap();
+    assert_eq!(i2c.0, vec![vec![0x6c, 0x4c]]);
+    pin.done();
+}
+
+#[test]
+fn fractional_frequencies_resolve_below_1_hz() {
+    use ltc690x::Frequency;
+
+    let i2c = I2cMock::new(&[]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+
+    // the DAC steps by about 0.5 Hz at the bottom of the range
+    let f = Frequency::from_millihertz(1_040_500);
+    ltc.set_frequency_precise(f).unwrap();
+    assert_eq!(ltc.get_dac(), 3);
+    assert_eq!(ltc.requested_frequency_precise(), f);
+    assert_eq!(ltc.requested_frequency(), 1_041);
+    ltc.set_frequency(1_040).unwrap();
+    assert_eq!(ltc.get_dac(), 2);
+    assert_eq!(
+        ltc.requested_frequency_precise(),
+        Frequency::from_hertz(1_040)
+    );
+
+    done(ltc);
+}
+
+#[test]
+fn frequency_units() {
+    use ltc690x::Frequency;
+
+    assert_eq!(
+        Frequency::from_megahertz(12),
+        Frequency::from_kilohertz(12_000)
+    );
+    assert_eq!(Frequency::from_kilohertz(108), Frequency::from(108_000));
+    assert_eq!(Frequency::from_millihertz(1_499).hz(), 1);
+    assert_eq!(Frequency::from_millihertz(1_500).hz(), 2);
+    assert_eq!(Frequency::from_millihertz(u64::MAX).hz(), u32::MAX);
+}
+
+#[test]
+fn with_initial_writes_right_away() {
+    // 1 MHz: OCT = 9, DAC = 959, CLK only
+    let i2c = I2cMock::new(&[I2cTransaction::write(ADDR_LOW, vec![0x9e, 0xfe])]);
+    let ltc = LTC6904::with_initial(
+        i2c,
+        Address::AddressLow,
+        pin_freed(),
+        1_000_000,
+        OutputMode::ClkOnly,
+    )
+    .unwrap();
+    assert!(!ltc.has_pending_changes());
+    assert_eq!(ltc.requested_frequency(), 1_000_000);
+    done(ltc);
+
+    let result = LTC6904::with_initial(
+        I2cMock::new(&[]),
+        Address::AddressLow,
+        pin_freed(),
+        100,
+        OutputMode::ClkOnly,
+    );
+    assert!(matches!(
+        result,
+        Err(Error::Frequency(FrequencyError::TooLow))
+    ));
+}
+
+#[test]
+fn shutdown_powers_down_and_releases() {
+    let i2c = I2cMock::new(&[I2cTransaction::write(ADDR_LOW, vec![0x00, 0x03])]);
+    let ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+    let (mut i2c, mut pin) = ltc.shutdown().ok().unwrap();
+    i2c.done();
+    pin.done();
+
+    let i2c = I2cMock::new(&[
+        I2cTransaction::write(ADDR_LOW, vec![0x00, 0x03])
+            .with_error(MockError::Io(std::io::ErrorKind::Other)),
+        I2cTransaction::write(ADDR_LOW, vec![0x00, 0x03]),
+    ]);
+    let pin = PinMock::new(&[
+        PinTransaction::set(PinState::Low),
+        PinTransaction::set(PinState::Low),
+    ]);
+    let ltc = LTC6904::new(i2c, Address::AddressLow, pin);
+    let ltc = match ltc.shutdown() {
+        Err((ltc, Error::I2c(_))) => ltc,
+        _ => panic!("expected the I2C error"),
+    };
+    assert!(ltc.has_pending_changes());
+    let (mut i2c, mut pin) = ltc.shutdown().ok().unwrap();
+    i2c.done();
+    pin.done();
+}
+
+#[test]
+fn config_delta_only_writes_changes() {
+    // 1 MHz: OCT = 9, DAC = 959, then CLK only
+    let i2c = I2cMock::new(&[
+        I2cTransaction::write(ADDR_LOW, vec![0x9e, 0xfc]),
+        I2cTransaction::write(ADDR_LOW, vec![0x9e, 0xfe]),
+    ]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+
+    let first = Config {
+        frequency: 1_000_000,
+        ..Config::POWER_ON
+    };
+    let delta = ltc.config().diff(&first);
+    assert_eq!(delta.output_mode, None);
+    assert!(ltc.apply_delta(&delta).unwrap());
+    assert_eq!(ltc.config(), first);
+
+    assert!(!ltc.apply_delta(&ltc.config().diff(&first)).unwrap());
+
+    let second = Config {
+        output_mode: OutputMode::ClkOnly,
+        ..first
+    };
+    let delta = first.diff(&second);
+    assert_eq!(delta.frequency, None);
+    assert_eq!(first.with_delta(&delta), second);
+    assert!(ltc.apply_delta(&delta).unwrap());
+    done(ltc);
+}
+
+#[cfg(feature = "readback")]
+#[test]
+fn detect_without_a_device() {
+    let mut i2c = I2cMock::new(&[
+        I2cTransaction::read(ADDR_LOW, vec![0, 0])
+            .with_error(MockError::Io(std::io::ErrorKind::Other)),
+        I2cTransaction::read(ADDR_HIGH, vec![0, 0])
+            .with_error(MockError::Io(std::io::ErrorKind::Other)),
+    ]);
+    assert_eq!(ltc690x::detect(&mut i2c), None);
+    i2c.done();
+}
+
+#[test]
+fn set_frequency_checked_rejects_edge_codes() {
+    let mut ltc = LTC6904::new(I2cMock::new(&[]), Address::AddressLow, pin_freed());
+    let reg = ltc.set_frequency(108_000).unwrap();
+    assert_eq!(ltc.set_frequency_checked(108_000), Ok(reg));
+
+    // the bottom of octave 0 is DAC 0, the top of octave 9 DAC 1023
+    for f in [1_039, 1_063_000] {
+        assert_eq!(ltc.set_frequency_checked(f), Err(FrequencyError::EdgeCode));
+        assert_eq!(ltc.get_reg(), reg);
+        assert!(ltc.set_frequency(f).is_ok());
+        ltc.set_frequency(108_000).unwrap();
+    }
+    assert_eq!(ltc.requested_frequency(), 108_000);
+    done(ltc);
+}
+
+#[test]
+fn failed_hop_keeps_the_center_cached() {
+    use ltc690x::hopping::hop_pattern;
+
+    let hops: Vec<u16> = hop_pattern(1_000_000, 20_000).unwrap().take(2).collect();
+    let i2c = I2cMock::new(&[
+        I2cTransaction::write(ADDR_LOW, hops[0].to_be_bytes().to_vec()),
+        I2cTransaction::write(ADDR_LOW, hops[1].to_be_bytes().to_vec())
+            .with_error(MockError::Io(std::io::ErrorKind::Other)),
+    ]);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, pin_freed());
+
+    assert!(matches!(
+        ltc.hop(1_000_000, 20_000, 100, &mut MockNoop::new()),
+        Err(Error::I2c(_))
+    ));
+    assert_eq!(
+        ltc.register().bits(),
+        ltc690x::freq_to_reg(1_000_000).unwrap()
+    );
+    assert!(ltc.has_pending_changes());
+
+    done(ltc);
+}
@@ -0,0 +1,150 @@
+use embedded_hal::{
+    blocking::i2c::{Read, Write, WriteRead},
+    timer::CountDown,
+};
+use ltc690x::{nonblocking::NbWrite, Address, Error, LTC6904};
+
+/// Bus finishing every transfer after `latency` polls
+#[derive(Default)]
+struct SlowBus {
+    latency: usize,
+    busy: usize,
+    refuse: bool,
+    written: Vec<(u8, [u8; 2])>,
+}
+
+impl NbWrite for SlowBus {
+    type Error = ();
+
+    fn start_write(&mut self, address: u8, bytes: [u8; 2]) -> nb::Result<(), ()> {
+        if self.refuse {
+            return Err(nb::Error::Other(()));
+        }
+        self.written.push((address, bytes));
+        self.busy = self.latency;
+        Ok(())
+    }
+
+    fn poll_write(&mut self) -> nb::Result<(), ()> {
+        if self.busy > 0 {
+            self.busy -= 1;
+            Err(nb::Error::WouldBlock)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Write for SlowBus {
+    type Error = ();
+
+    fn write(&mut self, _: u8, _: &[u8]) -> Result<(), ()> {
+        unimplemented!()
+    }
+}
+
+impl Read for SlowBus {
+    type Error = ();
+
+    fn read(&mut self, _: u8, _: &mut [u8]) -> Result<(), ()> {
+        unimplemented!()
+    }
+}
+
+impl WriteRead for SlowBus {
+    type Error = ();
+
+    fn write_read(&mut self, _: u8, _: &[u8], _: &mut [u8]) -> Result<(), ()> {
+        unimplemented!()
+    }
+}
+
+struct NoPin;
+
+impl embedded_hal::digital::v2::OutputPin for NoPin {
+    type Error = ();
+
+    fn set_low(&mut self) -> Result<(), ()> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), ()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn write_out_nb_starts_once_and_polls() {
+    let bus = SlowBus {
+        latency: 2,
+        ..Default::default()
+    };
+    let mut ltc = LTC6904::new(bus, Address::AddressLow, NoPin);
+    ltc.set_frequency(108_000).unwrap();
+
+    assert_eq!(ltc.write_out_nb(), Err(nb::Error::WouldBlock));
+    assert!(ltc.is_write_pending());
+    assert_eq!(ltc.write_out_nb(), Err(nb::Error::WouldBlock));
+    assert_eq!(ltc.write_out_nb(), Ok(()));
+    assert!(!ltc.is_write_pending());
+    assert!(ltc.is_written());
+
+    let (bus, _) = ltc.free().unwrap();
+    assert_eq!(bus.written, vec![(0x17, [0x6c, 0x4c])]);
+}
+
+#[test]
+fn refused_start_is_counted() {
+    let bus = SlowBus {
+        refuse: true,
+        ..Default::default()
+    };
+    let mut ltc = LTC6904::new(bus, Address::AddressLow, NoPin);
+
+    assert_eq!(ltc.write_out_nb(), Err(nb::Error::Other(())));
+    assert!(!ltc.is_write_pending());
+    assert_eq!(ltc.stats().writes, 0);
+    assert_eq!(ltc.stats().bus_errors, 1);
+    // the next attempt repeats the failed word
+    assert!(ltc.write_out_nb().is_err());
+    assert_eq!(ltc.stats().retries, 1);
+}
+
+/// Timer expiring after `ticks` polls
+struct Ticks(u32);
+
+impl CountDown for Ticks {
+    type Time = u32;
+
+    fn start<T: Into<u32>>(&mut self, count: T) {
+        self.0 = count.into();
+    }
+
+    fn wait(&mut self) -> nb::Result<(), void::Void> {
+        if self.0 == 0 {
+            Ok(())
+        } else {
+            self.0 -= 1;
+            Err(nb::Error::WouldBlock)
+        }
+    }
+}
+
+#[test]
+fn write_out_timeout_gives_up_on_a_stuck_bus() {
+    let bus = SlowBus {
+        latency: 5,
+        ..Default::default()
+    };
+    let mut ltc = LTC6904::new(bus, Address::AddressLow, NoPin);
+    ltc.set_frequency(108_000).unwrap();
+
+    assert_eq!(ltc.write_out_timeout(&mut Ticks(0), 2), Err(Error::Timeout));
+    assert!(ltc.is_write_pending());
+    assert!(!ltc.is_written());
+    assert_eq!(ltc.write_out_timeout(&mut Ticks(0), 10), Ok(()));
+    assert!(ltc.is_written());
+
+    let (bus, _) = ltc.free().unwrap();
+    assert_eq!(bus.written.len(), 1);
+}
@@ -0,0 +1,5 @@
+MEMORY
+{
+    FLASH : ORIGIN = 0x00000000, LENGTH = 1024K
+    RAM : ORIGIN = 0x20000000, LENGTH = 256K
+}
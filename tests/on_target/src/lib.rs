@@ -0,0 +1,33 @@
+//! Board setup shared by the on-target tests
+//!
+//! nRF52840 DK with an LTC6904 on SCL P0.28, SDA P0.29 and OE P1.01, ADR strapped low, as in
+//! `examples/nrf52840.rs`.
+
+#![no_std]
+
+use nrf52840_hal::{
+    gpio::{self, Output, Pin, PushPull},
+    pac,
+    twim::{self, Twim},
+};
+
+use ltc690x::{Address, LTC6904};
+
+pub type Ltc = LTC6904<Twim<pac::TWIM0>, Pin<Output<PushPull>>>;
+
+/// Takes the peripherals and creates the driver, with the outputs disabled
+pub fn setup() -> Ltc {
+    let p = pac::Peripherals::take().unwrap();
+    let p0 = gpio::p0::Parts::new(p.P0);
+    let p1 = gpio::p1::Parts::new(p.P1);
+
+    let scl = p0.p0_28.into_floating_input().degrade();
+    let sda = p0.p0_29.into_floating_input().degrade();
+    let i2c = Twim::new(p.TWIM0, twim::Pins { scl, sda }, twim::Frequency::K100);
+
+    LTC6904::new(
+        i2c,
+        Address::AddressLow,
+        p1.p1_01.into_push_pull_output(gpio::Level::Low).degrade(),
+    )
+}
@@ -0,0 +1,53 @@
+//! Register writes against a real LTC6904, see `src/lib.rs` for the wiring
+
+#![no_std]
+#![no_main]
+
+use defmt_rtt as _;
+use panic_probe as _;
+
+#[defmt_test::tests]
+mod tests {
+    use ltc690x::{OutputMode, Register};
+    use ltc690x_on_target::{setup, Ltc};
+
+    #[init]
+    fn init() -> Ltc {
+        setup()
+    }
+
+    #[test]
+    fn device_acknowledges_its_address(ltc: &mut Ltc) {
+        defmt::assert!(ltc.self_test().passed());
+    }
+
+    #[test]
+    fn programmed_frequency_reads_back(ltc: &mut Ltc) {
+        let programmed = ltc.program_frequency(1_000_000).unwrap();
+        defmt::assert_eq!(ltc.read_back().unwrap().bits(), programmed.reg);
+        defmt::assert!(ltc.verify().unwrap());
+    }
+
+    #[test]
+    fn power_down_reads_back(ltc: &mut Ltc) {
+        ltc.set_output_mode(OutputMode::PowerDown);
+        ltc.write_out().unwrap();
+        defmt::assert_eq!(
+            ltc.read_back().unwrap().output_mode(),
+            OutputMode::PowerDown
+        );
+
+        ltc.set_output_mode(OutputMode::BothOutputs);
+        ltc.write_out().unwrap();
+        defmt::assert!(ltc.verify().unwrap());
+    }
+
+    #[test]
+    fn every_octave_reads_back(ltc: &mut Ltc) {
+        for oct in 0..=ltc690x::consts::OCT_MAX {
+            let reg = Register::from_fields(oct, 512, 0).bits();
+            ltc.write_reg(reg).unwrap();
+            defmt::assert_eq!(ltc.read_back().unwrap().bits(), reg);
+        }
+    }
+}
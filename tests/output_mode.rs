@@ -0,0 +1,95 @@
+use std::convert::TryFrom;
+
+use ltc690x::{InvalidOutputMode, OutputMode};
+
+#[test]
+fn output_mode_conversion_rejects_invalid_values() {
+    assert_eq!(OutputMode::try_from(2u16), Ok(OutputMode::ClkOnly));
+    assert_eq!(OutputMode::try_from(3u8), Ok(OutputMode::PowerDown));
+    assert_eq!(OutputMode::try_from(4u16), Err(InvalidOutputMode(4)));
+    assert_eq!(OutputMode::try_from(0xffu8), Err(InvalidOutputMode(0xff)));
+}
+
+#[test]
+#[allow(deprecated)]
+fn defaults_are_the_power_on_state() {
+    assert_eq!(u16::from(OutputMode::default()), 0);
+    assert_eq!(u16::from(ltc690x::OutputSettings::default()), 0);
+}
+
+#[test]
+fn plain_enums_work_as_map_keys() {
+    use std::collections::{BTreeSet, HashMap};
+
+    use ltc690x::{Address, OePolarity};
+
+    let mut boards = HashMap::new();
+    boards.insert(Address::AddressLow, OePolarity::ActiveHigh);
+    boards.insert(Address::AddressHigh, OePolarity::ActiveLow);
+    assert_eq!(boards[&Address::AddressHigh], OePolarity::ActiveLow);
+
+    let modes: BTreeSet<_> = [OutputMode::PowerDown, OutputMode::BothOutputs]
+        .iter()
+        .copied()
+        .collect();
+    assert_eq!(modes.iter().next(), Some(&OutputMode::BothOutputs));
+}
+
+#[test]
+fn next_cycles_outputs_and_skips_power_down_unless_asked() {
+    let mut mode = OutputMode::ClkOnly;
+    let mut seen = vec![];
+    for _ in 0..3 {
+        mode = mode.next(false);
+        seen.push(mode);
+    }
+    assert_eq!(
+        seen,
+        [
+            OutputMode::ClkInvOnly,
+            OutputMode::BothOutputs,
+            OutputMode::ClkOnly
+        ]
+    );
+    assert_eq!(OutputMode::BothOutputs.next(true), OutputMode::PowerDown);
+    assert_eq!(OutputMode::PowerDown.next(false), OutputMode::ClkOnly);
+    assert_eq!(OutputMode::PowerDown.next(true), OutputMode::ClkOnly);
+}
+
+#[test]
+#[allow(deprecated)]
+fn raw_values_convert_both_ways() {
+    use ltc690x::{Address, InvalidAddress, OutputSettings};
+
+    assert_eq!(OutputMode::ClkOnly.bits(), 2);
+    assert_eq!(u8::from(OutputMode::PowerDown), 3);
+    assert_eq!(OutputSettings::ClkPos.bits(), 2);
+    assert_eq!(u8::from(OutputSettings::ClkBoth), 1);
+
+    for addr in [Address::AddressLow, Address::AddressHigh] {
+        assert_eq!(addr.as_u8(), u8::from(addr));
+        assert_eq!(Address::try_from(addr.as_u8()), Ok(addr));
+    }
+    assert_eq!(Address::try_from(0x2e), Err(InvalidAddress(0x2e)));
+}
+
+#[cfg(feature = "parse")]
+#[test]
+fn output_modes_parse_from_their_names() {
+    use ltc690x::parse::ParseOutputModeError;
+
+    for mode in [
+        OutputMode::BothOutputs,
+        OutputMode::ClkOnly,
+        OutputMode::ClkInvOnly,
+        OutputMode::PowerDown,
+    ] {
+        assert_eq!(mode.name().parse(), Ok(mode));
+    }
+    assert_eq!(
+        OutputMode::try_from(" CLKINV\n"),
+        Ok(OutputMode::ClkInvOnly)
+    );
+    assert_eq!("clk_inv".parse::<OutputMode>(), Err(ParseOutputModeError));
+    assert_eq!("".parse::<OutputMode>(), Err(ParseOutputModeError));
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "sim")]
+
+use embedded_hal_mock::{
+    delay::MockNoop,
+    pin::{Mock, State, Transaction},
+    MockError,
+};
+use ltc690x::{power::RailError, sim::Ltc6904Sim, Address, OutputMode, LTC6904};
+
+#[test]
+fn rail_up_writes_the_configuration_with_the_outputs_disabled() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let rail = Mock::new(&[
+        Transaction::set(State::High),
+        Transaction::set(State::Low),
+        Transaction::set(State::High),
+    ]);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin()).with_power_rail(rail);
+    ltc.configure(1_000_000, OutputMode::ClkOnly).unwrap();
+    ltc.enable_output().unwrap();
+    assert!(!ltc.is_rail_up());
+
+    ltc.power_rail_up(&mut MockNoop::new()).unwrap();
+    assert!(ltc.is_rail_up());
+    assert!(!sim.is_output_enabled());
+    assert_eq!(sim.register(), ltc.get_reg());
+
+    ltc.enable_output().unwrap();
+    ltc.power_rail_down().unwrap();
+    assert!(!ltc.is_rail_up());
+    assert!(!sim.is_output_enabled());
+    // the device forgot its configuration, the same register is written again
+    assert!(!ltc.is_written());
+    ltc.power_rail_up(&mut MockNoop::new()).unwrap();
+    assert_eq!(sim.writes().len(), 3);
+    assert_eq!(sim.writes()[1], sim.writes()[2]);
+
+    let (_, mut rail) = ltc.into_parts();
+    rail.done();
+}
+
+#[test]
+fn rail_pin_error_skips_the_write() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let error = MockError::Io(std::io::ErrorKind::NotConnected);
+    let rail = Mock::new(&[Transaction::set(State::High).with_error(error.clone())]);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin()).with_power_rail(rail);
+
+    assert_eq!(
+        ltc.power_rail_up(&mut MockNoop::new()),
+        Err(RailError::Rail(error))
+    );
+    assert!(!ltc.is_rail_up());
+    assert!(sim.writes().is_empty());
+
+    let (_, mut rail) = ltc.into_parts();
+    rail.done();
+}
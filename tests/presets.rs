@@ -0,0 +1,51 @@
+use ltc690x::{error_ppm, freq_to_reg, presets::STANDARD_FREQUENCIES};
+
+#[test]
+fn standard_frequencies_match_calculation() {
+    for s in STANDARD_FREQUENCIES.iter() {
+        assert_eq!(freq_to_reg(s.frequency), Ok(s.reg), "{}", s.frequency);
+        assert_eq!(error_ppm(s.frequency), Ok(s.error_ppm), "{}", s.frequency);
+    }
+    assert!(STANDARD_FREQUENCIES
+        .windows(2)
+        .all(|w| w[0].frequency < w[1].frequency));
+}
+
+#[cfg(all(feature = "heapless", feature = "sim"))]
+#[test]
+fn named_presets_are_selected_by_name() {
+    use ltc690x::{
+        presets::{NamedPresets, PresetError},
+        sim::Ltc6904Sim,
+        Address, FrequencyError, OutputMode, LTC6904,
+    };
+
+    let mut presets = NamedPresets::<2>::new();
+    presets.add_preset("mclk", 12_288_000).unwrap();
+    presets.add_preset("uart", 1_843_200).unwrap();
+    assert_eq!(
+        presets.add_preset("spare", 8_000_000),
+        Err(PresetError::Full)
+    );
+    assert_eq!(
+        presets.add_preset("mclk", 100),
+        Err(PresetError::Frequency(FrequencyError::TooLow))
+    );
+    presets.add_preset("mclk", 24_576_000).unwrap();
+    assert_eq!(presets.frequency("mclk"), Some(24_576_000));
+    assert!(presets.remove_preset("uart"));
+    assert_eq!(
+        presets.add_preset("a name much too long", 8_000_000),
+        Err(PresetError::NameTooLong)
+    );
+    assert_eq!(presets.iter().collect::<Vec<_>>(), [("mclk", 24_576_000)]);
+
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    ltc.set_output_mode(OutputMode::ClkOnly);
+    assert!(!presets.select_preset(&mut ltc, "uart").unwrap());
+    assert!(sim.writes().is_empty());
+    assert!(presets.select_preset(&mut ltc, "mclk").unwrap());
+    assert_eq!(sim.register(), freq_to_reg(24_576_000).unwrap() | 2);
+    assert_eq!(ltc.requested_frequency(), 24_576_000);
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "sim")]
+
+use embedded_hal_mock::delay::MockNoop;
+use ltc690x::{
+    freq_to_reg,
+    profile::{Profile, Step},
+    sim::Ltc6904Sim,
+    Address, Error, FrequencyError, OutputMode, LTC6904,
+};
+
+const STEPS: [Step; 3] = [
+    Step::new(10_000, 1_000_000, OutputMode::BothOutputs),
+    Step::new(5_000, 2_000_000, OutputMode::ClkOnly),
+    Step::new(1_000, 1_039, OutputMode::PowerDown),
+];
+
+#[test]
+fn play_programs_every_step() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+
+    ltc.play(&Profile::new(&STEPS), &mut MockNoop::new())
+        .unwrap();
+    let expected: Vec<_> = STEPS
+        .iter()
+        .map(|s| freq_to_reg(s.frequency).unwrap() | s.output_mode as u16)
+        .collect();
+    assert_eq!(sim.writes(), expected);
+}
+
+#[test]
+fn invalid_profile_isnt_started() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+
+    let steps = [
+        Step::new(10_000, 1_000_000, OutputMode::BothOutputs),
+        Step::new(10_000, 100, OutputMode::BothOutputs),
+    ];
+    assert!(ltc
+        .play(&Profile::new(&steps), &mut MockNoop::new())
+        .is_err());
+    assert!(sim.writes().is_empty());
+}
+
+#[test]
+fn profile_exceeding_the_error_limit_isnt_started() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin()).with_max_error_ppm(10);
+
+    // an achievable frequency is within the limit, one between two codes misses it
+    let exact = ltc690x::reg_to_freq(freq_to_reg(1_000_000).unwrap());
+    assert!(ltc690x::error_ppm(exact).unwrap().unsigned_abs() <= 10);
+    let off = (1_000_000..1_010_000)
+        .find(|f| ltc690x::error_ppm(*f).unwrap().unsigned_abs() > 10)
+        .unwrap();
+    let steps = [
+        Step::new(10_000, exact, OutputMode::BothOutputs),
+        Step::new(10_000, off, OutputMode::BothOutputs),
+    ];
+    assert_eq!(
+        ltc.play(&Profile::new(&steps), &mut MockNoop::new()),
+        Err(Error::Frequency(FrequencyError::ToleranceExceeded))
+    );
+    assert!(sim.writes().is_empty());
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn play_async_programs_every_step() {
+    use std::{
+        future::Future,
+        pin::pin,
+        sync::Arc,
+        task::{Context, Poll, Wake},
+    };
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    struct NoopDelay;
+
+    impl embedded_hal_async::delay::DelayNs for NoopDelay {
+        async fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+
+    let profile = Profile::new(&STEPS);
+    let mut delay = NoopDelay;
+    let mut play = pin!(ltc.play_async(&profile, &mut delay));
+    let waker = Arc::new(NoopWaker).into();
+    assert!(matches!(
+        play.as_mut().poll(&mut Context::from_waker(&waker)),
+        Poll::Ready(Ok(()))
+    ));
+    assert_eq!(sim.writes().len(), STEPS.len());
+}
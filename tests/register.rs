@@ -0,0 +1,67 @@
+use ltc690x::{OutputMode, Register};
+
+#[test]
+fn fields_are_packed_in_place() {
+    let reg = Register::default()
+        .with_oct(6)
+        .with_dac(787)
+        .with_output_mode(OutputMode::ClkOnly);
+    assert_eq!(reg.bits(), 0x6c4e);
+    assert_eq!((reg.oct(), reg.dac(), reg.cnf()), (6, 787, 2));
+    assert_eq!(reg.output_mode(), OutputMode::ClkOnly);
+    assert_eq!(reg.to_be_bytes(), [0x6c, 0x4e]);
+    assert_eq!(Register::from_be_bytes([0x6c, 0x4e]), reg);
+}
+
+#[test]
+fn setters_only_touch_their_field() {
+    let full = Register::new(0xffff);
+    assert_eq!(full.with_oct(0).bits(), 0x0fff);
+    assert_eq!(full.with_dac(0).bits(), 0xf003);
+    assert_eq!(full.with_cnf(0).bits(), 0xfffc);
+    // values too wide for the field are truncated
+    assert_eq!(Register::default().with_dac(0x7ff).dac(), 0x3ff);
+    assert_eq!(Register::default().with_cnf(7).bits(), 0b11);
+}
+
+#[test]
+fn formats_as_hex() {
+    let reg = Register::new(0x9efc);
+    assert_eq!(format!("{:#06x}", reg), "0x9efc");
+    assert_eq!(format!("{:X}", reg), "9EFC");
+    assert_eq!(u16::from(reg), 0x9efc);
+}
+
+#[test]
+fn every_word_round_trips_through_its_fields() {
+    for bits in 0..=u16::MAX {
+        let reg = Register::new(bits);
+        assert!(reg.oct() < 16 && reg.dac() < 1024 && reg.cnf() < 4);
+        assert_eq!(Register::from_fields(reg.oct(), reg.dac(), reg.cnf()), reg);
+        assert_eq!(Register::from_be_bytes(reg.to_be_bytes()), reg);
+        assert_eq!(reg.with_output_mode(reg.output_mode()), reg);
+    }
+}
+
+#[test]
+fn every_field_value_packs_in_place() {
+    for oct in 0..16 {
+        assert_eq!(Register::from_fields(oct, 0, 0).bits(), oct << 12);
+    }
+    for dac in 0..1024 {
+        let reg = Register::from_fields(15, dac, 3);
+        assert_eq!(reg.bits(), 0xf003 | dac << 2);
+        assert_eq!((reg.oct(), reg.dac(), reg.cnf()), (15, dac, 3));
+    }
+    for cnf in 0..4 {
+        assert_eq!(Register::from_fields(0, 0, cnf).bits(), cnf);
+        assert_eq!(Register::from_fields(0, 0, cnf).output_mode() as u16, cnf);
+    }
+}
+
+#[test]
+fn converts_to_and_from_bus_bytes() {
+    let reg = Register::from(0x6c4c);
+    assert_eq!(<[u8; 2]>::from(reg), [0x6c, 0x4c]);
+    assert_eq!(Register::from([0x6c, 0x4c]), reg);
+}
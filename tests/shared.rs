@@ -0,0 +1,27 @@
+#![cfg(all(feature = "critical-section", feature = "sim"))]
+
+use ltc690x::{
+    shared::SharedLtc6904,
+    sim::{Ltc6904Sim, SimI2c, SimOutputEnable},
+    Address, LTC6904,
+};
+
+#[test]
+fn shared_driver_is_usable_through_lock() {
+    let shared: SharedLtc6904<SimI2c, SimOutputEnable> = SharedLtc6904::new();
+    assert_eq!(shared.lock(|ltc| ltc.get_reg()), None);
+
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    assert!(shared.init(ltc).is_none());
+
+    shared
+        .lock(|ltc| ltc.program_frequency(108_000).map(|_| ()))
+        .unwrap()
+        .unwrap();
+    shared.lock(|ltc| ltc.enable_output()).unwrap().unwrap();
+    assert!(sim.output_frequency().is_some());
+
+    assert!(shared.take().is_some());
+    assert_eq!(shared.lock(|ltc| ltc.get_reg()), None);
+}
@@ -0,0 +1,513 @@
+#![cfg(feature = "sim")]
+
+use ltc690x::{sim::Ltc6904Sim, Address, OePolarity, OeState, OutputMode, LTC6904};
+
+#[test]
+fn status_reflects_pin_and_configuration() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+
+    assert!(!ltc.is_output_enabled().unwrap());
+    ltc.enable_output().unwrap();
+    assert!(ltc.is_output_enabled().unwrap());
+
+    assert!(!ltc.is_powered_down());
+    ltc.set_output_mode(OutputMode::PowerDown);
+    assert!(ltc.is_powered_down());
+    assert!(!sim.is_powered_down());
+    ltc.write_out().unwrap();
+    assert!(sim.is_powered_down());
+    assert_eq!(sim.output_frequency(), None);
+}
+
+#[test]
+fn ensure_output_only_changes_pin_when_needed() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+
+    assert_eq!(ltc.oe_state().unwrap(), OeState::Disabled);
+    assert!(ltc.ensure_output_enabled().unwrap());
+    assert!(!ltc.ensure_output_enabled().unwrap());
+    assert!(sim.is_output_enabled());
+    assert!(ltc.ensure_output_disabled().unwrap());
+    assert!(!ltc.ensure_output_disabled().unwrap());
+    assert_eq!(ltc.oe_state().unwrap(), OeState::Disabled);
+}
+
+#[test]
+fn oe_state_respects_polarity() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new_with_polarity(
+        sim.i2c(),
+        Address::AddressLow,
+        sim.oe_pin(),
+        OePolarity::ActiveLow,
+    );
+
+    ltc.enable_output().unwrap();
+    assert_eq!(ltc.oe_state().unwrap(), OeState::Enabled);
+    // the simulated pin sees the inverted level
+    assert!(!sim.is_output_enabled());
+}
+
+#[test]
+fn programmed_frequency_matches_device() {
+    let sim = Ltc6904Sim::new(Address::AddressHigh);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressHigh, sim.oe_pin());
+
+    ltc.set_frequency(12_288_000).unwrap();
+    ltc.write_out().unwrap();
+
+    assert_eq!(ltc.requested_frequency(), 12_288_000);
+    assert_eq!(ltc.programmed_frequency(), sim.frequency());
+    assert_ne!(ltc.programmed_frequency(), ltc.requested_frequency());
+}
+
+#[test]
+fn split_driver_commits_latest_staged_word() {
+    use ltc690x::split::Staged;
+
+    let staged = Staged::new();
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    let (mut planner, mut writer) = ltc.split(&staged);
+
+    assert!(!writer.commit().unwrap());
+    planner.set_frequency(1_000_000).unwrap();
+    planner.set_output_mode(OutputMode::ClkOnly);
+    assert!(staged.is_pending());
+    assert!(writer.commit().unwrap());
+    assert!(!writer.commit().unwrap());
+    assert_eq!(sim.writes(), vec![0x9efe]);
+
+    writer.enable_output().unwrap();
+    assert!(sim.output_frequency().is_some());
+    let ltc = writer.join(planner);
+    assert_eq!(ltc.get_reg(), 0x9efe);
+    assert_eq!(ltc.requested_frequency(), 1_000_000);
+}
+
+#[test]
+fn staged_frequency_leaves_cache_until_commit() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    ltc.set_frequency(108_000).unwrap();
+
+    assert!(!ltc.commit().unwrap());
+    ltc.stage_frequency(1_000_000).unwrap();
+    assert!(ltc.has_staged());
+    assert_eq!(ltc.requested_frequency(), 108_000);
+    assert!(sim.writes().is_empty());
+
+    // the output mode is taken at commit time
+    ltc.set_output_mode(OutputMode::ClkOnly);
+    assert!(ltc.commit().unwrap());
+    assert!(!ltc.has_staged());
+    assert_eq!(sim.writes(), vec![0x9efe]);
+    assert_eq!(ltc.requested_frequency(), 1_000_000);
+
+    assert!(ltc.stage_frequency(100).is_err());
+    ltc.stage_frequency(2_000_000).unwrap();
+    ltc.discard_staged();
+    assert!(!ltc.commit().unwrap());
+}
+
+#[test]
+fn driver_is_usable_as_dyn_oscillator() {
+    use ltc690x::{FrequencyError, Oscillator, OscillatorError};
+
+    struct Instrument<'a> {
+        clock: &'a mut dyn Oscillator,
+    }
+
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    let instrument = Instrument { clock: &mut ltc };
+
+    let programmed = instrument.clock.program(1_000_000).unwrap();
+    instrument
+        .clock
+        .apply_output_mode(OutputMode::ClkOnly)
+        .unwrap();
+    instrument.clock.set_output_enabled(true).unwrap();
+    assert_eq!(instrument.clock.frequency(), programmed.frequency);
+    assert_eq!(
+        instrument.clock.program(10),
+        Err(OscillatorError::Frequency(FrequencyError::TooLow))
+    );
+    assert_eq!(sim.output_frequency(), Some(programmed.frequency));
+    assert_eq!(sim.output_mode(), OutputMode::ClkOnly);
+}
+
+#[test]
+fn initial_state_matches_power_on() {
+    use ltc690x::Config;
+
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    assert_eq!(ltc.config(), Config::default());
+    assert_eq!(ltc.get_reg(), sim.register());
+    assert_eq!(Config::default().to_reg(), Ok(sim.register()));
+    assert_eq!(ltc.programmed_frequency(), sim.frequency());
+
+    let config = Config {
+        frequency: 1_000_000,
+        ..Default::default()
+    };
+    assert_eq!(ltc.set_config(&config), config.to_reg());
+    assert_eq!(ltc.config(), config);
+}
+
+#[test]
+fn const_config_table_matches_runtime() {
+    use ltc690x::{freq_to_reg, Config};
+
+    static PROFILES: [Config; 3] = [
+        Config::checked(1_039, OutputMode::BothOutputs),
+        Config::checked(1_000_000, OutputMode::ClkOnly),
+        Config::checked(68_000_000, OutputMode::ClkInvOnly),
+    ];
+    const REG: u16 = match PROFILES[1].to_reg() {
+        Ok(reg) => reg,
+        Err(_) => panic!(),
+    };
+
+    assert_eq!(REG, freq_to_reg(1_000_000).unwrap() | 2);
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    for config in &PROFILES {
+        ltc.configure(config.frequency, config.output_mode).unwrap();
+        assert_eq!(Ok(sim.register()), config.to_reg());
+    }
+}
+
+#[test]
+fn tone_powers_down_afterwards() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+
+    ltc.tone(
+        4_000,
+        100_000,
+        &mut embedded_hal_mock::delay::MockNoop::new(),
+    )
+    .unwrap();
+    let writes = sim.writes();
+    assert_eq!(writes.len(), 2);
+    assert_eq!(writes[0] >> 12, 1);
+    assert_eq!(writes[0] & 0b11, OutputMode::BothOutputs as u16);
+    assert!(sim.is_powered_down());
+    assert!(!sim.is_output_enabled());
+}
+
+#[test]
+fn dither_step_only_writes_changed_codes() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    ltc.set_output_mode(OutputMode::ClkOnly);
+
+    let mut dither = ltc.dither(1_000_100).unwrap();
+    let (lo, hi) = dither.codes();
+    // a quarter of the way up keeps the lower code for most periods
+    for _ in 0..64 {
+        ltc.dither_step(&mut dither).unwrap();
+    }
+    let writes = sim.writes();
+    assert!(writes.len() < 64);
+    assert!(writes
+        .iter()
+        .all(|w| *w == lo | OutputMode::ClkOnly as u16 || *w == hi | OutputMode::ClkOnly as u16));
+    assert_eq!(ltc.requested_frequency(), 1_000_100);
+}
+
+#[test]
+fn hop_spreads_around_the_center() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    ltc.set_output_mode(OutputMode::ClkOnly);
+
+    let mut delay = embedded_hal_mock::delay::MockNoop::new();
+    ltc.hop(1_000_000, 20_000, 100, &mut delay).unwrap();
+    let writes = sim.writes();
+    // 8 steps up, 8 down and the center
+    assert_eq!(writes.len(), 17);
+    let freqs: Vec<_> = writes.iter().map(|w| ltc690x::reg_to_freq(*w)).collect();
+    assert!(freqs[0] < 985_000 && freqs[8] > 1_015_000);
+    assert!(freqs[..=8].windows(2).all(|w| w[0] < w[1]));
+    assert!(freqs[8..16].windows(2).all(|w| w[0] > w[1]));
+    assert!(writes
+        .iter()
+        .all(|w| w & 0b11 == OutputMode::ClkOnly as u16));
+    assert_eq!(
+        *writes.last().unwrap() & !0b11,
+        ltc690x::freq_to_reg(1_000_000).unwrap()
+    );
+    assert!(ltc.hop(68_000_000, 20_000, 100, &mut delay).is_err());
+}
+
+#[test]
+fn clock_tree_programs_named_clocks() {
+    use ltc690x::{
+        clock_tree::{Clock, ClockError, ClockTree},
+        FrequencyError, OscillatorError,
+    };
+
+    let codec = Ltc6904Sim::new(Address::AddressLow);
+    let fpga = Ltc6904Sim::new(Address::AddressHigh);
+    let mut codec_ltc = LTC6904::new(codec.i2c(), Address::AddressLow, codec.oe_pin());
+    let mut fpga_ltc = LTC6904::new(fpga.i2c(), Address::AddressHigh, fpga.oe_pin());
+
+    let mut clocks = ClockTree::new([
+        Clock::new("codec_mclk", &mut codec_ltc, 12_288_000),
+        Clock::new("fpga_ref", &mut fpga_ltc, 25_000_000),
+    ]);
+    clocks.apply_all().unwrap();
+    assert_eq!(codec.register(), ltc690x::freq_to_reg(12_288_000).unwrap());
+    assert_eq!(fpga.register(), ltc690x::freq_to_reg(25_000_000).unwrap());
+
+    clocks.retarget("codec_mclk", 11_289_600).unwrap();
+    assert_eq!(clocks.get("codec_mclk").unwrap().target(), 11_289_600);
+    assert_eq!(codec.writes().len(), 2);
+    // a rejected target keeps the previous one
+    assert_eq!(
+        clocks.retarget("fpga_ref", 100),
+        Err(ClockError::Oscillator {
+            name: "fpga_ref",
+            error: OscillatorError::Frequency(FrequencyError::TooLow)
+        })
+    );
+    assert_eq!(clocks.get("fpga_ref").unwrap().target(), 25_000_000);
+    let e = clocks.retarget("fpga_ref", 100).unwrap_err();
+    assert_eq!(e.category(), ltc690x::ErrorCategory::Range);
+    assert_eq!(e.to_string(), "programming clock fpga_ref failed");
+    let source = std::error::Error::source(&e).unwrap();
+    assert_eq!(source.to_string(), "frequency below 1039 Hz");
+    assert_eq!(
+        clocks.retarget("dsp", 1_000_000),
+        Err(ClockError::UnknownClock)
+    );
+}
+
+#[cfg(feature = "readback")]
+#[test]
+fn read_back_and_verify() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+
+    ltc.set_frequency(108_000).unwrap();
+    assert!(!ltc.verify().unwrap());
+    ltc.write_out().unwrap();
+    assert_eq!(ltc.read_back().unwrap(), ltc.register());
+    assert!(ltc.verify().unwrap());
+}
+
+#[cfg(feature = "readback")]
+#[test]
+fn sync_adopts_a_register_written_behind_the_driver() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    let mut other = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+
+    ltc.configure(1_000_000, OutputMode::ClkOnly).unwrap();
+    assert!(ltc.sync().unwrap().is_empty());
+    assert_eq!(ltc.requested_frequency(), 1_000_000);
+
+    other.configure(1_000_000, OutputMode::BothOutputs).unwrap();
+    let delta = ltc.sync().unwrap();
+    assert_eq!(delta.frequency, None);
+    assert_eq!(delta.output_mode, Some(OutputMode::BothOutputs));
+    assert_eq!(ltc.requested_frequency(), 1_000_000);
+
+    other.configure(108_000, OutputMode::BothOutputs).unwrap();
+    let delta = ltc.sync().unwrap();
+    assert_eq!(delta.frequency, Some(sim.frequency()));
+    assert_eq!(delta.output_mode, None);
+    assert_eq!(ltc.requested_frequency(), sim.frequency());
+    assert_eq!(ltc.get_reg(), sim.register());
+    assert!(!ltc.has_pending_changes());
+}
+
+#[cfg(feature = "readback")]
+#[test]
+fn self_test_reads_back_the_written_register() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    ltc.set_frequency(1_000_000).unwrap();
+
+    let report = ltc.self_test_read_back();
+    assert!(report.passed());
+    assert_eq!(report.read_back, Some(Ok(report.written)));
+    assert_eq!(sim.register(), ltc.get_reg());
+    assert!(!ltc.has_pending_changes());
+
+    let mut other = LTC6904::new(sim.i2c(), Address::AddressHigh, sim.oe_pin());
+    let report = other.self_test_read_back();
+    assert!(!report.passed());
+    assert_eq!(report.write_error, Some(ltc690x::sim::SimError::Nack));
+    assert_eq!(report.read_back, None);
+}
+
+#[test]
+fn deep_sleep_and_wake_restore_the_output_mode() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    ltc.configure(1_000_000, OutputMode::ClkOnly).unwrap();
+    ltc.enable_output().unwrap();
+
+    ltc.deep_sleep().unwrap();
+    assert!(ltc.is_asleep());
+    assert!(sim.is_powered_down());
+    assert!(!sim.is_output_enabled());
+    assert_eq!(sim.output_frequency(), None);
+
+    // sleeping twice keeps the mode to restore
+    ltc.deep_sleep().unwrap();
+    ltc.wake(&mut embedded_hal_mock::delay::MockNoop::new())
+        .unwrap();
+    assert!(!ltc.is_asleep());
+    assert_eq!(sim.output_mode(), OutputMode::ClkOnly);
+    assert!(sim.is_output_enabled());
+    assert_eq!(sim.output_frequency(), Some(ltc.programmed_frequency()));
+}
+
+#[test]
+fn on_write_hook_sees_every_write() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    static LAST: AtomicU32 = AtomicU32::new(u32::MAX);
+    static WRITES: AtomicU32 = AtomicU32::new(0);
+    fn mirror(reg: ltc690x::Register) {
+        LAST.store(reg.bits() as u32, Ordering::Relaxed);
+        WRITES.fetch_add(1, Ordering::Relaxed);
+    }
+
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    ltc.set_on_write(Some(mirror));
+
+    ltc.configure(1_000_000, OutputMode::ClkOnly).unwrap();
+    assert_eq!(LAST.load(Ordering::Relaxed), sim.register() as u32);
+    ltc.refresh().unwrap();
+    assert_eq!(WRITES.load(Ordering::Relaxed), 2);
+
+    // failed writes and read-back don't count
+    ltc.set_address(Address::AddressHigh);
+    assert!(ltc.write_out().is_err());
+    ltc.set_address(Address::AddressLow);
+    #[cfg(feature = "readback")]
+    assert!(ltc.verify().unwrap());
+    assert_eq!(WRITES.load(Ordering::Relaxed), 2);
+
+    ltc.set_on_write(None);
+    ltc.refresh().unwrap();
+    assert_eq!(WRITES.load(Ordering::Relaxed), 2);
+}
+
+#[cfg(feature = "readback")]
+#[test]
+fn detect_finds_the_strapped_address() {
+    for addr in [Address::AddressLow, Address::AddressHigh] {
+        let sim = Ltc6904Sim::new(addr);
+        assert_eq!(ltc690x::detect(&mut sim.i2c()), Some(addr));
+        assert!(sim.writes().is_empty());
+    }
+}
+
+#[test]
+fn delayed_driver_runs_timed_operations_without_a_delay_argument() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    let mut ltc = ltc.with_delay(embedded_hal_mock::delay::MockNoop::new());
+
+    ltc.tone(4_000, 1_000).unwrap();
+    assert!(sim.is_powered_down());
+    assert!(!sim.is_output_enabled());
+
+    ltc.set_frequency(1_000_000).unwrap();
+    assert_eq!(ltc.requested_frequency(), 1_000_000);
+    let (ltc, _delay) = ltc.into_parts();
+    assert_eq!(ltc.output_mode(), OutputMode::PowerDown);
+}
+
+#[test]
+fn multiple_of_reports_the_ratio_error() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+
+    let ppm = ltc.set_multiple_of(48_000_000, 1, 4).unwrap();
+    ltc.write_out().unwrap();
+    assert_eq!(ltc.requested_frequency(), 12_000_000);
+    let actual = sim.frequency() as f64 / 12_000_000.0 - 1.0;
+    assert!(
+        (actual * 1e6 - ppm as f64).abs() < 1.0,
+        "{} {}",
+        actual,
+        ppm
+    );
+
+    // 1/3 of 32.768 kHz keeps its fraction
+    ltc.set_multiple_of(32_768, 1, 3).unwrap();
+    assert_eq!(ltc.requested_frequency_precise().millihertz(), 10_922_667);
+    assert!(ltc.set_multiple_of(48_000_000, 2, 1).is_err());
+}
+
+#[test]
+fn cycle_output_writes_the_next_mode() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    ltc.set_output_mode(OutputMode::ClkOnly);
+
+    assert_eq!(ltc.next_output_mode(false), OutputMode::ClkInvOnly);
+    assert_eq!(ltc.cycle_output(false).unwrap(), OutputMode::ClkInvOnly);
+    assert_eq!(sim.output_mode(), OutputMode::ClkInvOnly);
+    assert_eq!(ltc.cycle_output(true).unwrap(), OutputMode::BothOutputs);
+    assert_eq!(ltc.cycle_output(true).unwrap(), OutputMode::PowerDown);
+    assert!(sim.is_powered_down());
+}
+
+#[test]
+fn split_oe_gates_independently_of_the_driver() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let ltc = LTC6904::new_with_polarity(
+        sim.i2c(),
+        Address::AddressLow,
+        sim.oe_pin(),
+        OePolarity::ActiveLow,
+    );
+    let (mut ltc, mut oe) = ltc.split_oe();
+    assert_eq!(oe.polarity(), OePolarity::ActiveLow);
+
+    ltc.configure(1_000_000, OutputMode::ClkOnly).unwrap();
+    oe.enable().unwrap();
+    assert!(oe.is_enabled().unwrap());
+    // the simulated pin level, low for enabled with an active low OE
+    assert!(!sim.is_output_enabled());
+    // the driver has no say over OE anymore
+    ltc.disable_output().unwrap();
+    assert!(oe.is_enabled().unwrap());
+
+    oe.disable().unwrap();
+    let mut ltc = ltc.join_oe(oe);
+    assert_eq!(ltc.oe_polarity(), OePolarity::ActiveLow);
+    assert_eq!(ltc.requested_frequency(), 1_000_000);
+    ltc.enable_output().unwrap();
+    assert_eq!(ltc.oe_state().unwrap(), OeState::Enabled);
+}
+
+#[test]
+fn infallible_pins_switch_without_a_result() {
+    use ltc690x::oe::InfallibleOe;
+
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let mut ltc = LTC6904::new(sim.i2c(), Address::AddressLow, sim.oe_pin());
+    ltc.output_on();
+    assert!(sim.is_output_enabled());
+    ltc.output_off();
+    assert!(!sim.is_output_enabled());
+
+    let (_ltc, mut oe) = ltc.split_oe();
+    oe.output_on();
+    assert!(sim.is_output_enabled());
+    oe.output_off();
+    assert!(!sim.is_output_enabled());
+}
@@ -0,0 +1,44 @@
+//! Frequencies shared by several consumers
+
+use ltc690x::{
+    freq_to_reg, reg_to_freq, resolution_at,
+    solver::{solve, Conflict, Consumer},
+};
+
+#[test]
+fn solution_satisfies_every_consumer() {
+    let consumers = [
+        Consumer::range(3_612_672, 3_760_128).with_dividers(1, 16),
+        Consumer::range(11_200_000, 12_400_000),
+        Consumer::range(1_000_000, 1_500_000).with_dividers(2, 32),
+    ];
+    let solution = solve(&consumers, 11_059_200).unwrap();
+    assert_eq!(solution.frequency, reg_to_freq(solution.reg));
+    for (c, d) in consumers.iter().zip(solution.dividers.iter()) {
+        let f = solution.frequency / d;
+        assert!(c.min_hz <= f && f <= c.max_hz, "{:?} / {}", c, d);
+    }
+    // the closest solution to the preferred frequency is at the bottom of the codec's range
+    let step = resolution_at(11_200_000).unwrap();
+    assert!(solution.frequency < 11_200_000 + step, "{:?}", solution);
+}
+
+#[test]
+fn preferred_frequency_wins_if_possible() {
+    let consumers = [Consumer::range(1_000_000, 20_000_000)];
+    let solution = solve(&consumers, 12_288_000).unwrap();
+    assert_eq!(solution.reg, freq_to_reg(12_288_000).unwrap());
+    assert_eq!(solution.dividers, [1]);
+}
+
+#[test]
+fn conflict_names_the_first_unsatisfiable_consumer() {
+    let consumers = [
+        Consumer::range(1_000_000, 2_000_000),
+        Consumer::range(2_500_000, 3_000_000).with_dividers(1, 1),
+        Consumer::range(1, 2),
+    ];
+    assert_eq!(solve(&consumers, 1_500_000), Err(Conflict { consumer: 1 }));
+    let out_of_range = [Consumer::range(100, 200)];
+    assert_eq!(solve(&out_of_range, 150), Err(Conflict { consumer: 0 }));
+}
@@ -0,0 +1,62 @@
+use ltc690x::{freq_to_reg, log_sweep, reg_to_freq, sweep, LogSpacing, OutputMode};
+
+#[test]
+fn linear_sweep_up_and_down() {
+    let up: Vec<u32> = sweep(10_000, 10_400, 100)
+        .unwrap()
+        .map(reg_to_freq)
+        .collect();
+    assert_eq!(up.len(), 5);
+    assert!(up.windows(2).all(|w| w[0] < w[1]));
+
+    let down: Vec<u16> = sweep(10_400, 10_000, 100).unwrap().collect();
+    let mut up: Vec<u16> = sweep(10_000, 10_400, 100).unwrap().collect();
+    up.reverse();
+    assert_eq!(down, up);
+}
+
+#[test]
+fn sweep_stops_before_overshooting() {
+    let s = sweep(1_000_000, 1_000_250, 100).unwrap();
+    assert_eq!(s.len(), 3);
+    assert_eq!(s.last(), freq_to_reg(1_000_200).ok());
+}
+
+#[test]
+fn sweep_applies_output_configuration() {
+    assert!(sweep(2_000, 3_000, 250)
+        .unwrap()
+        .with_output(OutputMode::ClkOnly)
+        .all(|reg| reg & 0b11 == OutputMode::ClkOnly as u16));
+}
+
+#[test]
+fn sweep_rejects_out_of_range_end_points() {
+    assert!(sweep(1_000, 2_000, 10).is_err());
+    assert!(sweep(1_000_000, 70_000_000, 10).is_err());
+}
+
+#[test]
+fn log_sweep_per_octave() {
+    let points: Vec<u32> = log_sweep(2_000, 16_000, LogSpacing::PerOctave(1))
+        .unwrap()
+        .map(reg_to_freq)
+        .collect();
+    assert_eq!(points.len(), 4);
+    for (p, f) in points.iter().zip([2_000, 4_000, 8_000, 16_000]) {
+        assert!((*p as i64 - f).abs() <= 2, "{} != {}", p, f);
+    }
+}
+
+#[test]
+fn log_sweep_per_decade_down() {
+    let points: Vec<u32> = log_sweep(10_000_000, 10_000, LogSpacing::PerDecade(10))
+        .unwrap()
+        .map(reg_to_freq)
+        .collect();
+    assert_eq!(points.len(), 31);
+    assert!(points.windows(2).all(|w| w[0] > w[1]));
+    // 10^(1/10) ~ 1.2589
+    let ratio = points[0] as f64 / points[1] as f64;
+    assert!((ratio - 1.2589).abs() < 0.001, "{}", ratio);
+}
@@ -0,0 +1,78 @@
+#![cfg(all(feature = "test-hooks", feature = "sim"))]
+
+use ltc690x::{
+    sim::{Ltc6904Sim, SimError},
+    test_hooks::FaultyI2c,
+    Address, OutputMode, Stats, LTC6904,
+};
+
+#[test]
+fn injected_fault_leaves_the_change_pending() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let i2c = FaultyI2c::new(sim.i2c()).fail_at(2, SimError::Nack);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, sim.oe_pin());
+
+    ltc.set_frequency(1_000_000).unwrap();
+    ltc.write_out().unwrap();
+    let first = sim.register();
+
+    ltc.set_frequency(2_000_000).unwrap();
+    assert_eq!(ltc.write_out(), Err(SimError::Nack));
+    assert!(ltc.has_pending_changes());
+    assert_eq!(sim.register(), first);
+
+    ltc.write_out().unwrap();
+    assert!(!ltc.has_pending_changes());
+    assert_eq!(sim.register(), ltc.get_reg());
+    assert_eq!(sim.writes().len(), 2);
+}
+
+#[test]
+fn periodic_faults_are_counted() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let i2c = FaultyI2c::new(sim.i2c()).fail_every(3, SimError::InvalidLength(0));
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, sim.oe_pin());
+
+    let results: Vec<_> = (0..6).map(|_| ltc.refresh().is_ok()).collect();
+    assert_eq!(results, [true, true, false, true, true, false]);
+    assert_eq!(sim.writes().len(), 4);
+    let (i2c, _) = ltc.free().unwrap();
+    assert_eq!(i2c.calls(), 6);
+}
+
+#[cfg(feature = "readback")]
+#[test]
+fn self_test_reports_a_failed_read_back() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let i2c = FaultyI2c::new(sim.i2c()).fail_at(2, SimError::Nack);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, sim.oe_pin());
+
+    let report = ltc.self_test_read_back();
+    assert_eq!(report.write_error, None);
+    assert_eq!(report.read_back, Some(Err(SimError::Nack)));
+    assert!(!report.passed());
+}
+
+#[test]
+fn stats_count_errors_and_retries() {
+    let sim = Ltc6904Sim::new(Address::AddressLow);
+    let i2c = FaultyI2c::new(sim.i2c()).fail_at(2, SimError::Nack);
+    let mut ltc = LTC6904::new(i2c, Address::AddressLow, sim.oe_pin());
+
+    ltc.set_frequency(1_000_000).unwrap();
+    ltc.write_out().unwrap();
+    ltc.set_frequency(2_000_000).unwrap();
+    assert!(ltc.write_out().is_err());
+    ltc.write_out().unwrap();
+    ltc.set_output_mode(OutputMode::ClkOnly);
+    ltc.write_out().unwrap();
+
+    let stats = ltc.stats();
+    assert_eq!(stats.writes, 3);
+    assert_eq!(stats.bus_errors, 1);
+    assert_eq!(stats.retries, 1);
+    assert_eq!(stats.frequency_changes, 2);
+
+    ltc.reset_stats();
+    assert_eq!(ltc.stats(), Stats::default());
+}
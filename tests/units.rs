@@ -0,0 +1,66 @@
+#![cfg(feature = "uom")]
+
+use std::convert::TryFrom;
+
+use embedded_hal_mock::{
+    i2c::Mock as I2cMock,
+    pin::{Mock as PinMock, State, Transaction},
+};
+use ltc690x::{Address, Frequency, FrequencyError, LTC6904};
+use uom::si::{
+    f64,
+    frequency::{hertz, kilohertz, megahertz},
+};
+
+#[test]
+fn frequencies_convert_both_ways() {
+    let f = Frequency::from_millihertz(1_039_500);
+    let q = f64::Frequency::from(f);
+    assert_eq!(q.get::<hertz>(), 1_039.5);
+    assert_eq!(Frequency::try_from(q), Ok(f));
+    assert_eq!(
+        Frequency::try_from(f64::Frequency::new::<megahertz>(12.288)),
+        Ok(Frequency::from_hertz(12_288_000))
+    );
+}
+
+#[test]
+fn negative_and_nan_quantities_are_rejected() {
+    assert_eq!(
+        Frequency::try_from(f64::Frequency::new::<hertz>(-1.0)),
+        Err(FrequencyError::TooLow)
+    );
+    assert_eq!(
+        Frequency::try_from(f64::Frequency::new::<hertz>(f64::NAN)),
+        Err(FrequencyError::TooLow)
+    );
+    assert_eq!(
+        Frequency::try_from(f64::Frequency::new::<hertz>(1e30)),
+        Err(FrequencyError::TooHigh)
+    );
+}
+
+#[test]
+fn driver_takes_and_reports_quantities() {
+    let pin = PinMock::new(&[Transaction::set(State::Low)]);
+    let mut ltc = LTC6904::new(I2cMock::new(&[]), Address::AddressLow, pin);
+
+    let reg = ltc.set_frequency(108_000).unwrap();
+    assert_eq!(
+        ltc.set_frequency_uom(f64::Frequency::new::<kilohertz>(108.0)),
+        Ok(reg)
+    );
+    assert_eq!(ltc.requested_frequency_uom().get::<hertz>(), 108_000.0);
+    assert_eq!(
+        ltc.programmed_frequency_uom().get::<hertz>(),
+        ltc.programmed_frequency() as f64
+    );
+    assert_eq!(
+        ltc.set_frequency_uom(f64::Frequency::new::<hertz>(10.0)),
+        Err(FrequencyError::TooLow)
+    );
+
+    let (mut i2c, mut pin) = ltc.free().ok().unwrap();
+    i2c.done();
+    pin.done();
+}